@@ -25,6 +25,9 @@ const THUMBNAIL_BOOTSTRAP_LOCATION = `../rust/target/lambda/thumbnail/bootstrap.
 const BINAHBOT_FUNCTION_HANDLER = "binah_bot.bootstrap";
 const THUMBNAIL_FUNCTION_HANDLER = "thumbnail.bootstrap";
 
+// the thumbnail lambda writes rendered cards here, and the bot links to them
+const CARD_DIRECTORY = "card_images";
+
 export interface DiscordStackProps extends StackProps {
     clientId: string;
     emojis: {
@@ -39,6 +42,11 @@ export class DiscordStack extends Stack {
     private readonly apigw: RestApi;
     private readonly imageHostBucket: Bucket;
     private readonly deckRepository: TableV2;
+    private readonly deckHistory: TableV2;
+    private readonly deckIndex: TableV2;
+    private readonly deckVotes: TableV2;
+    private readonly guildCollection: TableV2;
+    private readonly guildConfig: TableV2;
     private readonly interactionTtl: TableV2;
 
     constructor(scope: Construct, id: string, props: DiscordStackProps) {
@@ -49,6 +57,11 @@ export class DiscordStack extends Stack {
         this.apigw = this.createApigw();
         this.imageHostBucket = this.createImageHostBucket();
         this.deckRepository = this.createDeckRepository();
+        this.deckHistory = this.createDeckHistory();
+        this.deckIndex = this.createDeckIndex();
+        this.deckVotes = this.createDeckVotes();
+        this.guildCollection = this.createGuildCollection();
+        this.guildConfig = this.createGuildConfig();
         this.interactionTtl = this.createInteractionTtl();
 
         this.discordAPISecrets.grantRead(this.discordBotLambda);
@@ -70,6 +83,26 @@ export class DiscordStack extends Stack {
             "DECK_REPOSITORY_NAME",
             this.deckRepository.tableName
         );
+        this.discordBotLambda.addEnvironment(
+            "DECK_HISTORY_NAME",
+            this.deckHistory.tableName
+        );
+        this.discordBotLambda.addEnvironment(
+            "DECK_INDEX_NAME",
+            this.deckIndex.tableName
+        );
+        this.discordBotLambda.addEnvironment(
+            "DECK_VOTES_NAME",
+            this.deckVotes.tableName
+        );
+        this.discordBotLambda.addEnvironment(
+            "GUILD_COLLECTION_NAME",
+            this.guildCollection.tableName
+        );
+        this.discordBotLambda.addEnvironment(
+            "GUILD_CONFIG_NAME",
+            this.guildConfig.tableName
+        );
         this.discordBotLambda.addEnvironment(
             "INTERACTION_TTL_NAME",
             this.interactionTtl.tableName
@@ -79,6 +112,10 @@ export class DiscordStack extends Stack {
             "THUMBNAIL_LAMBDA_ARN",
             this.thumbnailLambda.functionArn
         );
+        this.discordBotLambda.addEnvironment(
+            "CARD_DIRECTORY",
+            CARD_DIRECTORY
+        );
 
         this.thumbnailLambda.addEnvironment(
             "S3_BUCKET_NAME", 
@@ -88,8 +125,25 @@ export class DiscordStack extends Stack {
             "S3_DIRECTORY", 
             "deck_thumbnails"
         );
+        this.thumbnailLambda.addEnvironment(
+            "CARD_DIRECTORY",
+            CARD_DIRECTORY
+        );
+        this.thumbnailLambda.addEnvironment(
+            "FONT_NAME",
+            "fonts/thumbnail.ttf"
+        );
+        this.thumbnailLambda.addEnvironment(
+            "ARTWORK_TILE_DIRECTORY",
+            "artwork_tiles"
+        );
 
         this.deckRepository.grantReadWriteData(this.discordBotLambda);
+        this.deckHistory.grantReadWriteData(this.discordBotLambda);
+        this.deckIndex.grantReadWriteData(this.discordBotLambda);
+        this.deckVotes.grantReadWriteData(this.discordBotLambda);
+        this.guildCollection.grantReadWriteData(this.discordBotLambda);
+        this.guildConfig.grantReadWriteData(this.discordBotLambda);
         this.interactionTtl.grantReadWriteData(this.discordBotLambda);
         this.createBucketDeckThumbnailWriteAccessPolicy(
             this.imageHostBucket
@@ -108,7 +162,8 @@ export class DiscordStack extends Stack {
             runtime: Runtime.PROVIDED_AL2023,
             handler: BINAHBOT_FUNCTION_HANDLER,
             code: Code.fromAsset(BINAHBOT_BOOTSTRAP_LOCATION),
-            timeout: Duration.seconds(5),
+            // Slow commands are deferred and keep running past Discord's 3 second deadline
+            timeout: Duration.seconds(15),
             memorySize: 512,
             architecture: Architecture.ARM_64,
         });
@@ -200,7 +255,11 @@ export class DiscordStack extends Stack {
             new PolicyStatement({
                 effect: Effect.ALLOW,
                 actions: ["s3:PutObject", "s3:GetObject"],
-                resources: [`${bucket.bucketArn}/deck_thumbnails/*`],
+                resources: [
+                    `${bucket.bucketArn}/deck_thumbnails/*`,
+                    `${bucket.bucketArn}/card_images/*`,
+                    `${bucket.bucketArn}/artwork_tiles/*`,
+                ],
             }),
             new PolicyStatement({
                 effect: Effect.ALLOW,
@@ -208,7 +267,11 @@ export class DiscordStack extends Stack {
                 resources: [bucket.bucketArn],
                 conditions: {
                     StringLike: {
-                        "s3:prefix": ["deck_thumbnails/*"],
+                        "s3:prefix": [
+                            "deck_thumbnails/*",
+                            "card_images/*",
+                            "artwork_tiles/*",
+                        ],
                     },
                 },
             }),
@@ -235,6 +298,83 @@ export class DiscordStack extends Stack {
         });
     }
 
+    private createDeckHistory(): TableV2 {
+        return new TableV2(this, "DeckHistoryTable", {
+            partitionKey: { name: "deck_key", type: AttributeType.STRING },
+            sortKey: { name: "version", type: AttributeType.NUMBER },
+            deletionProtection: true,
+            pointInTimeRecovery: true,
+            tableName: "DeckHistory",
+        });
+    }
+
+    private createDeckIndex(): TableV2 {
+        return new TableV2(this, "DeckIndexTable", {
+            partitionKey: { name: "deck_key", type: AttributeType.STRING },
+            sortKey: { name: "index_key", type: AttributeType.STRING },
+            deletionProtection: true,
+            globalSecondaryIndexes: [
+                {
+                    indexName: "gsi1",
+                    partitionKey: {
+                        name: "index_key",
+                        type: AttributeType.STRING,
+                    },
+                    sortKey: { name: "deck_key", type: AttributeType.STRING },
+                },
+            ],
+            tableName: "DeckIndex",
+        });
+    }
+
+    private createDeckVotes(): TableV2 {
+        return new TableV2(this, "DeckVotesTable", {
+            partitionKey: { name: "deck_key", type: AttributeType.STRING },
+            sortKey: { name: "user_id", type: AttributeType.STRING },
+            deletionProtection: true,
+            // sparse, only the per-deck totals items set "totals"
+            globalSecondaryIndexes: [
+                {
+                    indexName: "gsi1",
+                    partitionKey: {
+                        name: "totals",
+                        type: AttributeType.STRING,
+                    },
+                    sortKey: { name: "deck_key", type: AttributeType.STRING },
+                },
+            ],
+            pointInTimeRecovery: true,
+            tableName: "DeckVotes",
+        });
+    }
+
+    private createGuildCollection(): TableV2 {
+        return new TableV2(this, "GuildCollectionTable", {
+            partitionKey: { name: "guild_id", type: AttributeType.STRING },
+            sortKey: { name: "deck_key", type: AttributeType.STRING },
+            deletionProtection: true,
+            globalSecondaryIndexes: [
+                {
+                    indexName: "gsi1",
+                    partitionKey: {
+                        name: "deck_key",
+                        type: AttributeType.STRING,
+                    },
+                    sortKey: { name: "guild_id", type: AttributeType.STRING },
+                },
+            ],
+            tableName: "GuildCollection",
+        });
+    }
+
+    private createGuildConfig(): TableV2 {
+        return new TableV2(this, "GuildConfigTable", {
+            partitionKey: { name: "scope", type: AttributeType.STRING },
+            deletionProtection: true,
+            tableName: "GuildConfig",
+        });
+    }
+
     private createInteractionTtl(): TableV2 {
         return new TableV2(this, "InteractionTtlTable", {
             partitionKey: { name: "interaction_id", type: AttributeType.STRING },
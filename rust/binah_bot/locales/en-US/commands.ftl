@@ -0,0 +1,104 @@
+## Slash command descriptions, registered with `register-commands`. Add `command_{command}_name` or
+## `command_{command}_{option}_name` to translate a name, and `command_option_{option}_description` is
+## used for options without a description of their own
+
+command_lor_description = Search for combat pages, key pages, abno pages, passives, and battle symbols from Library of Ruina
+command_lor_query_description = What to search for
+command_lor_locale_description = Get card in this locale instead (BinahBot locale will remain the same)
+command_lor_all_description = Get all cards, rather than only collectable and obtainable cards (default)
+
+command_lc_description = Search for information about abnormalities and EGO equipment from Lobotomy Corporation
+command_lc_query_description = What to search for
+command_lc_locale_description = Get information in this locale instead (BinahBot locale will remain the same)
+
+command_deck_description = Look up a deck from the deck repository
+command_deck_author_description = Author of deck
+command_deck_tag_description = Only suggest decks with this tag
+command_deck_page_description = Only suggest decks containing this combat page
+command_deck_passive_description = Only suggest decks with this passive
+
+command_createdeck_description = Create deck
+command_createdeck_deck_description = Deck code from Tiphereth (Zasz)'s deck editor, or a list like "Key page: X / 3x Page"
+
+command_updatedeck_description = Update deck
+command_updatedeck_deck_description = Deck code from Tiphereth (Zasz)'s deck editor
+
+command_deletedeck_description = Delete deck
+
+command_forkdeck_description = Copy another deck from the deck repository into your own decks
+command_forkdeck_name_description = Name of deck to fork
+command_forkdeck_new_name_description = Name of your copy (defaults to the original name)
+
+command_deckeditors_description = Manage who can edit one of your decks
+command_deckeditors_action_description = What to do with the deck's editors (defaults to list)
+command_deckeditors_user_description = User to grant or revoke edit rights, or to transfer ownership to
+
+command_deckhistory_description = View, compare and roll back versions of a deck
+command_deckhistory_action_description = What to do with the deck history (defaults to list)
+command_deckhistory_version_description = Version to view, compare from or roll back to
+command_deckhistory_compare_description = Version to compare against (defaults to the current version)
+
+command_deckdiff_description = Compare two decks from the deck repository
+command_deckdiff_first_description = Deck to compare from
+command_deckdiff_second_description = Deck to compare to
+
+command_decksearch_description = Search the deck repository by tag, chapter, combat page or passive
+command_decksearch_tag_description = Tag of deck
+command_decksearch_page_description = Combat page the deck contains
+command_decksearch_passive_description = Passive the deck has
+
+command_decktop_description = Show the highest rated decks from the deck repository
+command_decktop_sort_description = What to rank decks by (default: upvotes)
+
+command_deckcollection_description = View or curate this server's deck collection
+command_deckcollection_action_description = What to do with the collection (defaults to list)
+command_deckcollection_name_description = Name of deck to add or remove
+
+command_rollcalc_description = Compare roll probabilities
+command_rollcalc_min1_description = Minroll of first die
+command_rollcalc_max1_description = Maxroll of first die
+command_rollcalc_min2_description = Minroll of second die
+command_rollcalc_max2_description = Maxroll of second die
+
+command_spoilers_description = Show or change the latest chapter whose pages can be shown in this channel
+command_spoilers_action_description = What to do with the channel's spoiler limit (defaults to show)
+command_spoilers_chapter_description = Latest chapter to allow, when setting
+
+command_progress_description = Show or set how far you are in the game, to hide pages and decks from later chapters
+command_progress_chapter_description = Latest chapter you've reached
+
+command_config_description = View or change BinahBot's settings for this server or one of its channels
+command_config_channel_description = Channel to configure instead of the whole server
+command_config_spoilers_description = Latest chapter whose pages can be shown
+command_config_locale_description = Language to respond in, instead of each user's own
+command_config_privacy_description = Whether responses are only visible to the user when they don't choose
+command_config_disable_description = Command to disable
+command_config_enable_description = Command to enable again
+command_config_reset_description = Clear every setting before applying the others
+
+command_about_description = About BinahBot
+
+command_option_name_description = Name of deck
+command_option_description_description = Optional description associated with the deck
+command_option_tags_description = Comma separated tags, e.g. "bleed, Urban Nightmare clear"
+command_option_keypage_description = Key page of deck
+command_option_chapter_description = Chapter of deck
+command_option_private_description = Response will only be visible to you
+
+command_choice_UrbanMyth = Urban Myth
+command_choice_UrbanLegend = Urban Legend
+command_choice_UrbanPlague = Urban Plague
+command_choice_UrbanNightmare = Urban Nightmare
+command_choice_StarOfTheCity = Star of the City
+command_choice_ImpuritasCivitatis = Impuritas Civitatis
+command_choice_Canard = Canard
+command_choice_reset = Reset to default
+command_choice_show = Show
+command_choice_set = Set
+command_choice_private = Private
+command_choice_public = Public
+command_choice_en-US = English
+command_choice_ko = 한국어
+command_choice_ja = 日本語
+command_choice_zh-CN = 简体中文
+command_choice_zh-TW = 繁體中文
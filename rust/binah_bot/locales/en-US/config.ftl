@@ -0,0 +1,11 @@
+config_guild_title = Server settings
+config_channel_title = Settings for { $channel }
+config_description = Anything not set here defers to the server's settings, and then to BinahBot's defaults
+config_spoilers_field = Max spoiler chapter
+config_locale_field = Default language
+config_privacy_field = Default privacy
+config_disabled_commands_field = Disabled commands
+config_not_set = Not set
+
+config_guild_only_error_message = Settings can only be changed inside a server
+config_no_permission_error_message = You need the Manage Server permission to change settings
@@ -6,9 +6,64 @@ read_deck_combat_page_count = { $page_name } x{ $count }
 list_deck_name_author = { $deck_name } ({ $author })
 update_deck_success = Successfully updated "{ $deck_name }"
 delete_deck_success = Successfully deleted "{ $deck_name }"
+create_deck_import_confirm_message = Some entries didn't match a single page exactly. Please confirm the following matches:
+create_deck_import_ambiguous_entry = "{ $query }" → { $page_name }
+create_deck_import_confirm_button_text = Create deck
+create_deck_import_cancel_button_text = Cancel
+create_deck_import_cancelled = Cancelled creating "{ $deck_name }"
+deck_history_version_title = { $deck_name } (v{ $version })
+deck_history_version_entry = v{ $version } · { $timestamp } · { $editor }
+deck_history_current_version_entry = v{ $version } · { $timestamp } · { $editor } (current)
+deck_history_unknown_timestamp = unknown date
+deck_history_rollback_success = Rolled back "{ $deck_name }" to v{ $version }. It is now saved as v{ $new_version }
+deck_diff_title = { $old } → { $new }
+deck_diff_keypage_change = { $old } → { $new }
+deck_diff_no_changes = No differences
+read_deck_tags_header = Tags
+deck_search_title = Search results ({ $count })
+deck_search_no_results = No decks matched your search
+read_deck_upvote_button_text = ▲ Upvote
+read_deck_favorite_button_text = ★ Favorite
+deck_vote_upvoted = Upvoted "{ $deck_name }". It now has { $upvotes } upvotes
+deck_vote_upvote_removed = Removed your upvote from "{ $deck_name }"
+deck_vote_favorited = Added "{ $deck_name }" to your favorites
+deck_vote_favorite_removed = Removed "{ $deck_name }" from your favorites
+deck_leaderboard_title = Top decks
+deck_leaderboard_entry = { $rank }. { $deck_name } ({ $author }) · ▲ { $upvotes } · ★ { $favorites }
+deck_leaderboard_empty = No decks have been voted on yet
+list_deck_collection_name_author = ✦ { $deck_name } ({ $author })
+deck_collection_title = Server collection ({ $count })
+deck_collection_empty = This server hasn't added any decks to its collection yet
+deck_collection_added = Added "{ $deck_name }" to this server's collection
+deck_collection_removed = Removed "{ $deck_name }" from this server's collection
+read_deck_forked_from_header = Forked from
+read_deck_forked_from = { $author } · { $deck_name } (v{ $version })
+fork_deck_success = Forked "{ $source_name }" as "{ $deck_name }"
+deck_editors_title = Editors of { $deck_name }
+deck_editors_owner = Owner: { $owner }
+deck_editors_none = No one else can edit this deck
+deck_editors_added = { $user } can now edit "{ $deck_name }"
+deck_editors_removed = { $user } can no longer edit "{ $deck_name }"
+deck_editors_transferred = Transferred ownership of "{ $deck_name }" to { $user }
+user_decks_title = Decks by { $user }
+user_decks_none = { $user } hasn't saved any decks yet
 
 cant_parse_deck_error_message = Couldn't parse deck data. Are you passing in the deck code or URL from Tiph's Deck Editor? https://tiphereth.zasz.su/u/deck_editor/
 invalid_deck_error_message = Invalid deck code. https://tiphereth.zasz.su/u/deck_editor/
 missing_combat_pages_error_message = Deck must have all 9 combat page slots filled. https://tiphereth.zasz.su/u/deck_editor/
 missing_keypage_error_message = Deck is missing a key page. https://tiphereth.zasz.su/u/deck_editor/
-deck_not_found_error_message = Couldn't get deck. Due to technical limitations, you must use the autocomplete in order to find user-submitted decks
\ No newline at end of file
+deck_not_found_error_message = Couldn't get deck. Due to technical limitations, you must use the autocomplete in order to find user-submitted decks
+import_deck_unparseable_entry_error_message = Couldn't understand "{ $entry }". Use one entry per line or separate entries with " / ", e.g. "Key page: Xiao's Page / 3x Focus Spirit"
+import_deck_page_not_found_error_message = Couldn't find a page named "{ $entry }"
+import_deck_too_many_combat_pages_error_message = Deck can't have more than 9 combat pages
+import_deck_too_many_key_pages_error_message = Deck can't have more than one key page
+deck_history_not_found_error_message = No history found for this deck. History is recorded starting from the next update
+deck_history_version_not_found_error_message = Couldn't find that version. Use the list action to see available versions
+invalid_tags_error_message = Tags must be comma separated, with at most 5 tags of up to 32 characters each
+deck_search_no_filters_error_message = Provide at least one of tag, chapter, page or passive to search by
+deck_collection_guild_only_error_message = Deck collections are only available in servers
+deck_collection_no_permission_error_message = You need the Manage Server permission to curate this server's collection
+fork_deck_same_name_error_message = You already own this deck. Give the fork a different name
+deck_not_owner_error_message = Only the deck's owner can do that
+deck_not_editor_error_message = Only the deck's owner and editors can modify it
+deck_editors_missing_user_error_message = Choose a user to grant or revoke edit rights
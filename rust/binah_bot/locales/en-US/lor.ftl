@@ -82,5 +82,20 @@ rarity_display_objetdart = Objet d'Art
 no_page_error_message = *No page found*
 spoiler_enforcement_message = Cannot display `card_id={ $card_id }` because its chapter is { $chapter }, but this channel only allows { $configured_chapter } and below
 deck_spoiler_enforcement_message = Cannot show `{ $deck_name }` because its chapter is { $chapter }, but this channel only allows { $configured_chapter } and below
+progress_enforcement_message = Cannot display `card_id={ $card_id }` because its chapter is { $chapter }, but your progress is set to { $configured_chapter }. Change it with `/progress`
+deck_progress_enforcement_message = Cannot show `{ $deck_name }` because its chapter is { $chapter }, but your progress is set to { $configured_chapter }. Change it with `/progress`
+
+spoilers_show_message = This channel shows pages up to { $chapter }
+spoilers_show_unrestricted_message = This channel shows pages from every chapter
+spoilers_updated_message = This channel now shows pages up to { $chapter }
+spoilers_updated_unrestricted_message = This channel now shows pages from every chapter
+spoilers_guild_only_error_message = Spoiler limits can only be changed in server channels
+spoilers_no_permission_error_message = You need the Manage Channels permission to change this channel's spoiler limit
+spoilers_missing_chapter_error_message = Choose the latest chapter to allow with the `chapter` option
+
+progress_show_message = Your progress is set to { $chapter }. Pages and decks from later chapters are hidden from you
+progress_show_unrestricted_message = You haven't set your progress, so pages and decks from every chapter are shown
+progress_updated_message = Your progress is now set to { $chapter }. Pages and decks from later chapters will be hidden from you
+progress_updated_unrestricted_message = Your progress has been cleared, so pages and decks from every chapter will be shown
 
 exhaust_on_use = Single-use
\ No newline at end of file
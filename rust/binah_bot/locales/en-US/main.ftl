@@ -2,8 +2,12 @@ autocomplete_display = { $display }
 autocomplete_display_disambiguation = { $display } ({ $disambiguation })
 
 generic_error_message = Unknown error has occurred
+malformed_interaction_error_message = Discord sent an incomplete request. Try running the command again
+stale_interaction_error_message = This command or button is no longer supported. Try running the command again
+not_interaction_author_error_message = Only the person who ran the command can use these buttons
+interaction_expired_error_message = This interaction has expired. Try running the command again
 
 about_binahbot_header = About BinahBot
 about_binahbot_github_header = GitHub
 
-delete_message_button_text = Delete
\ No newline at end of file
+delete_message_button_text = Deletecommand_disabled_error_message = This command has been disabled here by the server's moderators
@@ -104,6 +104,7 @@ pub fn about_command(
 #[cfg(test)]
 mod tests {
     use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
     use crate::models::discord::DiscordInteractionData;
     use crate::models::discord::DiscordInteractionOptions;
     use crate::models::discord::DiscordInteractionType;
@@ -122,15 +123,19 @@ mod tests {
                 DiscordApplicationCommandInteractionData {
                     id: "id".to_string(),
                     name: "lor".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
                     options: Some(vec![DiscordInteractionOptions {
                         name: "private".to_string(),
                         name_localizations: None,
                         value: DiscordInteractionOptionValue::Bool(false),
                         focused: None,
                     }]),
+                    target_id: None,
+                    resolved: None,
                 },
             )),
             channel_id: None,
+            guild_id: None,
             token: "token".to_string(),
             locale: None,
             guild_locale: None,
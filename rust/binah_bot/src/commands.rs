@@ -0,0 +1,609 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use fluent_templates::Loader;
+use fluent_templates::StaticLoader;
+
+use crate::discord::overwrite_global_commands;
+use crate::models::discord::DiscordApplicationCommand;
+use crate::models::discord::DiscordApplicationCommandOption;
+use crate::models::discord::DiscordApplicationCommandOptionChoice;
+use crate::models::discord::DiscordApplicationCommandOptionType;
+use crate::models::discord::DiscordApplicationCommandType;
+use crate::models::discord::DiscordApplicationIntegrationType;
+use crate::models::discord::DiscordInteractionContextType;
+use crate::models::discord::DiscordPermission;
+use crate::secrets::load_discord_secrets;
+
+pub const ABOUT_COMMAND_NAME: &str = "about";
+pub const LC_COMMAND_NAME: &str = "lc";
+pub const LOR_COMMAND_NAME: &str = "lor";
+pub const CREATE_DECK_COMMAND_NAME: &str = "createdeck";
+pub const READ_DECK_COMMAND_NAME: &str = "deck";
+pub const UPDATE_DECK_COMMAND_NAME: &str = "updatedeck";
+pub const DELETE_DECK_COMMAND_NAME: &str = "deletedeck";
+pub const DECK_HISTORY_COMMAND_NAME: &str = "deckhistory";
+pub const DECK_DIFF_COMMAND_NAME: &str = "deckdiff";
+pub const DECK_SEARCH_COMMAND_NAME: &str = "decksearch";
+pub const DECK_LEADERBOARD_COMMAND_NAME: &str = "decktop";
+pub const DECK_COLLECTION_COMMAND_NAME: &str = "deckcollection";
+pub const FORK_DECK_COMMAND_NAME: &str = "forkdeck";
+pub const DECK_EDITORS_COMMAND_NAME: &str = "deckeditors";
+pub const ROLLCALC_COMMAND_NAME: &str = "rollcalc";
+pub const CONFIG_COMMAND_NAME: &str = "config";
+pub const SPOILERS_COMMAND_NAME: &str = "spoilers";
+pub const PROGRESS_COMMAND_NAME: &str = "progress";
+// context menu commands are named by what's shown in the menu
+pub const LOOKUP_MESSAGE_COMMAND_NAME: &str = "Look up page or deck";
+pub const USER_DECKS_COMMAND_NAME: &str = "View decks";
+
+pub static REGISTER_COMMANDS_COMMAND: &str = "register-commands";
+
+const LOR_LOCALE_CHOICES: &[&str] = &["en", "kr", "jp", "cn", "trcn"];
+const LC_LOCALE_CHOICES: &[&str] = &[
+    "en", "kr", "jp", "cn", "cn_tr", "ru", "bg", "es", "fr", "pt_br", "pt_pt",
+];
+const CHAPTER_CHOICES: &[&str] = &[
+    "Canard",
+    "UrbanMyth",
+    "UrbanLegend",
+    "UrbanPlague",
+    "UrbanNightmare",
+    "StarOfTheCity",
+    "ImpuritasCivitatis",
+];
+// choice value that clears a setting back to its default
+pub const RESET_CHOICE: &str = "reset";
+const CHAPTER_OR_RESET_CHOICES: &[&str] = &[
+    "Canard",
+    "UrbanMyth",
+    "UrbanLegend",
+    "UrbanPlague",
+    "UrbanNightmare",
+    "StarOfTheCity",
+    "ImpuritasCivitatis",
+    RESET_CHOICE,
+];
+const CONFIG_LOCALE_CHOICES: &[&str] = &["en-US", "ko", "ja", "zh-CN", "zh-TW", RESET_CHOICE];
+pub const PRIVATE_CHOICE: &str = "private";
+pub const PUBLIC_CHOICE: &str = "public";
+const CONFIG_PRIVACY_CHOICES: &[&str] = &[PRIVATE_CHOICE, PUBLIC_CHOICE, RESET_CHOICE];
+// /config itself can't be disabled, or a server could lock itself out
+const CONFIG_COMMAND_CHOICES: &[&str] = &[
+    LOR_COMMAND_NAME,
+    LC_COMMAND_NAME,
+    READ_DECK_COMMAND_NAME,
+    CREATE_DECK_COMMAND_NAME,
+    UPDATE_DECK_COMMAND_NAME,
+    DELETE_DECK_COMMAND_NAME,
+    FORK_DECK_COMMAND_NAME,
+    DECK_EDITORS_COMMAND_NAME,
+    DECK_HISTORY_COMMAND_NAME,
+    DECK_DIFF_COMMAND_NAME,
+    DECK_SEARCH_COMMAND_NAME,
+    DECK_LEADERBOARD_COMMAND_NAME,
+    DECK_COLLECTION_COMMAND_NAME,
+    ROLLCALC_COMMAND_NAME,
+    SPOILERS_COMMAND_NAME,
+    PROGRESS_COMMAND_NAME,
+    ABOUT_COMMAND_NAME,
+    LOOKUP_MESSAGE_COMMAND_NAME,
+    USER_DECKS_COMMAND_NAME,
+];
+const SPOILERS_ACTION_CHOICES: &[&str] = &["show", "set", RESET_CHOICE];
+const EDITORS_ACTION_CHOICES: &[&str] = &["list", "add", "remove", "transfer"];
+const HISTORY_ACTION_CHOICES: &[&str] = &["list", "view", "diff", "rollback"];
+const COLLECTION_ACTION_CHOICES: &[&str] = &["list", "add", "remove"];
+const SORT_CHOICES: &[&str] = &["upvotes", "favorites"];
+const MAX_DIE_ROLL: i32 = 65536;
+
+struct CommandDefinition {
+    name: &'static str,
+    // fluent keys for the command are prefixed with `command_{key}`
+    key: &'static str,
+    r#type: DiscordApplicationCommandType,
+    options: Vec<OptionDefinition>,
+    // commands limited to server members with this permission, unless a server overrides it
+    permission: Option<DiscordPermission>,
+}
+
+struct OptionDefinition {
+    name: &'static str,
+    r#type: DiscordApplicationCommandOptionType,
+    required: bool,
+    autocomplete: bool,
+    max_length: Option<i32>,
+    min_value: Option<i32>,
+    max_value: Option<i32>,
+    // choice values; their display names are looked up under `command_choice_{value}`
+    choices: &'static [&'static str],
+}
+
+impl OptionDefinition {
+    fn new(name: &'static str, r#type: DiscordApplicationCommandOptionType) -> Self {
+        OptionDefinition {
+            name,
+            r#type,
+            required: false,
+            autocomplete: false,
+            max_length: None,
+            min_value: None,
+            max_value: None,
+            choices: &[],
+        }
+    }
+
+    fn string(name: &'static str, max_length: i32) -> Self {
+        OptionDefinition {
+            max_length: Some(max_length),
+            ..OptionDefinition::new(name, DiscordApplicationCommandOptionType::String)
+        }
+    }
+
+    fn choice(name: &'static str, choices: &'static [&'static str]) -> Self {
+        OptionDefinition {
+            choices,
+            ..OptionDefinition::new(name, DiscordApplicationCommandOptionType::String)
+        }
+    }
+
+    fn integer(name: &'static str, min_value: i32, max_value: Option<i32>) -> Self {
+        OptionDefinition {
+            min_value: Some(min_value),
+            max_value,
+            ..OptionDefinition::new(name, DiscordApplicationCommandOptionType::Integer)
+        }
+    }
+
+    fn boolean(name: &'static str) -> Self {
+        OptionDefinition::new(name, DiscordApplicationCommandOptionType::Boolean)
+    }
+
+    fn user(name: &'static str) -> Self {
+        OptionDefinition::new(name, DiscordApplicationCommandOptionType::User)
+    }
+
+    fn channel(name: &'static str) -> Self {
+        OptionDefinition::new(name, DiscordApplicationCommandOptionType::Channel)
+    }
+
+    fn required(self) -> Self {
+        OptionDefinition {
+            required: true,
+            ..self
+        }
+    }
+
+    fn autocomplete(self) -> Self {
+        OptionDefinition {
+            autocomplete: true,
+            ..self
+        }
+    }
+}
+
+fn slash_command(name: &'static str, options: Vec<OptionDefinition>) -> CommandDefinition {
+    CommandDefinition {
+        name,
+        key: name,
+        r#type: DiscordApplicationCommandType::ChatInput,
+        options,
+        permission: None,
+    }
+}
+
+impl CommandDefinition {
+    fn with_permission(self, permission: DiscordPermission) -> Self {
+        CommandDefinition {
+            permission: Some(permission),
+            ..self
+        }
+    }
+}
+
+// Every command the router handles, in the order they're shown to users
+fn command_definitions() -> Vec<CommandDefinition> {
+    vec![
+        slash_command(
+            LOR_COMMAND_NAME,
+            vec![
+                OptionDefinition::string("query", 100)
+                    .required()
+                    .autocomplete(),
+                OptionDefinition::choice("locale", LOR_LOCALE_CHOICES),
+                OptionDefinition::boolean("all"),
+                OptionDefinition::boolean("private"),
+            ],
+        ),
+        slash_command(
+            LC_COMMAND_NAME,
+            vec![
+                OptionDefinition::string("query", 100)
+                    .required()
+                    .autocomplete(),
+                OptionDefinition::choice("locale", LC_LOCALE_CHOICES),
+                OptionDefinition::boolean("private"),
+            ],
+        ),
+        slash_command(
+            READ_DECK_COMMAND_NAME,
+            vec![
+                OptionDefinition::string("name", 100)
+                    .required()
+                    .autocomplete(),
+                OptionDefinition::user("author"),
+                OptionDefinition::string("keypage", 50).autocomplete(),
+                OptionDefinition::string("tag", 32),
+                OptionDefinition::string("page", 100).autocomplete(),
+                OptionDefinition::string("passive", 100).autocomplete(),
+                OptionDefinition::boolean("private"),
+            ],
+        ),
+        slash_command(
+            CREATE_DECK_COMMAND_NAME,
+            vec![
+                OptionDefinition::string("deck", 2000).required(),
+                OptionDefinition::string("name", 80).required(),
+                OptionDefinition::string("description", 300),
+                OptionDefinition::string("tags", 200),
+            ],
+        ),
+        slash_command(
+            UPDATE_DECK_COMMAND_NAME,
+            vec![
+                OptionDefinition::string("name", 100)
+                    .required()
+                    .autocomplete(),
+                OptionDefinition::string("deck", 80),
+                OptionDefinition::string("description", 300),
+                OptionDefinition::string("tags", 200),
+            ],
+        ),
+        slash_command(
+            DELETE_DECK_COMMAND_NAME,
+            vec![OptionDefinition::string("name", 100)
+                .required()
+                .autocomplete()],
+        ),
+        slash_command(
+            FORK_DECK_COMMAND_NAME,
+            vec![
+                OptionDefinition::string("name", 100)
+                    .required()
+                    .autocomplete(),
+                OptionDefinition::string("new_name", 80),
+            ],
+        ),
+        slash_command(
+            DECK_EDITORS_COMMAND_NAME,
+            vec![
+                OptionDefinition::string("name", 100)
+                    .required()
+                    .autocomplete(),
+                OptionDefinition::choice("action", EDITORS_ACTION_CHOICES),
+                OptionDefinition::user("user"),
+            ],
+        ),
+        slash_command(
+            DECK_HISTORY_COMMAND_NAME,
+            vec![
+                OptionDefinition::string("name", 100)
+                    .required()
+                    .autocomplete(),
+                OptionDefinition::choice("action", HISTORY_ACTION_CHOICES),
+                OptionDefinition::integer("version", 1, None),
+                OptionDefinition::integer("compare", 1, None),
+            ],
+        ),
+        slash_command(
+            DECK_DIFF_COMMAND_NAME,
+            vec![
+                OptionDefinition::string("first", 100)
+                    .required()
+                    .autocomplete(),
+                OptionDefinition::string("second", 100)
+                    .required()
+                    .autocomplete(),
+                OptionDefinition::boolean("private"),
+            ],
+        ),
+        slash_command(
+            DECK_SEARCH_COMMAND_NAME,
+            vec![
+                OptionDefinition::string("tag", 32),
+                OptionDefinition::choice("chapter", CHAPTER_CHOICES),
+                OptionDefinition::string("page", 100).autocomplete(),
+                OptionDefinition::string("passive", 100).autocomplete(),
+                OptionDefinition::boolean("private"),
+            ],
+        ),
+        slash_command(
+            DECK_LEADERBOARD_COMMAND_NAME,
+            vec![
+                OptionDefinition::string("keypage", 50).autocomplete(),
+                OptionDefinition::choice("chapter", CHAPTER_CHOICES),
+                OptionDefinition::choice("sort", SORT_CHOICES),
+                OptionDefinition::boolean("private"),
+            ],
+        ),
+        slash_command(
+            DECK_COLLECTION_COMMAND_NAME,
+            vec![
+                OptionDefinition::choice("action", COLLECTION_ACTION_CHOICES),
+                OptionDefinition::string("name", 100).autocomplete(),
+                OptionDefinition::boolean("private"),
+            ],
+        ),
+        slash_command(
+            ROLLCALC_COMMAND_NAME,
+            ["min1", "max1", "min2", "max2"]
+                .into_iter()
+                .map(|x| {
+                    OptionDefinition::integer(x, 1, Some(MAX_DIE_ROLL))
+                        .required()
+                        .autocomplete()
+                })
+                .chain([OptionDefinition::boolean("private")])
+                .collect(),
+        ),
+        slash_command(
+            SPOILERS_COMMAND_NAME,
+            vec![
+                OptionDefinition::choice("action", SPOILERS_ACTION_CHOICES),
+                OptionDefinition::choice("chapter", CHAPTER_CHOICES),
+            ],
+        ),
+        slash_command(
+            PROGRESS_COMMAND_NAME,
+            vec![OptionDefinition::choice(
+                "chapter",
+                CHAPTER_OR_RESET_CHOICES,
+            )],
+        ),
+        slash_command(
+            CONFIG_COMMAND_NAME,
+            vec![
+                OptionDefinition::channel("channel"),
+                OptionDefinition::choice("spoilers", CHAPTER_OR_RESET_CHOICES),
+                OptionDefinition::choice("locale", CONFIG_LOCALE_CHOICES),
+                OptionDefinition::choice("privacy", CONFIG_PRIVACY_CHOICES),
+                OptionDefinition::choice("disable", CONFIG_COMMAND_CHOICES),
+                OptionDefinition::choice("enable", CONFIG_COMMAND_CHOICES),
+                OptionDefinition::boolean("reset"),
+            ],
+        )
+        .with_permission(DiscordPermission::ManageGuild),
+        slash_command(
+            ABOUT_COMMAND_NAME,
+            vec![OptionDefinition::boolean("private")],
+        ),
+        CommandDefinition {
+            name: LOOKUP_MESSAGE_COMMAND_NAME,
+            key: "lookup_message",
+            r#type: DiscordApplicationCommandType::Message,
+            options: Vec::new(),
+            permission: None,
+        },
+        CommandDefinition {
+            name: USER_DECKS_COMMAND_NAME,
+            key: "user_decks",
+            r#type: DiscordApplicationCommandType::User,
+            options: Vec::new(),
+            permission: None,
+        },
+    ]
+}
+
+// Builds the commands registered with Discord. Descriptions come from the fallback locale, and
+// every other locale that translates a name or description is added to its localizations. Locales
+// are looked up without fallback, so one without a commands.ftl is left out rather than repeating
+// the fallback's text
+pub fn build_application_commands(locales: &StaticLoader) -> Vec<DiscordApplicationCommand> {
+    command_definitions()
+        .into_iter()
+        .map(|command| {
+            let prefix = format!("command_{}", command.key);
+            // Discord only shows descriptions for slash commands and rejects them elsewhere
+            let is_slash_command = command.r#type == DiscordApplicationCommandType::ChatInput;
+            DiscordApplicationCommand {
+                name: command.name.to_string(),
+                name_localizations: localize(locales, &[format!("{}_name", prefix)]),
+                description: if is_slash_command {
+                    locales.lookup(locales.fallback(), &format!("{}_description", prefix))
+                } else {
+                    String::new()
+                },
+                description_localizations: is_slash_command
+                    .then(|| localize(locales, &[format!("{}_description", prefix)]))
+                    .flatten(),
+                r#type: command.r#type,
+                // permissions only exist inside servers, so restricted commands aren't offered
+                // anywhere else
+                integration_types: if command.permission.is_some() {
+                    vec![DiscordApplicationIntegrationType::GuildInstall]
+                } else {
+                    vec![
+                        DiscordApplicationIntegrationType::GuildInstall,
+                        DiscordApplicationIntegrationType::UserInstall,
+                    ]
+                },
+                contexts: command
+                    .permission
+                    .is_some()
+                    .then(|| vec![DiscordInteractionContextType::Guild]),
+                options: command
+                    .options
+                    .iter()
+                    .map(|x| build_option(locales, &prefix, x))
+                    .collect(),
+                default_member_permissions: command.permission.map(|x| (x as u64).to_string()),
+            }
+        })
+        .collect()
+}
+
+// Options shared between commands fall back to a common `command_option_{name}` key
+fn build_option(
+    locales: &StaticLoader,
+    command_prefix: &str,
+    option: &OptionDefinition,
+) -> DiscordApplicationCommandOption {
+    let keys = |suffix: &str| {
+        [
+            format!("{}_{}_{}", command_prefix, option.name, suffix),
+            format!("command_option_{}_{}", option.name, suffix),
+        ]
+    };
+    let description_keys = keys("description");
+
+    DiscordApplicationCommandOption {
+        r#type: option.r#type,
+        name: option.name.to_string(),
+        name_localizations: localize(locales, &keys("name")),
+        description: lookup_first(locales, locales.fallback(), &description_keys)
+            .unwrap_or_default(),
+        description_localizations: localize(locales, &description_keys),
+        required: option.required,
+        autocomplete: option.autocomplete.then_some(true),
+        max_length: option.max_length,
+        min_value: option.min_value,
+        max_value: option.max_value,
+        choices: (!option.choices.is_empty()).then(|| {
+            option
+                .choices
+                .iter()
+                .map(|x| DiscordApplicationCommandOptionChoice {
+                    name: lookup_first(
+                        locales,
+                        locales.fallback(),
+                        &[format!("command_choice_{}", x)],
+                    )
+                    .unwrap_or(x.to_string()),
+                    value: x.to_string(),
+                })
+                .collect()
+        }),
+    }
+}
+
+// Collects every locale that defines one of `keys` itself, rather than through the fallback
+fn localize(locales: &StaticLoader, keys: &[String]) -> Option<HashMap<String, String>> {
+    let localizations = locales
+        .locales()
+        .filter_map(|lang| lookup_first(locales, lang, keys).map(|x| (lang.to_string(), x)))
+        .collect::<HashMap<_, _>>();
+    (!localizations.is_empty()).then_some(localizations)
+}
+
+fn lookup_first(
+    locales: &StaticLoader,
+    lang: &unic_langid::LanguageIdentifier,
+    keys: &[String],
+) -> Option<String> {
+    keys.iter()
+        .find_map(|key| locales.lookup_single_language::<&str>(lang, key, None).ok())
+}
+
+// Usage: register-commands [--dry-run]
+//
+// Overwrites the bot's global commands with the ones defined here. With --dry-run, the
+// commands are printed instead and no secrets are needed.
+pub async fn run(
+    args: &[String],
+    locales: &StaticLoader,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let commands = build_application_commands(locales);
+
+    match args.first().map(|x| x.as_str()) {
+        Some("--dry-run") => {
+            println!("{}", serde_json::to_string_pretty(&commands)?);
+        }
+        None => {
+            let config = aws_config::load_from_env().await;
+            let secrets = load_discord_secrets(&config).await;
+            overwrite_global_commands(&reqwest::Client::new(), &secrets, &commands).await?;
+            println!("Registered {} commands", commands.len());
+        }
+        Some(x) => return Err(format!("unknown argument {}", x).into()),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LOCALES;
+
+    use super::*;
+
+    #[test]
+    fn sanity_commands_are_described() {
+        let commands = build_application_commands(&LOCALES);
+
+        assert_eq!(command_definitions().len(), commands.len());
+        for command in commands.iter() {
+            let descriptions = command
+                .description_localizations
+                .iter()
+                .flat_map(|x| x.values())
+                .chain(command.options.iter().flat_map(|x| {
+                    x.description_localizations
+                        .iter()
+                        .flat_map(|x| x.values())
+                        .chain([&x.description])
+                }));
+            // discord caps descriptions at 100 characters
+            for description in descriptions {
+                assert!(!description.is_empty() && description.chars().count() <= 100);
+            }
+        }
+    }
+
+    #[test]
+    fn should_only_localize_locales_with_command_translations() {
+        let has_translations = |lang: &str| {
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("locales")
+                .join(lang)
+                .join("commands.ftl")
+                .exists()
+        };
+
+        for command in build_application_commands(&LOCALES) {
+            let localized_langs = command
+                .name_localizations
+                .iter()
+                .chain(command.description_localizations.iter())
+                .chain(command.options.iter().flat_map(|x| {
+                    x.name_localizations
+                        .iter()
+                        .chain(x.description_localizations.iter())
+                }))
+                .flat_map(|x| x.keys());
+            for lang in localized_langs {
+                assert!(has_translations(lang), "{} {}", command.name, lang);
+            }
+        }
+    }
+
+    #[test]
+    fn sanity_slash_command_names() {
+        let is_valid_name = |x: &str| {
+            !x.is_empty()
+                && x.len() <= 32
+                && x.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        };
+
+        for command in build_application_commands(&LOCALES)
+            .iter()
+            .filter(|x| x.r#type == DiscordApplicationCommandType::ChatInput)
+        {
+            assert!(is_valid_name(&command.name), "{}", command.name);
+            assert!(!command.description.is_empty());
+            for option in command.options.iter() {
+                assert!(is_valid_name(&option.name), "{}", option.name);
+            }
+        }
+    }
+}
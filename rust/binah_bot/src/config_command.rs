@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::Loader;
+use ruina::ruina_common::game_objects::common::Chapter;
+use unic_langid::LanguageIdentifier;
+
+use crate::commands::PRIVATE_CHOICE;
+use crate::commands::PUBLIC_CHOICE;
+use crate::error::BinahBotError;
+use crate::macros::cast_enum_variant;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::binahbot::BinahBotLocale;
+use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::config::ConfigScope;
+use crate::models::config::GuildConfig;
+use crate::models::discord::AllowedMentions;
+use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordEmbedFields;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionOptionValue;
+use crate::models::discord::DiscordInteractionOptions;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::DiscordPermission;
+use crate::models::discord::MessageResponse;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_choice_display_name;
+use crate::utils::get_command_options;
+use crate::utils::get_option_value;
+use crate::utils::has_permission;
+
+// Updates the settings of the server, or of one of its channels when `channel` is given,
+// then shows the resulting settings of that scope. With no changes it only shows them
+pub async fn config_command(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
+
+    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+
+    let Some(guild_id) = interaction.guild_id.as_ref() else {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "config_guild_only_error_message",
+            env,
+        ));
+    };
+
+    // discord already hides the command from other members, but servers can override that
+    if !has_permission(interaction.member.as_ref(), DiscordPermission::ManageGuild) {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "config_no_permission_error_message",
+            env,
+        ));
+    }
+
+    let scope = match get_string_option("channel", command_args) {
+        Some(x) => ConfigScope::Channel(x.to_string()),
+        None => ConfigScope::Guild(guild_id.clone()),
+    };
+
+    let reset = get_option_value("reset", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Bool))
+        .is_some_and(|x| *x);
+
+    let mut config = if reset {
+        GuildConfig::default()
+    } else {
+        match env.config_repository.get_config(&scope).await {
+            Ok(x) => x,
+            Err(_) => {
+                return Ok(build_error_message_response(
+                    &lang_id,
+                    "generic_error_message",
+                    env,
+                ))
+            }
+        }
+    };
+
+    let is_updated = reset || apply_config_options(&mut config, command_args);
+
+    if is_updated
+        && env
+            .config_repository
+            .put_config(&scope, &config)
+            .await
+            .is_err()
+    {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "generic_error_message",
+            env,
+        ));
+    }
+
+    Ok(MessageResponse {
+        r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+            content: None,
+            embeds: Some(vec![build_config_embed(&scope, &config, &lang_id, env)]),
+            flags: Some(DiscordMessageFlag::EphemeralMessage as i32),
+            components: None,
+        }),
+    })
+}
+
+// Returns whether any setting was given. Choices are validated by Discord, so values that
+// don't parse are ignored
+fn apply_config_options(config: &mut GuildConfig, options: &[DiscordInteractionOptions]) -> bool {
+    let mut is_updated = false;
+
+    if let Some(x) = get_string_option("spoilers", options) {
+        config.max_spoiler_chapter = Chapter::from_str(x).ok();
+        is_updated = true;
+    }
+
+    if let Some(x) = get_string_option("locale", options) {
+        config.default_locale = BinahBotLocale::from_str(x).ok();
+        is_updated = true;
+    }
+
+    if let Some(x) = get_string_option("privacy", options) {
+        config.default_private = match x {
+            PRIVATE_CHOICE => Some(true),
+            PUBLIC_CHOICE => Some(false),
+            _ => None,
+        };
+        is_updated = true;
+    }
+
+    if let Some(x) = get_string_option("disable", options) {
+        if !config.disabled_commands.iter().any(|y| y == x) {
+            config.disabled_commands.push(x.to_string());
+        }
+        is_updated = true;
+    }
+
+    if let Some(x) = get_string_option("enable", options) {
+        config.disabled_commands.retain(|y| y != x);
+        is_updated = true;
+    }
+
+    is_updated
+}
+
+fn get_string_option<'a>(
+    name: &'a str,
+    options: &'a [DiscordInteractionOptions],
+) -> Option<&'a str> {
+    get_option_value(name, options)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
+        .map(|x| x.as_str())
+}
+
+fn build_config_embed(
+    scope: &ConfigScope,
+    config: &GuildConfig,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> DiscordEmbed {
+    let not_set = || env.locales.lookup(lang_id, "config_not_set");
+    let choice_name = |x: String| get_choice_display_name(&x, lang_id, env);
+
+    let title = match scope {
+        ConfigScope::Channel(x) => env.locales.lookup_with_args(
+            lang_id,
+            "config_channel_title",
+            &HashMap::from([("channel", FluentValue::from(format!("<#{}>", x)))]),
+        ),
+        _ => env.locales.lookup(lang_id, "config_guild_title"),
+    };
+
+    let fields = [
+        (
+            "config_spoilers_field",
+            config
+                .max_spoiler_chapter
+                .as_ref()
+                .map(|x| choice_name(x.to_string())),
+        ),
+        (
+            "config_locale_field",
+            config
+                .default_locale
+                .as_ref()
+                .map(|x| choice_name(x.to_string())),
+        ),
+        (
+            "config_privacy_field",
+            config
+                .default_private
+                .map(|x| choice_name(if x { PRIVATE_CHOICE } else { PUBLIC_CHOICE }.to_string())),
+        ),
+        (
+            "config_disabled_commands_field",
+            (!config.disabled_commands.is_empty()).then(|| config.disabled_commands.join(", ")),
+        ),
+    ]
+    .into_iter()
+    .map(|(key, value)| DiscordEmbedFields {
+        name: env.locales.lookup(lang_id, key),
+        value: value.unwrap_or_else(not_set),
+        inline: Some(true),
+    })
+    .collect();
+
+    DiscordEmbed {
+        title: Some(title),
+        description: Some(env.locales.lookup(lang_id, "config_description")),
+        color: Some(DiscordEmbedColors::Default as i32),
+        image: None,
+        thumbnail: None,
+        footer: None,
+        author: None,
+        url: None,
+        fields: Some(fields),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::RESET_CHOICE;
+    use crate::test_utils::build_guild_command_interaction;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn sanity_config_command() {
+        let env = build_mocked_binahbot_env();
+
+        let interaction = build_guild_command_interaction(
+            "config",
+            "32",
+            vec![
+                ("channel", "channel"),
+                ("spoilers", "UrbanPlague"),
+                ("privacy", PRIVATE_CHOICE),
+                ("disable", "lc"),
+            ],
+        );
+        config_command(&interaction, &env).await.unwrap();
+
+        let config = env
+            .config_repository
+            .get_config(&ConfigScope::Channel("channel".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            GuildConfig {
+                max_spoiler_chapter: Some(Chapter::UrbanPlague),
+                default_locale: None,
+                default_private: Some(true),
+                disabled_commands: vec!["lc".to_string()],
+            },
+            config
+        );
+
+        let interaction = build_guild_command_interaction(
+            "config",
+            "32",
+            vec![
+                ("channel", "channel"),
+                ("spoilers", RESET_CHOICE),
+                ("enable", "lc"),
+            ],
+        );
+        config_command(&interaction, &env).await.unwrap();
+
+        let config = env
+            .config_repository
+            .get_config(&ConfigScope::Channel("channel".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            GuildConfig {
+                default_private: Some(true),
+                ..GuildConfig::default()
+            },
+            config
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_config_command_no_permission() {
+        let env = build_mocked_binahbot_env();
+        let lang_id = LanguageIdentifier::from(&BinahBotLocale::EnglishUS);
+
+        let interaction =
+            build_guild_command_interaction("config", "0", vec![("spoilers", "UrbanPlague")]);
+        let response = config_command(&interaction, &env).await.unwrap();
+
+        assert_eq!(
+            format!(
+                "{:?}",
+                build_error_message_response(&lang_id, "config_no_permission_error_message", &env)
+            ),
+            format!("{:?}", response)
+        );
+        assert_eq!(
+            GuildConfig::default(),
+            env.config_repository
+                .get_config(&ConfigScope::Guild("guild".to_string()))
+                .await
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::Loader;
+use ruina::ruina_common::localizations::common::Locale;
+use ruina::ruina_index::models::ParsedTypedId;
+use unic_langid::LanguageIdentifier;
+
+use crate::deck::read_deck::calculate_deck_chapter;
+use crate::deck::read_deck::spoiler_found;
+use crate::deck::read_deck::transform_deck;
+use crate::error::BinahBotError;
+use crate::guild_config::find_exceeded_spoiler_limit;
+use crate::guild_config::get_spoiler_limits;
+use crate::lor::command::build_lor_response;
+use crate::lor::lookup::lookup;
+use crate::lor::lookup::page_exists;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::deck::Deck;
+use crate::models::deck::TiphDeck;
+use crate::models::discord::AllowedMentions;
+use crate::models::discord::DiscordApplicationCommandInteractionData;
+use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::DiscordUser;
+use crate::models::discord::MessageResponse;
+use crate::thumbnail::generate_thumbnail;
+use crate::tiph::decode;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_data;
+use crate::utils::get_interaction_user;
+use crate::utils::get_reqwest_client;
+use crate::utils::parse_tiph_deck_id;
+use crate::utils::TIPH_DECK_URL;
+
+static DEFAULT_TIPH_DECK_VERSION: i32 = 1;
+// same limit as the lor command's query option
+const MAX_QUERY_LENGTH: usize = 100;
+
+// Context menu responses are only shown to the user who invoked them, so they aren't
+// subject to the channel's spoiler settings, only to the user's progress
+
+pub async fn lookup_message_command(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let request_locale = get_binahbot_locale(interaction);
+    let lang_id = LanguageIdentifier::from(&request_locale);
+
+    let data = get_command_data(interaction)?;
+    let message = data
+        .target_id
+        .as_ref()
+        .and_then(|x| {
+            data.resolved
+                .as_ref()
+                .and_then(|y| y.messages.as_ref())
+                .and_then(|y| y.get(x))
+        })
+        .ok_or(BinahBotError::MissingInteractionData)?;
+    let content = message.content.as_deref().unwrap_or("");
+
+    if let Some(link) = find_tiph_deck_link(content) {
+        let author = match message.author.as_ref() {
+            Some(x) => x,
+            None => get_interaction_user(interaction)?,
+        };
+        return preview_tiph_deck(link, author, &lang_id, interaction, env).await;
+    }
+
+    let locale = Locale::from(&request_locale);
+    Ok(match find_page(content, &locale) {
+        Some(x) => build_lor_response(interaction, &x, &locale, true, env).await,
+        None => build_error_message_response(&lang_id, "no_page_error_message", env),
+    })
+}
+
+pub async fn user_decks_command(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+
+    let data = get_command_data(interaction)?;
+    let user = get_target_user(data).ok_or(BinahBotError::MissingInteractionData)?;
+
+    let decks = match env.deck_repository.list_decks(Some(&user.id), None).await {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "generic_error_message",
+                env,
+            ))
+        }
+    };
+
+    let user_arg = HashMap::from([("user", FluentValue::from(format!("@{}", user.username)))]);
+    let description = if decks.is_empty() {
+        env.locales
+            .lookup_with_args(&lang_id, "user_decks_none", &user_arg)
+    } else {
+        decks
+            .iter()
+            .map(|x| format!("- {}", x.name))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    Ok(build_response(DiscordEmbed {
+        title: Some(
+            env.locales
+                .lookup_with_args(&lang_id, "user_decks_title", &user_arg),
+        ),
+        description: Some(description),
+        color: Some(DiscordEmbedColors::Default as i32),
+        image: None,
+        thumbnail: None,
+        footer: None,
+        author: None,
+        url: None,
+        fields: None,
+    }))
+}
+
+// Shows a tiphereth deck as if it were saved, without saving it
+async fn preview_tiph_deck(
+    link: &str,
+    author: &DiscordUser,
+    lang_id: &LanguageIdentifier,
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let tiph_deck = TiphDeck(parse_tiph_deck_id(link), DEFAULT_TIPH_DECK_VERSION);
+    let deck_data = match decode(get_reqwest_client(env)?, &tiph_deck).await {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                lang_id,
+                "cant_parse_deck_error_message",
+                env,
+            ))
+        }
+    };
+
+    let chapter = calculate_deck_chapter(&deck_data);
+    let spoiler_limits = get_spoiler_limits(interaction, true, env).await;
+    if let Some(spoiler_limit) = find_exceeded_spoiler_limit(&chapter, &spoiler_limits) {
+        return Ok(spoiler_found(
+            &tiph_deck.0,
+            &chapter,
+            spoiler_limit,
+            lang_id,
+            env,
+        ));
+    }
+
+    let deck = Deck {
+        name: tiph_deck.0.clone(),
+        author_id: author.id.clone(),
+        author_name: author.username.clone(),
+        description: None,
+        deck_data,
+        tiph_deck: Some(tiph_deck),
+        version: 1,
+        tags: Vec::new(),
+        forked_from: None,
+        owner_id: None,
+        editors: Vec::new(),
+    };
+
+    let _ = generate_thumbnail(
+        env.lambda_client.as_ref(),
+        &env.thumbnail_lambda_name,
+        &deck,
+        &Locale::from(&get_binahbot_locale(interaction)),
+    )
+    .await;
+
+    Ok(
+        match transform_deck(&deck, &get_binahbot_locale(interaction), env).await {
+            Ok(x) => build_response(x),
+            Err(_) => build_error_message_response(lang_id, "generic_error_message", env),
+        },
+    )
+}
+
+fn find_tiph_deck_link(content: &str) -> Option<&str> {
+    content
+        .split_whitespace()
+        .map(|x| x.trim_matches(|y| y == '<' || y == '>'))
+        .find(|x| x.starts_with(TIPH_DECK_URL) && !parse_tiph_deck_id(x).is_empty())
+}
+
+// only the first line is considered, since a whole message rarely matches a page name
+fn find_page(content: &str, locale: &Locale) -> Option<ParsedTypedId> {
+    let query = content.lines().map(|x| x.trim()).find(|x| !x.is_empty())?;
+    let query = match query.char_indices().nth(MAX_QUERY_LENGTH) {
+        Some((i, _)) => &query[..i],
+        None => query,
+    };
+
+    match ParsedTypedId::from_str(query) {
+        Ok(x) => Some(x).filter(page_exists),
+        Err(_) => lookup(query, locale, false).next(),
+    }
+}
+
+fn get_target_user(data: &DiscordApplicationCommandInteractionData) -> Option<&DiscordUser> {
+    data.resolved
+        .as_ref()
+        .and_then(|x| x.users.as_ref())
+        .and_then(|x| x.get(data.target_id.as_ref()?))
+}
+
+fn build_response(embed: DiscordEmbed) -> MessageResponse {
+    MessageResponse {
+        r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+            content: None,
+            embeds: Some(vec![embed]),
+            flags: Some(DiscordMessageFlag::EphemeralMessage as i32),
+            components: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lor::command::lor_command;
+    use crate::models::deck::DeckData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordInteractionData;
+    use crate::models::discord::DiscordInteractionOptionValue;
+    use crate::models::discord::DiscordInteractionOptions;
+    use crate::models::discord::DiscordInteractionType;
+    use crate::models::discord::DiscordMessage;
+    use crate::models::discord::DiscordResolvedData;
+    use crate::test_utils::build_deck;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    #[test]
+    fn sanity_find_tiph_deck_link() {
+        assert_eq!(
+            Some("https://tiphereth.zasz.su/u/decks/CS-iRmsieV9/"),
+            find_tiph_deck_link("try this <https://tiphereth.zasz.su/u/decks/CS-iRmsieV9/>")
+        );
+        assert_eq!(
+            None,
+            find_tiph_deck_link("https://tiphereth.zasz.su/u/decks/")
+        );
+        assert_eq!(None, find_tiph_deck_link("Weight of Sin"));
+    }
+
+    #[tokio::test]
+    async fn sanity_lookup_message_command() {
+        let env = build_mocked_binahbot_env();
+        let message = DiscordMessage {
+            content: Some("a#LongBird_Sin\nis it any good?".to_string()),
+            author: Some(build_user("author")),
+            interaction_metadata: None,
+        };
+        let interaction = build_discord_interaction(
+            DiscordApplicationCommandType::Message,
+            DiscordResolvedData {
+                users: None,
+                messages: Some(HashMap::from([("target".to_string(), message)])),
+            },
+        );
+
+        let response = lookup_message_command(&interaction, &env).await.unwrap();
+        let expected = lor_command(&build_lor_interaction("a#LongBird_Sin"), &env)
+            .await
+            .unwrap();
+
+        let data = response.data.unwrap();
+        assert_eq!(
+            Some(DiscordMessageFlag::EphemeralMessage as i32),
+            data.flags
+        );
+        assert_eq!(
+            expected.data.unwrap().embeds.unwrap()[0].title,
+            data.embeds.unwrap()[0].title
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_user_decks_command() {
+        let env = build_mocked_binahbot_env();
+        let deck = build_deck(
+            "target",
+            "Turbo Nikolai",
+            DeckData {
+                keypage_id: Some("250023".to_string()),
+                passive_ids: Vec::new(),
+                combat_page_ids: std::array::from_fn(|_| None),
+            },
+        );
+        env.deck_repository.put_deck(&deck, None).await.unwrap();
+        let interaction = build_discord_interaction(
+            DiscordApplicationCommandType::User,
+            DiscordResolvedData {
+                users: Some(HashMap::from([(
+                    "target".to_string(),
+                    DiscordUser {
+                        id: "target".to_string(),
+                        username: "gh".to_string(),
+                        avatar: None,
+                    },
+                )])),
+                messages: None,
+            },
+        );
+
+        let response = user_decks_command(&interaction, &env).await.unwrap();
+
+        let embed = &response.data.unwrap().embeds.unwrap()[0];
+        assert_eq!(Some("Decks by \u{2068}@gh\u{2069}"), embed.title.as_deref());
+        assert_eq!(Some("- Turbo Nikolai"), embed.description.as_deref());
+    }
+
+    fn build_user(username: &str) -> DiscordUser {
+        DiscordUser {
+            id: username.to_string(),
+            username: username.to_string(),
+            avatar: None,
+        }
+    }
+
+    fn build_discord_interaction(
+        command_type: DiscordApplicationCommandType,
+        resolved: DiscordResolvedData,
+    ) -> DiscordInteraction {
+        build_interaction(DiscordApplicationCommandInteractionData {
+            id: "id".to_string(),
+            name: "context menu".to_string(),
+            r#type: Some(command_type),
+            options: None,
+            target_id: Some("target".to_string()),
+            resolved: Some(resolved),
+        })
+    }
+
+    fn build_lor_interaction(query: &str) -> DiscordInteraction {
+        build_interaction(DiscordApplicationCommandInteractionData {
+            id: "id".to_string(),
+            name: "lor".to_string(),
+            r#type: Some(DiscordApplicationCommandType::ChatInput),
+            options: Some(vec![DiscordInteractionOptions {
+                name: "query".to_string(),
+                name_localizations: None,
+                value: DiscordInteractionOptionValue::String(query.to_string()),
+                focused: None,
+            }]),
+            target_id: None,
+            resolved: None,
+        })
+    }
+
+    fn build_interaction(data: DiscordApplicationCommandInteractionData) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommand,
+            data: Some(DiscordInteractionData::ApplicationCommand(data)),
+            channel_id: None,
+            guild_id: None,
+            token: "token".to_string(),
+            locale: None,
+            guild_locale: None,
+            user: Some(build_user("snowflake")),
+            member: None,
+            message: None,
+        }
+    }
+}
@@ -2,11 +2,20 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::string::String;
 
+use crate::models::binahbot::BinahBotLocale;
 use crate::models::binahbot::InteractionTtl;
+use crate::models::config::ConfigScope;
+use crate::models::config::GuildConfig;
 use crate::models::deck::Deck;
+use crate::models::deck::DeckIndexEntry;
 use crate::models::deck::DeckMetadata;
+use crate::models::deck::DeckVersion;
+use crate::models::deck::DeckVote;
+use crate::models::deck::DeckVoteTotals;
+use crate::models::deck::PendingDeck;
 use crate::models::deck::TiphDeck;
 use aws_sdk_dynamodb::types::AttributeValue;
+use ruina::ruina_common::game_objects::common::Chapter;
 
 // todo: https://crates.io/crates/snafu
 pub async fn get_deck(
@@ -33,22 +42,29 @@ pub async fn put_deck(
     client: &aws_sdk_dynamodb::Client,
     table_name: &str,
     deck: &Deck,
-    overwrite: bool,
+    previous_version: Option<u32>,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
-    tracing::info!("Calling PutDeck with deck={:?}", deck);
-    let condition_expression = if overwrite {
-        None
-    } else {
-        Some("attribute_not_exists(author)".to_string())
-    };
-    Ok(client
+    tracing::info!(
+        "Calling PutDeck with deck={:?}, previous_version={:?}",
+        deck,
+        previous_version
+    );
+    let request = client
         .put_item()
         .table_name(table_name)
-        .set_item(HashMap::<String, AttributeValue>::try_from(deck).ok())
-        .set_condition_expression(condition_expression)
-        .send()
-        .await
-        .map(|_| ())?)
+        .set_item(HashMap::<String, AttributeValue>::try_from(deck).ok());
+    let request = match previous_version {
+        // decks stored before versioning have no version and are read as the first one
+        Some(x) => request
+            .condition_expression(match x {
+                1 => "attribute_not_exists(#version) OR #version = :previous_version",
+                _ => "#version = :previous_version",
+            })
+            .expression_attribute_names("#version", "version")
+            .expression_attribute_values(":previous_version", AttributeValue::N(x.to_string())),
+        None => request.condition_expression("attribute_not_exists(author)"),
+    };
+    Ok(request.send().await.map(|_| ())?)
 }
 
 pub async fn list_decks(
@@ -129,6 +145,555 @@ pub async fn delete_deck(
         .map(|_| ())?)
 }
 
+pub async fn put_deck_version(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    deck_version: &DeckVersion,
+    overwrite: bool,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling PutDeckVersion with deck_version={:?}",
+        deck_version
+    );
+    let condition_expression = if overwrite {
+        None
+    } else {
+        Some("attribute_not_exists(deck_key)".to_string())
+    };
+    Ok(client
+        .put_item()
+        .table_name(table_name)
+        .set_item(HashMap::<String, AttributeValue>::try_from(deck_version).ok())
+        .set_condition_expression(condition_expression)
+        .send()
+        .await
+        .map(|_| ())?)
+}
+
+pub async fn list_deck_versions(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    name: &str,
+    author: &str,
+) -> Result<Vec<DeckVersion>, Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling ListDeckVersions with name={}; author={}",
+        name,
+        author
+    );
+
+    let items: Vec<HashMap<String, AttributeValue>> = client
+        .query()
+        .table_name(table_name)
+        .key_condition_expression("deck_key = :deck_key")
+        .expression_attribute_values(":deck_key", AttributeValue::S(get_deck_key(author, name)))
+        .into_paginator()
+        .items()
+        .send()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    items
+        .iter()
+        .map(|x| -> Result<DeckVersion, Box<dyn Error + Send + Sync>> { DeckVersion::try_from(x) })
+        .collect()
+}
+
+pub async fn delete_deck_versions(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    name: &str,
+    author: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling DeleteDeckVersions with name={}; author={}",
+        name,
+        author
+    );
+
+    let versions = list_deck_versions(client, table_name, name, author).await?;
+    for deck_version in versions {
+        client
+            .delete_item()
+            .table_name(table_name)
+            .key("deck_key", AttributeValue::S(get_deck_key(author, name)))
+            .key(
+                "version",
+                AttributeValue::N(deck_version.deck.version.to_string()),
+            )
+            .send()
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn get_deck_key(author: &str, name: &str) -> String {
+    format!("{}#{}", author, name)
+}
+
+pub async fn put_deck_index(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    deck: &Deck,
+    index_keys: &[String],
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling PutDeckIndex with name={}; author={}; index_keys={:?}",
+        deck.name,
+        deck.author_id,
+        index_keys
+    );
+
+    let stale_keys = list_deck_index_keys(client, table_name, &deck.name, &deck.author_id)
+        .await?
+        .into_iter()
+        .filter(|x| !index_keys.contains(x));
+    for index_key in stale_keys {
+        delete_deck_index_entry(client, table_name, &deck.name, &deck.author_id, &index_key)
+            .await?;
+    }
+
+    for index_key in index_keys {
+        let entry = DeckIndexEntry {
+            index_key: index_key.clone(),
+            metadata: DeckMetadata::from(deck),
+        };
+        put_deck_index_entry(client, table_name, &entry).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn put_deck_index_entry(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    entry: &DeckIndexEntry,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    Ok(client
+        .put_item()
+        .table_name(table_name)
+        .set_item(HashMap::<String, AttributeValue>::try_from(entry).ok())
+        .send()
+        .await
+        .map(|_| ())?)
+}
+
+pub async fn delete_deck_index(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    name: &str,
+    author: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling DeleteDeckIndex with name={}; author={}",
+        name,
+        author
+    );
+
+    for index_key in list_deck_index_keys(client, table_name, name, author).await? {
+        delete_deck_index_entry(client, table_name, name, author, &index_key).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn search_deck_index(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    index_key: &str,
+) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+    tracing::info!("Calling SearchDeckIndex with index_key={}", index_key);
+
+    let items: Vec<HashMap<String, AttributeValue>> = client
+        .query()
+        .table_name(table_name)
+        .index_name("gsi1")
+        .key_condition_expression("index_key = :index_key")
+        .expression_attribute_values(":index_key", AttributeValue::S(index_key.to_string()))
+        .projection_expression("author, author_name, deck_name")
+        .into_paginator()
+        .items()
+        .send()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    items
+        .iter()
+        .map(|x| -> Result<DeckMetadata, Box<dyn Error + Send + Sync>> {
+            DeckMetadata::try_from(x)
+        })
+        .collect()
+}
+
+async fn list_deck_index_keys(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    name: &str,
+    author: &str,
+) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+    let items: Vec<HashMap<String, AttributeValue>> = client
+        .query()
+        .table_name(table_name)
+        .key_condition_expression("deck_key = :deck_key")
+        .expression_attribute_values(":deck_key", AttributeValue::S(get_deck_key(author, name)))
+        .projection_expression("index_key")
+        .into_paginator()
+        .items()
+        .send()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    items
+        .iter()
+        .map(|x| -> Result<String, Box<dyn Error + Send + Sync>> {
+            Ok(x.get("index_key")
+                .ok_or("no index key")?
+                .as_s()
+                .map_err(failed_attributevalue_cast)?
+                .clone())
+        })
+        .collect()
+}
+
+async fn delete_deck_index_entry(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    name: &str,
+    author: &str,
+    index_key: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    Ok(client
+        .delete_item()
+        .table_name(table_name)
+        .key("deck_key", AttributeValue::S(get_deck_key(author, name)))
+        .key("index_key", AttributeValue::S(index_key.to_string()))
+        .send()
+        .await
+        .map(|_| ())?)
+}
+
+// vote totals share the votes partition, under a sort key no user id can collide with. Only
+// totals items set the "totals" attribute, so the sparse gsi1 index keyed on it lists just them
+const DECK_VOTE_TOTALS_KEY: &str = "#totals";
+
+pub async fn get_deck_vote(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    name: &str,
+    author: &str,
+    user_id: &str,
+) -> Result<DeckVote, Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling GetDeckVote with name={}; author={}; user_id={}",
+        name,
+        author,
+        user_id
+    );
+    let binding = client
+        .get_item()
+        .table_name(table_name)
+        .key("deck_key", AttributeValue::S(get_deck_key(author, name)))
+        .key("user_id", AttributeValue::S(user_id.to_string()))
+        .send()
+        .await?;
+
+    Ok(binding.item().map(DeckVote::from).unwrap_or_default())
+}
+
+pub async fn put_deck_vote(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    name: &str,
+    author: &str,
+    user_id: &str,
+    vote: &DeckVote,
+    previous_vote: &DeckVote,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling PutDeckVote with name={}; author={}; user_id={}; vote={:?}; previous_vote={:?}",
+        name,
+        author,
+        user_id,
+        vote,
+        previous_vote
+    );
+    // users who never voted have no item, which counts as the default vote
+    let condition_expression = if *previous_vote == DeckVote::default() {
+        "attribute_not_exists(user_id) OR (upvote = :previous_upvote AND favorite = :previous_favorite)"
+    } else {
+        "upvote = :previous_upvote AND favorite = :previous_favorite"
+    };
+    Ok(client
+        .put_item()
+        .table_name(table_name)
+        .item("deck_key", AttributeValue::S(get_deck_key(author, name)))
+        .item("user_id", AttributeValue::S(user_id.to_string()))
+        .item("upvote", AttributeValue::Bool(vote.upvote))
+        .item("favorite", AttributeValue::Bool(vote.favorite))
+        .condition_expression(condition_expression)
+        .expression_attribute_values(
+            ":previous_upvote",
+            AttributeValue::Bool(previous_vote.upvote),
+        )
+        .expression_attribute_values(
+            ":previous_favorite",
+            AttributeValue::Bool(previous_vote.favorite),
+        )
+        .send()
+        .await
+        .map(|_| ())?)
+}
+
+pub async fn update_deck_vote_totals(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    metadata: &DeckMetadata,
+    upvote_delta: i64,
+    favorite_delta: i64,
+) -> Result<DeckVoteTotals, Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling UpdateDeckVoteTotals with metadata={:?}; upvote_delta={}; favorite_delta={}",
+        metadata,
+        upvote_delta,
+        favorite_delta
+    );
+    let binding = client
+        .update_item()
+        .table_name(table_name)
+        .key(
+            "deck_key",
+            AttributeValue::S(get_deck_key(&metadata.author_id, &metadata.name)),
+        )
+        .key("user_id", AttributeValue::S(DECK_VOTE_TOTALS_KEY.to_string()))
+        .update_expression(
+            "ADD upvotes :upvote_delta, favorites :favorite_delta SET author = :author, author_name = :author_name, deck_name = :deck_name, totals = :totals",
+        )
+        .expression_attribute_values(
+            ":totals",
+            AttributeValue::S(DECK_VOTE_TOTALS_KEY.to_string()),
+        )
+        .expression_attribute_values(":upvote_delta", AttributeValue::N(upvote_delta.to_string()))
+        .expression_attribute_values(
+            ":favorite_delta",
+            AttributeValue::N(favorite_delta.to_string()),
+        )
+        .expression_attribute_values(":author", AttributeValue::S(metadata.author_id.clone()))
+        .expression_attribute_values(
+            ":author_name",
+            AttributeValue::S(metadata.author_name.clone()),
+        )
+        .expression_attribute_values(":deck_name", AttributeValue::S(metadata.name.clone()))
+        .return_values(aws_sdk_dynamodb::types::ReturnValue::AllNew)
+        .send()
+        .await?;
+
+    DeckVoteTotals::try_from(binding.attributes().ok_or("no attributes returned")?)
+}
+
+pub async fn list_deck_vote_totals(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+) -> Result<Vec<DeckVoteTotals>, Box<dyn Error + Send + Sync>> {
+    tracing::info!("Calling ListDeckVoteTotals");
+
+    let items: Vec<HashMap<String, AttributeValue>> = client
+        .query()
+        .table_name(table_name)
+        .index_name("gsi1")
+        .key_condition_expression("totals = :totals")
+        .expression_attribute_values(
+            ":totals",
+            AttributeValue::S(DECK_VOTE_TOTALS_KEY.to_string()),
+        )
+        .into_paginator()
+        .items()
+        .send()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    items
+        .iter()
+        .map(
+            |x| -> Result<DeckVoteTotals, Box<dyn Error + Send + Sync>> {
+                DeckVoteTotals::try_from(x)
+            },
+        )
+        .collect()
+}
+
+pub async fn delete_deck_votes(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    name: &str,
+    author: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling DeleteDeckVotes with name={}; author={}",
+        name,
+        author
+    );
+
+    let items: Vec<HashMap<String, AttributeValue>> = client
+        .query()
+        .table_name(table_name)
+        .key_condition_expression("deck_key = :deck_key")
+        .expression_attribute_values(":deck_key", AttributeValue::S(get_deck_key(author, name)))
+        .projection_expression("user_id")
+        .into_paginator()
+        .items()
+        .send()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for user_id in items.iter().filter_map(|x| x.get("user_id")) {
+        client
+            .delete_item()
+            .table_name(table_name)
+            .key("deck_key", AttributeValue::S(get_deck_key(author, name)))
+            .key("user_id", user_id.clone())
+            .send()
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn put_guild_collection_deck(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    guild_id: &str,
+    metadata: &DeckMetadata,
+    added_by: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling PutGuildCollectionDeck with guild_id={}; metadata={:?}; added_by={}",
+        guild_id,
+        metadata,
+        added_by
+    );
+    Ok(client
+        .put_item()
+        .table_name(table_name)
+        .item("guild_id", AttributeValue::S(guild_id.to_string()))
+        .item(
+            "deck_key",
+            AttributeValue::S(get_deck_key(&metadata.author_id, &metadata.name)),
+        )
+        .item("author", AttributeValue::S(metadata.author_id.clone()))
+        .item(
+            "author_name",
+            AttributeValue::S(metadata.author_name.clone()),
+        )
+        .item("deck_name", AttributeValue::S(metadata.name.clone()))
+        .item("added_by", AttributeValue::S(added_by.to_string()))
+        .send()
+        .await
+        .map(|_| ())?)
+}
+
+pub async fn delete_guild_collection_deck(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    guild_id: &str,
+    name: &str,
+    author: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling DeleteGuildCollectionDeck with guild_id={}; name={}; author={}",
+        guild_id,
+        name,
+        author
+    );
+    Ok(client
+        .delete_item()
+        .table_name(table_name)
+        .key("guild_id", AttributeValue::S(guild_id.to_string()))
+        .key("deck_key", AttributeValue::S(get_deck_key(author, name)))
+        .send()
+        .await
+        .map(|_| ())?)
+}
+
+pub async fn list_guild_collection(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    guild_id: &str,
+) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+    tracing::info!("Calling ListGuildCollection with guild_id={}", guild_id);
+
+    let items: Vec<HashMap<String, AttributeValue>> = client
+        .query()
+        .table_name(table_name)
+        .key_condition_expression("guild_id = :guild_id")
+        .expression_attribute_values(":guild_id", AttributeValue::S(guild_id.to_string()))
+        .projection_expression("author, author_name, deck_name")
+        .into_paginator()
+        .items()
+        .send()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    items
+        .iter()
+        .map(|x| -> Result<DeckMetadata, Box<dyn Error + Send + Sync>> {
+            DeckMetadata::try_from(x)
+        })
+        .collect()
+}
+
+pub async fn delete_deck_from_guild_collections(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    name: &str,
+    author: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling DeleteDeckFromGuildCollections with name={}; author={}",
+        name,
+        author
+    );
+
+    let items: Vec<HashMap<String, AttributeValue>> = client
+        .query()
+        .table_name(table_name)
+        .index_name("gsi1")
+        .key_condition_expression("deck_key = :deck_key")
+        .expression_attribute_values(":deck_key", AttributeValue::S(get_deck_key(author, name)))
+        .projection_expression("guild_id")
+        .into_paginator()
+        .items()
+        .send()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for guild_id in items.iter().filter_map(|x| x.get("guild_id")) {
+        client
+            .delete_item()
+            .table_name(table_name)
+            .key("guild_id", guild_id.clone())
+            .key("deck_key", AttributeValue::S(get_deck_key(author, name)))
+            .send()
+            .await?;
+    }
+
+    Ok(())
+}
+
 pub async fn put_interaction_token(
     client: &aws_sdk_dynamodb::Client,
     table_name: &str,
@@ -171,6 +736,91 @@ pub async fn get_interaction_token(
     InteractionTtl::try_from(interaction_ttl)
 }
 
+pub async fn put_pending_deck(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    pending_deck: &PendingDeck,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling PutPendingDeck with pending_deck={:?}",
+        pending_deck
+    );
+    Ok(client
+        .put_item()
+        .table_name(table_name)
+        .set_item(HashMap::<String, AttributeValue>::try_from(pending_deck).ok())
+        .send()
+        .await
+        .map(|_| ())?)
+}
+
+pub async fn get_pending_deck(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    interaction_id: &str,
+) -> Result<PendingDeck, Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling GetPendingDeck with interaction_id={:?}",
+        interaction_id
+    );
+
+    let binding = client
+        .get_item()
+        .table_name(table_name)
+        .key(
+            "interaction_id",
+            AttributeValue::S(get_pending_deck_key(interaction_id)),
+        )
+        .send()
+        .await?;
+    let pending_deck = binding.item().ok_or("could not get pending deck")?;
+
+    PendingDeck::try_from(pending_deck)
+}
+
+// pending decks share the interaction ttl table, so they are keyed separately from interaction tokens
+fn get_pending_deck_key(interaction_id: &str) -> String {
+    format!("pending#{}", interaction_id)
+}
+
+pub async fn get_guild_config(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    scope: &ConfigScope,
+) -> Result<GuildConfig, Box<dyn Error + Send + Sync>> {
+    tracing::info!("Calling GetGuildConfig with scope={:?}", scope);
+    let binding = client
+        .get_item()
+        .table_name(table_name)
+        .key("scope", AttributeValue::S(scope.key()))
+        .send()
+        .await?;
+
+    Ok(binding.item().map(GuildConfig::from).unwrap_or_default())
+}
+
+pub async fn put_guild_config(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: &str,
+    scope: &ConfigScope,
+    config: &GuildConfig,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    tracing::info!(
+        "Calling PutGuildConfig with scope={:?}; config={:?}",
+        scope,
+        config
+    );
+    let mut item = HashMap::<String, AttributeValue>::from(config);
+    item.insert("scope".to_string(), AttributeValue::S(scope.key()));
+    Ok(client
+        .put_item()
+        .table_name(table_name)
+        .set_item(Some(item))
+        .send()
+        .await
+        .map(|_| ())?)
+}
+
 fn failed_attributevalue_cast(_: &AttributeValue) -> String {
     "".to_string()
 }
@@ -222,6 +872,28 @@ impl TryFrom<&HashMap<String, AttributeValue>> for Deck {
                 _ => None,
             },
             description: description.cloned(),
+            // decks created before versioning was introduced are treated as the first version
+            version: value
+                .get("version")
+                .and_then(|x| x.as_n().ok())
+                .and_then(|x| x.parse().ok())
+                .unwrap_or(1),
+            tags: value
+                .get("tags")
+                .and_then(|x| x.as_l().ok())
+                .map(|x| x.iter().filter_map(|y| y.as_s().ok()).cloned().collect())
+                .unwrap_or_default(),
+            forked_from: value
+                .get("forked_from")
+                .and_then(|x| x.as_s().ok())
+                .map(|x| serde_json::from_str(x))
+                .transpose()?,
+            owner_id: value.get("owner").and_then(|x| x.as_s().ok()).cloned(),
+            editors: value
+                .get("editors")
+                .and_then(|x| x.as_l().ok())
+                .map(|x| x.iter().filter_map(|y| y.as_s().ok()).cloned().collect())
+                .unwrap_or_default(),
         })
     }
 }
@@ -272,6 +944,30 @@ impl TryFrom<&Deck> for HashMap<String, AttributeValue> {
                     .map(|x| AttributeValue::S(x.clone()))
                     .unwrap_or(AttributeValue::Null(true)),
             ),
+            (
+                "version".to_string(),
+                AttributeValue::N(value.version.to_string()),
+            ),
+            (
+                "tags".to_string(),
+                AttributeValue::L(
+                    value
+                        .tags
+                        .iter()
+                        .map(|x| AttributeValue::S(x.clone()))
+                        .collect(),
+                ),
+            ),
+            (
+                "editors".to_string(),
+                AttributeValue::L(
+                    value
+                        .editors
+                        .iter()
+                        .map(|x| AttributeValue::S(x.clone()))
+                        .collect(),
+                ),
+            ),
         ]);
 
         if let Some(tiph) = value.tiph_deck.as_ref() {
@@ -282,6 +978,17 @@ impl TryFrom<&Deck> for HashMap<String, AttributeValue> {
             );
         }
 
+        if let Some(owner_id) = value.owner_id.as_ref() {
+            hm.insert("owner".to_string(), AttributeValue::S(owner_id.clone()));
+        }
+
+        if let Some(origin) = value.forked_from.as_ref() {
+            hm.insert(
+                "forked_from".to_string(),
+                AttributeValue::S(serde_json::to_string(origin)?),
+            );
+        }
+
         Ok(hm)
     }
 }
@@ -313,6 +1020,74 @@ impl TryFrom<&HashMap<String, AttributeValue>> for DeckMetadata {
     }
 }
 
+impl TryFrom<&DeckIndexEntry> for HashMap<String, AttributeValue> {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(value: &DeckIndexEntry) -> Result<Self, Self::Error> {
+        Ok(HashMap::from([
+            (
+                "deck_key".to_string(),
+                AttributeValue::S(get_deck_key(
+                    &value.metadata.author_id,
+                    &value.metadata.name,
+                )),
+            ),
+            (
+                "index_key".to_string(),
+                AttributeValue::S(value.index_key.clone()),
+            ),
+            (
+                "author".to_string(),
+                AttributeValue::S(value.metadata.author_id.clone()),
+            ),
+            (
+                "author_name".to_string(),
+                AttributeValue::S(value.metadata.author_name.clone()),
+            ),
+            (
+                "deck_name".to_string(),
+                AttributeValue::S(value.metadata.name.clone()),
+            ),
+        ]))
+    }
+}
+
+impl From<&HashMap<String, AttributeValue>> for DeckVote {
+    fn from(value: &HashMap<String, AttributeValue>) -> Self {
+        let get_bool = |key: &str| {
+            value
+                .get(key)
+                .and_then(|x| x.as_bool().ok())
+                .is_some_and(|x| *x)
+        };
+
+        DeckVote {
+            upvote: get_bool("upvote"),
+            favorite: get_bool("favorite"),
+        }
+    }
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for DeckVoteTotals {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(value: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        let get_count = |key: &str| {
+            value
+                .get(key)
+                .and_then(|x| x.as_n().ok())
+                .and_then(|x| x.parse().ok())
+                .unwrap_or(0)
+        };
+
+        Ok(DeckVoteTotals {
+            metadata: DeckMetadata::try_from(value)?,
+            upvotes: get_count("upvotes"),
+            favorites: get_count("favorites"),
+        })
+    }
+}
+
 impl TryFrom<&InteractionTtl> for HashMap<String, AttributeValue> {
     type Error = Box<dyn Error + Send + Sync>;
 
@@ -364,3 +1139,168 @@ impl TryFrom<&HashMap<String, AttributeValue>> for InteractionTtl {
         })
     }
 }
+
+impl TryFrom<&PendingDeck> for HashMap<String, AttributeValue> {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(value: &PendingDeck) -> Result<Self, Self::Error> {
+        Ok(HashMap::from([
+            (
+                "interaction_id".to_string(),
+                AttributeValue::S(get_pending_deck_key(&value.interaction_id)),
+            ),
+            ("ttl".to_string(), AttributeValue::N(value.ttl.to_string())),
+            (
+                "pending_deck".to_string(),
+                AttributeValue::S(serde_json::to_string(&value.deck)?),
+            ),
+        ]))
+    }
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for PendingDeck {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(value: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        let key = value
+            .get("interaction_id")
+            .ok_or("no interaction_id")?
+            .as_s()
+            .map_err(failed_attributevalue_cast)?;
+        Ok(PendingDeck {
+            interaction_id: key
+                .strip_prefix(&get_pending_deck_key(""))
+                .unwrap_or(key)
+                .to_string(),
+            ttl: value
+                .get("ttl")
+                .ok_or("no ttl")?
+                .as_n()
+                .map_err(failed_attributevalue_cast)?
+                .parse()?,
+            deck: serde_json::from_str(
+                value
+                    .get("pending_deck")
+                    .ok_or("no pending deck")?
+                    .as_s()
+                    .map_err(failed_attributevalue_cast)?,
+            )?,
+        })
+    }
+}
+
+impl TryFrom<&DeckVersion> for HashMap<String, AttributeValue> {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(value: &DeckVersion) -> Result<Self, Self::Error> {
+        let mut hm = HashMap::from([
+            (
+                "deck_key".to_string(),
+                AttributeValue::S(get_deck_key(&value.deck.author_id, &value.deck.name)),
+            ),
+            (
+                "version".to_string(),
+                AttributeValue::N(value.deck.version.to_string()),
+            ),
+            (
+                "deck".to_string(),
+                AttributeValue::S(serde_json::to_string(&value.deck)?),
+            ),
+        ]);
+
+        if let Some(timestamp) = value.timestamp {
+            hm.insert(
+                "timestamp".to_string(),
+                AttributeValue::N(timestamp.to_string()),
+            );
+        }
+
+        if let Some(editor_id) = value.editor_id.as_ref() {
+            hm.insert(
+                "editor_id".to_string(),
+                AttributeValue::S(editor_id.clone()),
+            );
+        }
+
+        Ok(hm)
+    }
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for DeckVersion {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(value: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        Ok(DeckVersion {
+            deck: serde_json::from_str(
+                value
+                    .get("deck")
+                    .ok_or("no deck")?
+                    .as_s()
+                    .map_err(failed_attributevalue_cast)?,
+            )?,
+            timestamp: value
+                .get("timestamp")
+                .and_then(|x| x.as_n().ok())
+                .and_then(|x| x.parse().ok()),
+            editor_id: value.get("editor_id").and_then(|x| x.as_s().ok()).cloned(),
+        })
+    }
+}
+
+impl From<&HashMap<String, AttributeValue>> for GuildConfig {
+    fn from(value: &HashMap<String, AttributeValue>) -> Self {
+        let get_string = |key: &str| value.get(key).and_then(|x| x.as_s().ok());
+
+        GuildConfig {
+            max_spoiler_chapter: get_string("max_spoiler_chapter")
+                .and_then(|x| x.parse::<Chapter>().ok()),
+            default_locale: get_string("default_locale")
+                .and_then(|x| x.parse::<BinahBotLocale>().ok()),
+            default_private: value
+                .get("default_private")
+                .and_then(|x| x.as_bool().ok())
+                .copied(),
+            disabled_commands: value
+                .get("disabled_commands")
+                .and_then(|x| x.as_l().ok())
+                .map(|x| x.iter().filter_map(|y| y.as_s().ok()).cloned().collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&GuildConfig> for HashMap<String, AttributeValue> {
+    fn from(value: &GuildConfig) -> Self {
+        let mut hm = HashMap::from([(
+            "disabled_commands".to_string(),
+            AttributeValue::L(
+                value
+                    .disabled_commands
+                    .iter()
+                    .map(|x| AttributeValue::S(x.clone()))
+                    .collect(),
+            ),
+        )]);
+
+        if let Some(max_spoiler_chapter) = &value.max_spoiler_chapter {
+            hm.insert(
+                "max_spoiler_chapter".to_string(),
+                AttributeValue::S(max_spoiler_chapter.to_string()),
+            );
+        }
+        if let Some(default_locale) = &value.default_locale {
+            hm.insert(
+                "default_locale".to_string(),
+                AttributeValue::S(default_locale.to_string()),
+            );
+        }
+        if let Some(default_private) = value.default_private {
+            hm.insert(
+                "default_private".to_string(),
+                AttributeValue::Bool(default_private),
+            );
+        }
+
+        hm
+    }
+}
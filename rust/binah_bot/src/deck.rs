@@ -1,6 +1,16 @@
 pub mod create_deck;
+pub mod deck_collection;
+pub mod deck_diff;
+pub mod deck_editors;
+pub mod deck_history;
+pub mod deck_leaderboard;
 mod deck_utils;
+pub mod deck_vote;
 pub mod delete_deck;
+pub mod fork_deck;
 pub mod list_deck;
 pub mod read_deck;
+pub mod reindex_decks;
+pub mod search_deck;
+mod text_import;
 pub mod update_deck;
@@ -2,18 +2,24 @@ use std::collections::HashMap;
 
 use fluent_templates::fluent_bundle::FluentValue;
 use fluent_templates::Loader;
+use ruina::ruina_common::localizations::common::Locale;
 use unic_langid::LanguageIdentifier;
 
-use crate::ddb::put_deck;
+use crate::error::BinahBotError;
 use crate::macros::cast_enum_variant;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::DiscordEmbedColors;
 use crate::models::deck::Deck;
+use crate::models::deck::PendingDeck;
 use crate::models::deck::TiphDeck;
+use crate::models::discord::ActionRowComponent;
 use crate::models::discord::AllowedMentions;
+use crate::models::discord::ButtonComponent;
+use crate::models::discord::ButtonStyle;
+use crate::models::discord::DiscordComponent;
+use crate::models::discord::DiscordComponentType;
 use crate::models::discord::DiscordEmbed;
 use crate::models::discord::DiscordInteraction;
-use crate::models::discord::DiscordInteractionData;
 use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionResponseMessage;
 use crate::models::discord::DiscordInteractionResponseType;
@@ -22,117 +28,595 @@ use crate::models::discord::MessageResponse;
 use crate::thumbnail::generate_thumbnail;
 use crate::tiph::decode;
 use crate::utils::build_error_message_response;
+use crate::utils::build_error_message_response_with_args;
 use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
+use crate::utils::get_component_data;
+use crate::utils::get_disambiguation_format;
+use crate::utils::get_epoch_time;
+use crate::utils::get_interaction_user;
 use crate::utils::get_option_value;
+use crate::utils::get_required_string_option;
+use crate::utils::get_reqwest_client;
 use crate::utils::parse_tiph_deck_id;
 
+use super::deck_utils::parse_tags;
+use super::deck_utils::put_deck_with_history;
 use super::deck_utils::validate_deck;
+use super::text_import::is_text_list;
+use super::text_import::parse_text_list;
+use super::text_import::resolve_entries;
+use super::text_import::SectionLabels;
+use super::text_import::TextImportError;
 
 static DEFAULT_TIPH_DECK_VERSION: i32 = 1;
 
+// format: createdeck#<action>
+pub const CREATE_DECK_BUTTON_PREFIX: &str = "createdeck#";
+const CONFIRM_BUTTON_CUSTOM_ID: &str = "createdeck#confirm";
+const CANCEL_BUTTON_CUSTOM_ID: &str = "createdeck#cancel";
+
+// interaction tokens are only valid for 15 minutes, so there is no point keeping pending decks any longer
+const PENDING_DECK_TTL_SECONDS: u64 = 15 * 60;
+
 pub async fn create_deck(
     interaction: &DiscordInteraction,
     env: &BinahBotEnvironment,
-) -> MessageResponse {
-    let command_args = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-        .and_then(|x| x.options.as_ref())
-        .unwrap();
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
 
-    let tiph_deck_str = get_option_value("deck", command_args)
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
-        .unwrap();
-    let tiph_deck = TiphDeck(parse_tiph_deck_id(tiph_deck_str), DEFAULT_TIPH_DECK_VERSION);
+    let deck_str = get_required_string_option("deck", command_args)?;
 
-    let deck_name = get_option_value("name", command_args)
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
-        .unwrap();
+    let deck_name = get_required_string_option("name", command_args)?;
     let description = get_option_value("description", command_args)
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+    let tags_option = get_option_value("tags", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
 
-    let author = interaction
-        .user
-        .as_ref()
-        .unwrap_or(interaction.member.as_ref().unwrap().user.as_ref().unwrap());
+    let author = get_interaction_user(interaction)?;
 
     let author_id = &author.id;
     let author_name = &author.username;
 
-    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+    let request_locale = get_binahbot_locale(interaction);
+    let lang_id = LanguageIdentifier::from(&request_locale);
+    let card_locale = Locale::from(&request_locale);
 
-    let deck_data_result = decode(
-        env.reqwest_client.as_ref().expect("no reqwest client"),
-        &tiph_deck,
-    )
-    .await;
-
-    let deck_data = match deck_data_result {
-        Ok(x) => x,
+    let tags = match tags_option.map(|x| parse_tags(x)).transpose() {
+        Ok(x) => x.unwrap_or_default(),
         Err(_) => {
-            return build_error_message_response(&lang_id, "cant_parse_deck_error_message", env)
+            return Ok(build_error_message_response(
+                &lang_id,
+                "invalid_tags_error_message",
+                env,
+            ))
+        }
+    };
+
+    let (deck_data, tiph_deck, ambiguous) = if is_text_list(deck_str) {
+        let labels = SectionLabels::new(
+            &env.locales.lookup(&lang_id, "read_deck_keypage_header"),
+            &env.locales.lookup(&lang_id, "read_deck_passives_header"),
+            &env.locales
+                .lookup(&lang_id, "read_deck_combat_pages_header"),
+        );
+        let text_import =
+            parse_text_list(deck_str, &labels).and_then(|x| resolve_entries(&x, &card_locale));
+
+        match text_import {
+            Ok(x) => (x.deck_data, None, x.ambiguous),
+            Err(e) => return Ok(text_import_error(&e, &lang_id, env)),
+        }
+    } else {
+        let tiph_deck = TiphDeck(parse_tiph_deck_id(deck_str), DEFAULT_TIPH_DECK_VERSION);
+        let deck_data_result = decode(get_reqwest_client(env)?, &tiph_deck).await;
+
+        match deck_data_result {
+            Ok(x) => (x, Some(tiph_deck), Vec::new()),
+            Err(_) => {
+                return Ok(build_error_message_response(
+                    &lang_id,
+                    "cant_parse_deck_error_message",
+                    env,
+                ))
+            }
         }
     };
 
     if let Err(e) = validate_deck(&deck_data) {
-        return build_error_message_response(&lang_id, e.as_error_key(), env);
+        return Ok(build_error_message_response(
+            &lang_id,
+            e.as_error_key(),
+            env,
+        ));
     }
 
-    let _ = generate_thumbnail(
-        env.lambda_client.as_ref().expect("no aws lambda client"),
-        &env.thumbnail_lambda_name,
-        &deck_data.combat_page_ids,
-    )
-    .await;
-
     let deck = Deck {
         name: deck_name.to_string(),
         author_id: author_id.to_string(),
         author_name: author_name.to_string(),
         description: description.cloned(),
         deck_data,
-        tiph_deck: Some(tiph_deck),
+        tiph_deck,
+        version: 1,
+        tags,
+        forked_from: None,
+        owner_id: None,
+        editors: Vec::new(),
+    };
+
+    if ambiguous.is_empty() {
+        return Ok(save_deck(&deck, &lang_id, env).await);
+    }
+
+    let pending_deck = PendingDeck {
+        interaction_id: interaction.id.clone(),
+        ttl: get_epoch_time() + PENDING_DECK_TTL_SECONDS,
+        deck,
     };
 
-    let put_deck_result = put_deck(
-        env.ddb_client.as_ref().expect("no ddb client"),
-        &env.ddb_table_name,
-        &deck,
-        false,
+    let put_pending_deck_result = env.interaction_store.put_pending_deck(&pending_deck).await;
+
+    if put_pending_deck_result.is_err() {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "generic_error_message",
+            env,
+        ));
+    }
+
+    let ambiguous_entries = ambiguous
+        .iter()
+        .map(|(query, id)| {
+            env.locales.lookup_with_args(
+                &lang_id,
+                "create_deck_import_ambiguous_entry",
+                &HashMap::from([
+                    ("query", FluentValue::from(query)),
+                    (
+                        "page_name",
+                        FluentValue::from(get_disambiguation_format(
+                            id,
+                            &card_locale,
+                            &lang_id,
+                            env,
+                        )),
+                    ),
+                ]),
+            )
+        })
+        .map(|x| format!("- {}", x))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(MessageResponse {
+        r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+            content: None,
+            embeds: Some(vec![DiscordEmbed {
+                title: Some(pending_deck.deck.name.clone()),
+                description: Some(format!(
+                    "{}\n{}",
+                    env.locales
+                        .lookup(&lang_id, "create_deck_import_confirm_message"),
+                    ambiguous_entries
+                )),
+                color: Some(DiscordEmbedColors::Default as i32),
+                image: None,
+                thumbnail: None,
+                footer: None,
+                author: None,
+                url: None,
+                fields: None,
+            }]),
+            flags: Some(DiscordMessageFlag::EphemeralMessage as i32),
+            components: Some(vec![DiscordComponent::ActionRow(ActionRowComponent {
+                r#type: DiscordComponentType::ActionRow,
+                components: vec![
+                    DiscordComponent::Button(ButtonComponent {
+                        r#type: DiscordComponentType::Button,
+                        style: ButtonStyle::Success,
+                        label: Some(
+                            env.locales
+                                .lookup(&lang_id, "create_deck_import_confirm_button_text"),
+                        ),
+                        custom_id: Some(CONFIRM_BUTTON_CUSTOM_ID.to_string()),
+                        disabled: None,
+                    }),
+                    DiscordComponent::Button(ButtonComponent {
+                        r#type: DiscordComponentType::Button,
+                        style: ButtonStyle::Secondary,
+                        label: Some(
+                            env.locales
+                                .lookup(&lang_id, "create_deck_import_cancel_button_text"),
+                        ),
+                        custom_id: Some(CANCEL_BUTTON_CUSTOM_ID.to_string()),
+                        disabled: None,
+                    }),
+                ],
+            })]),
+        }),
+    })
+}
+
+pub async fn create_deck_button(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let custom_id = &get_component_data(interaction)?.custom_id;
+    let original_interaction_id = &interaction
+        .message
+        .as_ref()
+        .and_then(|x| x.interaction_metadata.as_ref())
+        .ok_or(BinahBotError::MissingMessage)?
+        .id;
+    let user_id = &get_interaction_user(interaction)?.id;
+
+    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+
+    let pending_deck = env
+        .interaction_store
+        .get_pending_deck(original_interaction_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("couldn't get pending deck: {:?}", e);
+            BinahBotError::InteractionExpired
+        })?;
+
+    if &pending_deck.deck.author_id != user_id {
+        return Err(BinahBotError::NotInteractionAuthor);
+    }
+
+    // cancelling is a normal outcome, so it isn't shown as an error
+    let mut response = match custom_id.as_str() {
+        CONFIRM_BUTTON_CUSTOM_ID => save_deck(&pending_deck.deck, &lang_id, env).await,
+        CANCEL_BUTTON_CUSTOM_ID => build_deck_message_response(
+            "create_deck_import_cancelled",
+            &pending_deck.deck,
+            &lang_id,
+            env,
+        ),
+        _ => return Err(BinahBotError::UnknownComponent(custom_id.to_string())),
+    };
+
+    response.r#type = DiscordInteractionResponseType::UpdateMessage;
+    if let Some(data) = response.data.as_mut() {
+        data.components = Some(Vec::new());
+    }
+
+    Ok(response)
+}
+
+async fn save_deck(
+    deck: &Deck,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> MessageResponse {
+    let _ = generate_thumbnail(
+        env.lambda_client.as_ref(),
+        &env.thumbnail_lambda_name,
+        deck,
+        &Locale::from(lang_id),
     )
     .await;
 
+    let put_deck_result = put_deck_with_history(deck, None, &deck.author_id, env).await;
+
     match put_deck_result {
-        Ok(_) => MessageResponse {
-            r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
-            data: Some(DiscordInteractionResponseMessage {
-                allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
-                content: None,
-                embeds: Some(vec![DiscordEmbed {
-                    title: None,
-                    description: Some(env.locales.lookup_with_args(
-                        &lang_id,
-                        "create_deck_success",
-                        &HashMap::from([("deck_name", FluentValue::from(deck_name))]),
-                    )),
-                    color: Some(DiscordEmbedColors::Default as i32),
-                    image: None,
-                    thumbnail: None,
-                    footer: None,
-                    author: None,
-                    url: None,
-                    fields: None,
-                }]),
-                flags: Some(DiscordMessageFlag::EphemeralMessage as i32),
-                components: None,
-            }),
-        },
+        Ok(_) => build_deck_message_response("create_deck_success", deck, lang_id, env),
         Err(_) => {
             // todo: check for error type
-            build_error_message_response(&lang_id, "generic_error_message", env)
+            build_error_message_response(lang_id, "generic_error_message", env)
+        }
+    }
+}
+
+fn build_deck_message_response(
+    message_key: &str,
+    deck: &Deck,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> MessageResponse {
+    MessageResponse {
+        r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+            content: None,
+            embeds: Some(vec![DiscordEmbed {
+                title: None,
+                description: Some(env.locales.lookup_with_args(
+                    lang_id,
+                    message_key,
+                    &HashMap::from([("deck_name", FluentValue::from(&deck.name))]),
+                )),
+                color: Some(DiscordEmbedColors::Default as i32),
+                image: None,
+                thumbnail: None,
+                footer: None,
+                author: None,
+                url: None,
+                fields: None,
+            }]),
+            flags: Some(DiscordMessageFlag::EphemeralMessage as i32),
+            components: None,
+        }),
+    }
+}
+
+fn text_import_error(
+    error: &TextImportError,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> MessageResponse {
+    build_error_message_response_with_args(
+        lang_id,
+        error.as_error_key(),
+        &HashMap::from([(
+            "entry",
+            FluentValue::from(error.entry().unwrap_or_default()),
+        )]),
+        env,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::deck::DeckData;
+    use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordGuildMember;
+    use crate::models::discord::DiscordInteractionData;
+    use crate::models::discord::DiscordInteractionMetadata;
+    use crate::models::discord::DiscordInteractionOptions;
+    use crate::models::discord::DiscordInteractionType;
+    use crate::models::discord::DiscordMessage;
+    use crate::models::discord::DiscordMessageComponentInteractionData;
+    use crate::models::discord::DiscordUser;
+    use crate::test_utils::build_deck;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    #[tokio::test]
+    async fn sanity_create_deck_text_import() {
+        let env = build_mocked_binahbot_env();
+        let interaction = build_command_interaction(&[
+            ("name", "Turbo Xiao"),
+            ("deck", "Key page: Xiao's Page / 9x Degraded Pillar"),
+            ("tags", "Fast"),
+        ]);
+
+        let response = create_deck(&interaction, &env).await.unwrap();
+
+        let deck = env
+            .deck_repository
+            .get_deck("Turbo Xiao", "snowflake")
+            .await
+            .unwrap();
+        let lang_id = LanguageIdentifier::from(&get_binahbot_locale(&interaction));
+        assert_eq!(
+            format!(
+                "{:?}",
+                build_deck_message_response("create_deck_success", &deck, &lang_id, &env)
+            ),
+            format!("{:?}", response)
+        );
+        assert_eq!(1, deck.version);
+        assert!(deck.deck_data.keypage_id.is_some());
+        assert!(deck.deck_data.combat_page_ids.iter().all(|x| x.is_some()));
+        assert_eq!(
+            1,
+            env.deck_repository
+                .search_deck_index("tag#fast")
+                .await
+                .unwrap()
+                .len()
+        );
+
+        // a second deck with the same name doesn't overwrite the first
+        create_deck(&interaction, &env).await.unwrap();
+        assert_eq!(
+            1,
+            env.deck_repository
+                .list_deck_versions("Turbo Xiao", "snowflake")
+                .await
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_hold_inexact_import_for_confirmation() {
+        let env = build_mocked_binahbot_env();
+        let interaction = build_command_interaction(&[
+            ("name", "Turbo Xiao"),
+            ("deck", "Key page: Xiao's Page / 9x degraded pilar"),
+        ]);
+
+        let response = create_deck(&interaction, &env).await.unwrap();
+
+        assert!(response.data.unwrap().components.is_some());
+        assert!(env
+            .deck_repository
+            .get_deck("Turbo Xiao", "snowflake")
+            .await
+            .is_err());
+        assert_eq!(
+            "Turbo Xiao",
+            env.interaction_store
+                .get_pending_deck("id")
+                .await
+                .unwrap()
+                .deck
+                .name
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_create_deck_button_cancel() {
+        let env = build_mocked_binahbot_env();
+        let lang_id = LanguageIdentifier::from(&get_binahbot_locale(&build_button_interaction(
+            CANCEL_BUTTON_CUSTOM_ID,
+        )));
+        let pending_deck = build_pending_deck();
+        env.interaction_store
+            .put_pending_deck(&pending_deck)
+            .await
+            .unwrap();
+
+        let response = create_deck_button(&build_button_interaction(CANCEL_BUTTON_CUSTOM_ID), &env)
+            .await
+            .unwrap();
+
+        let mut expected = build_deck_message_response(
+            "create_deck_import_cancelled",
+            &pending_deck.deck,
+            &lang_id,
+            &env,
+        );
+        expected.r#type = DiscordInteractionResponseType::UpdateMessage;
+        if let Some(data) = expected.data.as_mut() {
+            data.components = Some(Vec::new());
+        }
+        assert_eq!(format!("{:?}", expected), format!("{:?}", response));
+        assert!(env
+            .deck_repository
+            .get_deck("Turbo Nikolai", "snowflake")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn sanity_create_deck_button_confirm() {
+        let env = build_mocked_binahbot_env();
+        env.interaction_store
+            .put_pending_deck(&build_pending_deck())
+            .await
+            .unwrap();
+
+        create_deck_button(&build_button_interaction(CONFIRM_BUTTON_CUSTOM_ID), &env)
+            .await
+            .unwrap();
+
+        assert!(env
+            .deck_repository
+            .get_deck("Turbo Nikolai", "snowflake")
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_not_save_deck_on_unknown_button() {
+        let env = build_mocked_binahbot_env();
+        env.interaction_store
+            .put_pending_deck(&build_pending_deck())
+            .await
+            .unwrap();
+
+        let result = create_deck_button(&build_button_interaction("createdeck#other"), &env).await;
+
+        assert!(matches!(result, Err(BinahBotError::UnknownComponent(_))));
+        assert!(env
+            .deck_repository
+            .get_deck("Turbo Nikolai", "snowflake")
+            .await
+            .is_err());
+    }
+
+    fn build_pending_deck() -> PendingDeck {
+        PendingDeck {
+            interaction_id: "original_id".to_string(),
+            ttl: get_epoch_time() + PENDING_DECK_TTL_SECONDS,
+            deck: build_deck(
+                "snowflake",
+                "Turbo Nikolai",
+                DeckData {
+                    keypage_id: Some("250023".to_string()),
+                    passive_ids: Vec::new(),
+                    combat_page_ids: std::array::from_fn(|_| Some("608014".to_string())),
+                },
+            ),
+        }
+    }
+
+    fn build_user() -> DiscordUser {
+        DiscordUser {
+            id: "snowflake".to_string(),
+            username: "username".to_string(),
+            avatar: None,
+        }
+    }
+
+    fn build_button_interaction(custom_id: &str) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::MessageComponent,
+            data: Some(DiscordInteractionData::MessageComponent(
+                DiscordMessageComponentInteractionData {
+                    custom_id: custom_id.to_string(),
+                },
+            )),
+            channel_id: None,
+            guild_id: Some("guild".to_string()),
+            token: "token".to_string(),
+            locale: None,
+            guild_locale: None,
+            user: None,
+            member: Some(DiscordGuildMember {
+                user: Some(build_user()),
+                permissions: None,
+            }),
+            message: Some(DiscordMessage {
+                content: None,
+                author: None,
+                interaction_metadata: Some(DiscordInteractionMetadata {
+                    id: "original_id".to_string(),
+                    r#type: DiscordInteractionType::ApplicationCommand,
+                    user: build_user(),
+                    original_response_message_id: None,
+                    interacted_message_id: None,
+                }),
+            }),
+        }
+    }
+
+    fn build_command_interaction(options: &[(&str, &str)]) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommand,
+            data: Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: "createdeck".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
+                    options: Some(
+                        options
+                            .iter()
+                            .map(|(name, value)| DiscordInteractionOptions {
+                                name: name.to_string(),
+                                name_localizations: None,
+                                value: DiscordInteractionOptionValue::String(value.to_string()),
+                                focused: None,
+                            })
+                            .collect(),
+                    ),
+                    target_id: None,
+                    resolved: None,
+                },
+            )),
+            channel_id: None,
+            guild_id: Some("guild".to_string()),
+            token: "token".to_string(),
+            locale: None,
+            guild_locale: None,
+            user: None,
+            member: Some(DiscordGuildMember {
+                user: Some(build_user()),
+                permissions: None,
+            }),
+            message: None,
         }
     }
 }
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::Loader;
+use unic_langid::LanguageIdentifier;
+
+use crate::error::BinahBotError;
+use crate::macros::cast_enum_variant;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::deck::DeckMetadata;
+use crate::models::discord::ActionRowComponent;
+use crate::models::discord::AllowedMentions;
+use crate::models::discord::DiscordComponent;
+use crate::models::discord::DiscordComponentType;
+use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionOptionValue;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::DiscordPermission;
+use crate::models::discord::MessageResponse;
+use crate::utils::build_delete_button_component;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
+use crate::utils::get_interaction_user;
+use crate::utils::get_option_value;
+use crate::utils::has_permission;
+
+use super::deck_utils::format_to_list;
+use super::deck_utils::parse_deck_name_option;
+
+const LIST_ACTION: &str = "list";
+const ADD_ACTION: &str = "add";
+const REMOVE_ACTION: &str = "remove";
+
+// keeps the listing under the embed description limit
+const MAX_LISTED_DECKS: usize = 25;
+
+pub async fn deck_collection(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
+
+    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+
+    let Some(guild_id) = interaction.guild_id.as_ref() else {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "deck_collection_guild_only_error_message",
+            env,
+        ));
+    };
+
+    let action = get_option_value("action", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
+        .map(|x| x.as_str())
+        .unwrap_or(LIST_ACTION);
+    let name_option = get_option_value("name", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+    let is_private = get_option_value("private", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Bool))
+        .is_some_and(|x| *x);
+
+    if action == LIST_ACTION {
+        let decks = match env.deck_repository.list_guild_collection(guild_id).await {
+            Ok(x) => x,
+            Err(_) => {
+                return Ok(build_error_message_response(
+                    &lang_id,
+                    "generic_error_message",
+                    env,
+                ))
+            }
+        };
+
+        let components =
+            (!is_private).then_some(vec![DiscordComponent::ActionRow(ActionRowComponent {
+                r#type: DiscordComponentType::ActionRow,
+                components: vec![DiscordComponent::Button(build_delete_button_component(
+                    &lang_id, env,
+                ))],
+            })]);
+
+        return Ok(MessageResponse {
+            r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+            data: Some(DiscordInteractionResponseMessage {
+                allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+                content: None,
+                embeds: Some(vec![build_collection_embed(&decks, &lang_id, env)]),
+                flags: is_private.then_some(DiscordMessageFlag::EphemeralMessage as i32),
+                components,
+            }),
+        });
+    }
+
+    if !has_permission(interaction.member.as_ref(), DiscordPermission::ManageGuild) {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "deck_collection_no_permission_error_message",
+            env,
+        ));
+    }
+
+    let Some(deck_key) = name_option.and_then(|x| parse_deck_name_option(x).ok()) else {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "deck_not_found_error_message",
+            env,
+        ));
+    };
+
+    let user_id = &get_interaction_user(interaction)?.id;
+
+    let (result, message_key) = match action {
+        ADD_ACTION => {
+            let deck = match env.deck_repository.get_deck(&deck_key.1, &deck_key.0).await {
+                Ok(x) => x,
+                Err(_) => {
+                    return Ok(build_error_message_response(
+                        &lang_id,
+                        "deck_not_found_error_message",
+                        env,
+                    ))
+                }
+            };
+            let metadata = DeckMetadata {
+                name: deck.name,
+                author_id: deck.author_id,
+                author_name: deck.author_name,
+            };
+            (
+                env.deck_repository
+                    .put_guild_collection_deck(guild_id, &metadata, user_id)
+                    .await,
+                "deck_collection_added",
+            )
+        }
+        REMOVE_ACTION => (
+            env.deck_repository
+                .delete_guild_collection_deck(guild_id, &deck_key.1, &deck_key.0)
+                .await,
+            "deck_collection_removed",
+        ),
+        _ => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "generic_error_message",
+                env,
+            ))
+        }
+    };
+
+    Ok(match result {
+        Ok(_) => MessageResponse {
+            r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+            data: Some(DiscordInteractionResponseMessage {
+                allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+                content: None,
+                embeds: Some(vec![DiscordEmbed {
+                    title: None,
+                    description: Some(env.locales.lookup_with_args(
+                        &lang_id,
+                        message_key,
+                        &HashMap::from([("deck_name", FluentValue::from(&deck_key.1))]),
+                    )),
+                    color: Some(DiscordEmbedColors::Default as i32),
+                    image: None,
+                    thumbnail: None,
+                    footer: None,
+                    author: None,
+                    url: None,
+                    fields: None,
+                }]),
+                flags: Some(DiscordMessageFlag::EphemeralMessage as i32),
+                components: None,
+            }),
+        },
+        Err(_) => build_error_message_response(&lang_id, "generic_error_message", env),
+    })
+}
+
+fn build_collection_embed(
+    decks: &[DeckMetadata],
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> DiscordEmbed {
+    let entries = decks
+        .iter()
+        .take(MAX_LISTED_DECKS)
+        .map(|x| {
+            env.locales.lookup_with_args(
+                lang_id,
+                "list_deck_name_author",
+                &HashMap::from([
+                    ("deck_name", FluentValue::from(&x.name)),
+                    ("author", FluentValue::from(&x.author_name)),
+                ]),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    DiscordEmbed {
+        title: Some(env.locales.lookup_with_args(
+            lang_id,
+            "deck_collection_title",
+            &HashMap::from([("count", FluentValue::from(decks.len()))]),
+        )),
+        description: Some(if entries.is_empty() {
+            env.locales.lookup(lang_id, "deck_collection_empty")
+        } else {
+            format_to_list(&entries)
+        }),
+        color: Some(DiscordEmbedColors::Default as i32),
+        image: None,
+        thumbnail: None,
+        footer: None,
+        author: None,
+        url: None,
+        fields: None,
+    }
+}
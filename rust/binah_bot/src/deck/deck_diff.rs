@@ -0,0 +1,481 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::Loader;
+use ruina::ruina_common::game_objects::common::PageType;
+use ruina::ruina_common::localizations::common::Locale;
+use ruina::ruina_index::models::ParsedTypedId;
+use unic_langid::LanguageIdentifier;
+
+use crate::error::BinahBotError;
+use crate::guild_config::find_exceeded_spoiler_limit;
+use crate::guild_config::get_spoiler_limits;
+use crate::macros::cast_enum_variant;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::deck::Deck;
+use crate::models::deck::DeckData;
+use crate::models::discord::ActionRowComponent;
+use crate::models::discord::AllowedMentions;
+use crate::models::discord::DiscordComponent;
+use crate::models::discord::DiscordComponentType;
+use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordEmbedFields;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionOptionValue;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::MessageResponse;
+use crate::utils::build_delete_button_component;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
+use crate::utils::get_display_name_locale;
+use crate::utils::get_option_value;
+
+use super::deck_utils::aggregate_count;
+use super::deck_utils::dedup_preserve_order;
+use super::deck_utils::parse_deck_name_option;
+use super::deck_utils::DeckKey;
+use super::read_deck::calculate_deck_chapter;
+use super::read_deck::spoiler_found;
+
+#[derive(Debug, PartialEq)]
+pub struct DeckDiff {
+    pub keypage: Option<(Option<String>, Option<String>)>,
+    pub added_passives: Vec<String>,
+    pub removed_passives: Vec<String>,
+    // combat page id and how many copies were added (positive) or removed (negative)
+    pub combat_pages: Vec<(String, i32)>,
+}
+
+impl DeckDiff {
+    pub fn is_empty(&self) -> bool {
+        self.keypage.is_none()
+            && self.added_passives.is_empty()
+            && self.removed_passives.is_empty()
+            && self.combat_pages.is_empty()
+    }
+}
+
+pub async fn deck_diff(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
+
+    let request_locale = get_binahbot_locale(interaction);
+    let lang_id = LanguageIdentifier::from(&request_locale);
+    let card_locale = Locale::from(&request_locale);
+
+    let deck_keys = ["first", "second"].map(|name| {
+        get_option_value(name, command_args)
+            .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
+            .and_then(|x| parse_deck_name_option(x).ok())
+    });
+    let [Some(first_key), Some(second_key)] = deck_keys else {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "deck_not_found_error_message",
+            env,
+        ));
+    };
+
+    let is_private = get_option_value("private", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Bool))
+        .is_some_and(|x| *x);
+
+    let (first, second) = tokio::join!(
+        get_deck_by_key(&first_key, env),
+        get_deck_by_key(&second_key, env)
+    );
+    let (Ok(first), Ok(second)) = (first, second) else {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "deck_not_found_error_message",
+            env,
+        ));
+    };
+
+    let spoiler_limits = get_spoiler_limits(interaction, is_private, env).await;
+    for deck in [&first, &second] {
+        let chapter = calculate_deck_chapter(&deck.deck_data);
+        if let Some(spoiler_limit) = find_exceeded_spoiler_limit(&chapter, &spoiler_limits) {
+            return Ok(spoiler_found(
+                &deck.name,
+                &chapter,
+                spoiler_limit,
+                &lang_id,
+                env,
+            ));
+        }
+    }
+
+    let title = env.locales.lookup_with_args(
+        &lang_id,
+        "deck_diff_title",
+        &HashMap::from([
+            ("old", FluentValue::from(&first.name)),
+            ("new", FluentValue::from(&second.name)),
+        ]),
+    );
+    let diff = diff_decks(&first.deck_data, &second.deck_data);
+
+    let components =
+        (!is_private).then_some(vec![DiscordComponent::ActionRow(ActionRowComponent {
+            r#type: DiscordComponentType::ActionRow,
+            components: vec![DiscordComponent::Button(build_delete_button_component(
+                &lang_id, env,
+            ))],
+        })]);
+
+    Ok(MessageResponse {
+        r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+            content: None,
+            embeds: Some(vec![build_diff_embed(
+                title,
+                &diff,
+                &card_locale,
+                &lang_id,
+                env,
+            )]),
+            flags: is_private.then_some(DiscordMessageFlag::EphemeralMessage as i32),
+            components,
+        }),
+    })
+}
+
+async fn get_deck_by_key(
+    deck_key: &DeckKey,
+    env: &BinahBotEnvironment,
+) -> Result<Deck, Box<dyn Error + Send + Sync>> {
+    env.deck_repository.get_deck(&deck_key.1, &deck_key.0).await
+}
+
+pub fn diff_decks(old: &DeckData, new: &DeckData) -> DeckDiff {
+    let keypage = (old.keypage_id != new.keypage_id)
+        .then(|| (old.keypage_id.clone(), new.keypage_id.clone()));
+
+    let added_passives = new
+        .passive_ids
+        .iter()
+        .filter(|x| !old.passive_ids.contains(x))
+        .cloned()
+        .collect();
+    let removed_passives = old
+        .passive_ids
+        .iter()
+        .filter(|x| !new.passive_ids.contains(x))
+        .cloned()
+        .collect();
+
+    let old_pages = old.combat_page_ids.iter().flatten().collect::<Vec<_>>();
+    let new_pages = new.combat_page_ids.iter().flatten().collect::<Vec<_>>();
+    let old_counts = aggregate_count(&old_pages);
+    let new_counts = aggregate_count(&new_pages);
+
+    let mut all_pages = old_pages
+        .iter()
+        .chain(&new_pages)
+        .cloned()
+        .collect::<Vec<_>>();
+    dedup_preserve_order(&mut all_pages);
+
+    let combat_pages = all_pages
+        .iter()
+        .filter_map(|x| {
+            let delta = new_counts.get(x).unwrap_or(&0) - old_counts.get(x).unwrap_or(&0);
+            (delta != 0).then(|| (x.to_string(), delta))
+        })
+        .collect();
+
+    DeckDiff {
+        keypage,
+        added_passives,
+        removed_passives,
+        combat_pages,
+    }
+}
+
+pub fn build_diff_embed(
+    title: String,
+    diff: &DeckDiff,
+    card_locale: &Locale,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> DiscordEmbed {
+    let mut fields = Vec::new();
+
+    if let Some((old, new)) = &diff.keypage {
+        fields.push(DiscordEmbedFields {
+            name: env.locales.lookup(lang_id, "read_deck_keypage_header"),
+            value: env.locales.lookup_with_args(
+                lang_id,
+                "deck_diff_keypage_change",
+                &HashMap::from([
+                    (
+                        "old",
+                        FluentValue::from(get_page_name(PageType::KeyPage, old, card_locale)),
+                    ),
+                    (
+                        "new",
+                        FluentValue::from(get_page_name(PageType::KeyPage, new, card_locale)),
+                    ),
+                ]),
+            ),
+            inline: Some(false),
+        });
+    }
+
+    if !diff.added_passives.is_empty() || !diff.removed_passives.is_empty() {
+        let added = diff.added_passives.iter().map(|x| {
+            format!(
+                "+ {}",
+                get_page_name(PageType::Passive, &Some(x.clone()), card_locale)
+            )
+        });
+        let removed = diff.removed_passives.iter().map(|x| {
+            format!(
+                "- {}",
+                get_page_name(PageType::Passive, &Some(x.clone()), card_locale)
+            )
+        });
+        fields.push(DiscordEmbedFields {
+            name: env.locales.lookup(lang_id, "read_deck_passives_header"),
+            value: format_to_diff_block(added.chain(removed)),
+            inline: Some(true),
+        });
+    }
+
+    if !diff.combat_pages.is_empty() {
+        let lines = diff.combat_pages.iter().map(|(id, delta)| {
+            format!(
+                "{} {}",
+                if *delta > 0 { "+" } else { "-" },
+                env.locales.lookup_with_args(
+                    lang_id,
+                    "read_deck_combat_page_count",
+                    &HashMap::from([
+                        (
+                            "page_name",
+                            FluentValue::from(get_page_name(
+                                PageType::CombatPage,
+                                &Some(id.clone()),
+                                card_locale
+                            )),
+                        ),
+                        ("count", FluentValue::from(delta.abs())),
+                    ]),
+                )
+            )
+        });
+        fields.push(DiscordEmbedFields {
+            name: env.locales.lookup(lang_id, "read_deck_combat_pages_header"),
+            value: format_to_diff_block(lines),
+            inline: Some(true),
+        });
+    }
+
+    DiscordEmbed {
+        title: Some(title),
+        description: diff
+            .is_empty()
+            .then(|| env.locales.lookup(lang_id, "deck_diff_no_changes")),
+        color: Some(DiscordEmbedColors::Default as i32),
+        image: None,
+        thumbnail: None,
+        footer: None,
+        author: None,
+        url: None,
+        fields: Some(fields),
+    }
+}
+
+fn get_page_name(page_type: PageType, id: &Option<String>, card_locale: &Locale) -> String {
+    id.as_ref()
+        .map(|x| {
+            get_display_name_locale(&ParsedTypedId(page_type, x.clone()), card_locale)
+                .unwrap_or("???".to_string())
+        })
+        .unwrap_or("-".to_string())
+}
+
+// discord colors lines starting with + and - inside diff code blocks
+fn format_to_diff_block(lines: impl Iterator<Item = String>) -> String {
+    format!("```diff\n{}\n```", lines.collect::<Vec<_>>().join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::binahbot::BinahBotLocale;
+    use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordGuildMember;
+    use crate::models::discord::DiscordInteractionData;
+    use crate::models::discord::DiscordInteractionOptions;
+    use crate::models::discord::DiscordInteractionType;
+    use crate::models::discord::DiscordUser;
+    use crate::test_utils::build_deck;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    fn build_deck_data(
+        keypage_id: &str,
+        passive_ids: &[&str],
+        combat_page_ids: &[&str],
+    ) -> DeckData {
+        DeckData {
+            keypage_id: Some(keypage_id.to_string()),
+            passive_ids: passive_ids.iter().map(|x| x.to_string()).collect(),
+            combat_page_ids: std::array::from_fn(|i| combat_page_ids.get(i).map(|x| x.to_string())),
+        }
+    }
+
+    #[test]
+    fn sanity_diff_decks() {
+        let old = build_deck_data(
+            "1",
+            &["p1", "p2"],
+            &["a", "a", "a", "b", "b", "b", "c", "c", "c"],
+        );
+        let new = build_deck_data(
+            "2",
+            &["p2", "p3"],
+            &["a", "a", "b", "b", "b", "c", "c", "c", "d"],
+        );
+        let diff = diff_decks(&old, &new);
+
+        assert_eq!(
+            Some((Some("1".to_string()), Some("2".to_string()))),
+            diff.keypage
+        );
+        assert_eq!(vec!["p3".to_string()], diff.added_passives);
+        assert_eq!(vec!["p1".to_string()], diff.removed_passives);
+        assert_eq!(
+            vec![("a".to_string(), -1), ("d".to_string(), 1)],
+            diff.combat_pages
+        );
+    }
+
+    #[test]
+    fn should_be_empty_for_identical_decks() {
+        let deck = build_deck_data("1", &["p1"], &["a", "a", "a", "b", "b", "b", "c", "c", "c"]);
+        assert!(diff_decks(&deck, &deck.clone()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn sanity_deck_diff() {
+        let env = build_mocked_binahbot_env();
+        let old = build_deck_data(
+            "250023",
+            &["230018", "240118"],
+            &[
+                "608014", "608014", "608014", "608015", "608015", "608015", "608009", "608009",
+                "608004",
+            ],
+        );
+        let new = build_deck_data(
+            "250036",
+            &["240118", "250025"],
+            &[
+                "608014", "608014", "608015", "608015", "608015", "608009", "608009", "608004",
+                "608004",
+            ],
+        );
+        env.deck_repository
+            .put_deck(&build_deck("1", "Turbo Nikolai", old), None)
+            .await
+            .unwrap();
+        env.deck_repository
+            .put_deck(&build_deck("1", "Xiao", new), None)
+            .await
+            .unwrap();
+
+        let response = deck_diff(
+            &build_discord_interaction("1#Turbo Nikolai", "1#Xiao"),
+            &env,
+        )
+        .await
+        .unwrap();
+
+        let expected_diff = DeckDiff {
+            keypage: Some((Some("250023".to_string()), Some("250036".to_string()))),
+            added_passives: vec!["250025".to_string()],
+            removed_passives: vec!["230018".to_string()],
+            combat_pages: vec![("608014".to_string(), -1), ("608004".to_string(), 1)],
+        };
+        let lang_id = LanguageIdentifier::from(&BinahBotLocale::EnglishUS);
+        let expected_embed = build_diff_embed(
+            env.locales.lookup_with_args(
+                &lang_id,
+                "deck_diff_title",
+                &HashMap::from([
+                    ("old", FluentValue::from("Turbo Nikolai")),
+                    ("new", FluentValue::from("Xiao")),
+                ]),
+            ),
+            &expected_diff,
+            &Locale::English,
+            &lang_id,
+            &env,
+        );
+        let embed = response
+            .data
+            .and_then(|x| x.embeds)
+            .and_then(|x| x.into_iter().next())
+            .expect("no embeds found");
+
+        assert_eq!(
+            3,
+            embed.fields.as_ref().map(|x| x.len()).unwrap_or_default()
+        );
+        assert_eq!(format!("{:?}", expected_embed), format!("{:?}", embed));
+    }
+
+    fn build_discord_interaction(first: &str, second: &str) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommand,
+            data: Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: "deckdiff".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
+                    options: Some(
+                        [("first", first), ("second", second)]
+                            .into_iter()
+                            .map(|(name, value)| DiscordInteractionOptions {
+                                name: name.to_string(),
+                                name_localizations: None,
+                                value: DiscordInteractionOptionValue::String(value.to_string()),
+                                focused: None,
+                            })
+                            .collect(),
+                    ),
+                    target_id: None,
+                    resolved: None,
+                },
+            )),
+            channel_id: None,
+            guild_id: None,
+            token: "token".to_string(),
+            locale: Some("en-US".to_string()),
+            guild_locale: None,
+            user: None,
+            member: Some(DiscordGuildMember {
+                user: Some(DiscordUser {
+                    id: "snowflake".to_string(),
+                    username: "username".to_string(),
+                    avatar: None,
+                }),
+                permissions: None,
+            }),
+            message: None,
+        }
+    }
+}
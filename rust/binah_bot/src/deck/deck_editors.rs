@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::Loader;
+use unic_langid::LanguageIdentifier;
+
+use crate::error::BinahBotError;
+use crate::macros::cast_enum_variant;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::deck::Deck;
+use crate::models::discord::AllowedMentions;
+use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionOptionValue;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::MessageResponse;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
+use crate::utils::get_interaction_user;
+use crate::utils::get_option_value;
+use crate::utils::get_required_string_option;
+
+use super::deck_utils::format_to_list;
+use super::deck_utils::get_deck_owner;
+use super::deck_utils::parse_deck_name_option;
+use super::deck_utils::put_deck_with_history;
+
+const LIST_ACTION: &str = "list";
+const ADD_ACTION: &str = "add";
+const REMOVE_ACTION: &str = "remove";
+const TRANSFER_ACTION: &str = "transfer";
+
+pub async fn deck_editors(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
+
+    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+
+    let name_option = get_required_string_option("name", command_args)?;
+    let deck_key = match parse_deck_name_option(name_option) {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "deck_not_found_error_message",
+                env,
+            ))
+        }
+    };
+    let action = get_option_value("action", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
+        .map(|x| x.as_str())
+        .unwrap_or(LIST_ACTION);
+    let user_option = get_option_value("user", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+
+    let user_id = &get_interaction_user(interaction)?.id;
+
+    let mut deck = match env.deck_repository.get_deck(&deck_key.1, &deck_key.0).await {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "deck_not_found_error_message",
+                env,
+            ))
+        }
+    };
+
+    if action == LIST_ACTION {
+        return Ok(build_response(build_editors_embed(&deck, &lang_id, env)));
+    }
+
+    if get_deck_owner(&deck) != user_id {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "deck_not_owner_error_message",
+            env,
+        ));
+    }
+
+    let Some(target_id) = user_option else {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "deck_editors_missing_user_error_message",
+            env,
+        ));
+    };
+
+    let Some(message_key) = apply_editor_action(&mut deck, action, target_id) else {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "generic_error_message",
+            env,
+        ));
+    };
+    let previous_version = deck.version;
+    deck.version += 1;
+
+    Ok(
+        match put_deck_with_history(&deck, Some(previous_version), user_id, env).await {
+            Ok(_) => build_response(DiscordEmbed {
+                title: None,
+                description: Some(env.locales.lookup_with_args(
+                    &lang_id,
+                    message_key,
+                    &HashMap::from([
+                        ("deck_name", FluentValue::from(&deck.name)),
+                        ("user", FluentValue::from(format!("<@{}>", target_id))),
+                    ]),
+                )),
+                color: Some(DiscordEmbedColors::Default as i32),
+                image: None,
+                thumbnail: None,
+                footer: None,
+                author: None,
+                url: None,
+                fields: None,
+            }),
+            Err(_) => build_error_message_response(&lang_id, "generic_error_message", env),
+        },
+    )
+}
+
+// returns the key of the message describing the change
+fn apply_editor_action(deck: &mut Deck, action: &str, target_id: &str) -> Option<&'static str> {
+    match action {
+        ADD_ACTION => {
+            if get_deck_owner(deck) != target_id && !deck.editors.iter().any(|x| x == target_id) {
+                deck.editors.push(target_id.to_string());
+            }
+            Some("deck_editors_added")
+        }
+        REMOVE_ACTION => {
+            deck.editors.retain(|x| x != target_id);
+            Some("deck_editors_removed")
+        }
+        TRANSFER_ACTION => {
+            deck.owner_id = (deck.author_id != target_id).then(|| target_id.to_string());
+            deck.editors.retain(|x| x != target_id);
+            Some("deck_editors_transferred")
+        }
+        _ => None,
+    }
+}
+
+fn build_editors_embed(
+    deck: &Deck,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> DiscordEmbed {
+    let owner = env.locales.lookup_with_args(
+        lang_id,
+        "deck_editors_owner",
+        &HashMap::from([(
+            "owner",
+            FluentValue::from(format!("<@{}>", get_deck_owner(deck))),
+        )]),
+    );
+    let editors = if deck.editors.is_empty() {
+        env.locales.lookup(lang_id, "deck_editors_none")
+    } else {
+        format_to_list(
+            &deck
+                .editors
+                .iter()
+                .map(|x| format!("<@{}>", x))
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    DiscordEmbed {
+        title: Some(env.locales.lookup_with_args(
+            lang_id,
+            "deck_editors_title",
+            &HashMap::from([("deck_name", FluentValue::from(&deck.name))]),
+        )),
+        description: Some(format!("{}\n{}", owner, editors)),
+        color: Some(DiscordEmbedColors::Default as i32),
+        image: None,
+        thumbnail: None,
+        footer: None,
+        author: None,
+        url: None,
+        fields: None,
+    }
+}
+
+fn build_response(embed: DiscordEmbed) -> MessageResponse {
+    MessageResponse {
+        r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+            content: None,
+            embeds: Some(vec![embed]),
+            flags: Some(DiscordMessageFlag::EphemeralMessage as i32),
+            components: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::deck::DeckData;
+    use crate::test_utils::build_deck;
+
+    #[test]
+    fn sanity_apply_editor_action() {
+        let mut deck = build_deck(
+            "1",
+            "Turbo Nikolai",
+            DeckData {
+                keypage_id: None,
+                passive_ids: Vec::new(),
+                combat_page_ids: std::array::from_fn(|_| None),
+            },
+        );
+
+        apply_editor_action(&mut deck, ADD_ACTION, "2");
+        apply_editor_action(&mut deck, ADD_ACTION, "2");
+        apply_editor_action(&mut deck, ADD_ACTION, "1");
+        assert_eq!(vec!["2".to_string()], deck.editors);
+
+        apply_editor_action(&mut deck, TRANSFER_ACTION, "2");
+        assert_eq!(Some("2".to_string()), deck.owner_id);
+        assert!(deck.editors.is_empty());
+
+        apply_editor_action(&mut deck, TRANSFER_ACTION, "1");
+        assert_eq!(None, deck.owner_id);
+
+        assert_eq!(None, apply_editor_action(&mut deck, "delete", "2"));
+    }
+}
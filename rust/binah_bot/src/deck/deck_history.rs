@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::Loader;
+use ruina::ruina_common::localizations::common::Locale;
+use unic_langid::LanguageIdentifier;
+
+use crate::error::BinahBotError;
+use crate::macros::cast_enum_variant;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::deck::DeckVersion;
+use crate::models::discord::AllowedMentions;
+use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionOptionValue;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::MessageResponse;
+use crate::thumbnail::generate_thumbnail;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
+use crate::utils::get_interaction_user;
+use crate::utils::get_option_value;
+use crate::utils::get_required_string_option;
+
+use super::deck_diff::build_diff_embed;
+use super::deck_diff::diff_decks;
+use super::deck_utils::can_edit_deck;
+use super::deck_utils::format_to_list;
+use super::deck_utils::parse_deck_name_option;
+use super::deck_utils::put_deck_with_history;
+use super::read_deck::transform_deck;
+
+const LIST_ACTION: &str = "list";
+const VIEW_ACTION: &str = "view";
+const DIFF_ACTION: &str = "diff";
+const ROLLBACK_ACTION: &str = "rollback";
+
+// keeps the listing under the embed description limit
+const MAX_LISTED_VERSIONS: usize = 25;
+
+pub async fn deck_history(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
+
+    let request_locale = get_binahbot_locale(interaction);
+    let lang_id = LanguageIdentifier::from(&request_locale);
+    let card_locale = Locale::from(&request_locale);
+
+    let name_option = get_required_string_option("name", command_args)?;
+    let deck_key = match parse_deck_name_option(name_option) {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "deck_not_found_error_message",
+                env,
+            ))
+        }
+    };
+
+    let action = get_option_value("action", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
+        .map(|x| x.as_str())
+        .unwrap_or(LIST_ACTION);
+    let version_option = get_option_value("version", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Integer));
+    let compare_option = get_option_value("compare", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Integer));
+
+    let user_id = &get_interaction_user(interaction)?.id;
+
+    let mut versions = match env
+        .deck_repository
+        .list_deck_versions(&deck_key.1, &deck_key.0)
+        .await
+    {
+        Ok(x) if !x.is_empty() => x,
+        _ => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "deck_history_not_found_error_message",
+                env,
+            ))
+        }
+    };
+
+    // backends don't agree on an order, and the latest version is the default diff target
+    versions.sort_by_key(|x| x.deck.version);
+
+    let find_version = |version: Option<&i32>| {
+        version.and_then(|v| versions.iter().find(|x| x.deck.version as i32 == *v))
+    };
+
+    Ok(match action {
+        VIEW_ACTION => {
+            let Some(deck_version) = find_version(version_option) else {
+                return Ok(build_error_message_response(
+                    &lang_id,
+                    "deck_history_version_not_found_error_message",
+                    env,
+                ));
+            };
+
+            let _ = generate_thumbnail(
+                env.lambda_client.as_ref(),
+                &env.thumbnail_lambda_name,
+                &deck_version.deck,
+                &card_locale,
+            )
+            .await;
+
+            match transform_deck(&deck_version.deck, &request_locale, env).await {
+                Ok(mut embed) => {
+                    embed.title = Some(get_version_title(
+                        &deck_version.deck.name,
+                        deck_version.deck.version,
+                        &lang_id,
+                        env,
+                    ));
+                    build_response(embed)
+                }
+                Err(_) => build_error_message_response(&lang_id, "generic_error_message", env),
+            }
+        }
+        DIFF_ACTION => {
+            let from = find_version(version_option);
+            let to = match compare_option {
+                Some(_) => find_version(compare_option),
+                None => versions.last(),
+            };
+            let (Some(from), Some(to)) = (from, to) else {
+                return Ok(build_error_message_response(
+                    &lang_id,
+                    "deck_history_version_not_found_error_message",
+                    env,
+                ));
+            };
+
+            let title = env.locales.lookup_with_args(
+                &lang_id,
+                "deck_diff_title",
+                &HashMap::from([
+                    (
+                        "old",
+                        FluentValue::from(get_version_title(
+                            &from.deck.name,
+                            from.deck.version,
+                            &lang_id,
+                            env,
+                        )),
+                    ),
+                    (
+                        "new",
+                        FluentValue::from(get_version_title(
+                            &to.deck.name,
+                            to.deck.version,
+                            &lang_id,
+                            env,
+                        )),
+                    ),
+                ]),
+            );
+            let diff = diff_decks(&from.deck.deck_data, &to.deck.deck_data);
+
+            build_response(build_diff_embed(title, &diff, &card_locale, &lang_id, env))
+        }
+        ROLLBACK_ACTION => {
+            let Some(target) = find_version(version_option) else {
+                return Ok(build_error_message_response(
+                    &lang_id,
+                    "deck_history_version_not_found_error_message",
+                    env,
+                ));
+            };
+
+            let mut deck = match env.deck_repository.get_deck(&deck_key.1, &deck_key.0).await {
+                Ok(x) => x,
+                Err(_) => {
+                    return Ok(build_error_message_response(
+                        &lang_id,
+                        "deck_not_found_error_message",
+                        env,
+                    ))
+                }
+            };
+
+            if !can_edit_deck(&deck, user_id) {
+                return Ok(build_error_message_response(
+                    &lang_id,
+                    "deck_not_editor_error_message",
+                    env,
+                ));
+            }
+
+            deck.deck_data = target.deck.deck_data.clone();
+            deck.description = target.deck.description.clone();
+            deck.tiph_deck = target.deck.tiph_deck.clone();
+            deck.tags = target.deck.tags.clone();
+            let previous_version = deck.version;
+            deck.version += 1;
+
+            let _ = generate_thumbnail(
+                env.lambda_client.as_ref(),
+                &env.thumbnail_lambda_name,
+                &deck,
+                &card_locale,
+            )
+            .await;
+
+            match put_deck_with_history(&deck, Some(previous_version), user_id, env).await {
+                Ok(_) => build_response(DiscordEmbed {
+                    title: None,
+                    description: Some(env.locales.lookup_with_args(
+                        &lang_id,
+                        "deck_history_rollback_success",
+                        &HashMap::from([
+                            ("deck_name", FluentValue::from(&deck.name)),
+                            ("version", FluentValue::from(target.deck.version)),
+                            ("new_version", FluentValue::from(deck.version)),
+                        ]),
+                    )),
+                    color: Some(DiscordEmbedColors::Default as i32),
+                    image: None,
+                    thumbnail: None,
+                    footer: None,
+                    author: None,
+                    url: None,
+                    fields: None,
+                }),
+                Err(_) => build_error_message_response(&lang_id, "generic_error_message", env),
+            }
+        }
+        _ => build_response(build_version_list_embed(&versions, &lang_id, env)),
+    })
+}
+
+fn build_version_list_embed(
+    versions: &[DeckVersion],
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> DiscordEmbed {
+    let current_version = versions.iter().map(|x| x.deck.version).max();
+
+    let entries = versions
+        .iter()
+        .rev()
+        .take(MAX_LISTED_VERSIONS)
+        .map(|x| {
+            let timestamp = x.timestamp.map(|y| format!("<t:{}:f>", y)).unwrap_or(
+                env.locales
+                    .lookup(lang_id, "deck_history_unknown_timestamp"),
+            );
+            let key = if Some(x.deck.version) == current_version {
+                "deck_history_current_version_entry"
+            } else {
+                "deck_history_version_entry"
+            };
+            env.locales.lookup_with_args(
+                lang_id,
+                key,
+                &HashMap::from([
+                    ("version", FluentValue::from(x.deck.version)),
+                    ("timestamp", FluentValue::from(timestamp)),
+                    (
+                        "editor",
+                        FluentValue::from(format!(
+                            "<@{}>",
+                            x.editor_id.as_ref().unwrap_or(&x.deck.author_id)
+                        )),
+                    ),
+                ]),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    DiscordEmbed {
+        title: versions.first().map(|x| x.deck.name.clone()),
+        description: Some(format_to_list(&entries)),
+        color: Some(DiscordEmbedColors::Default as i32),
+        image: None,
+        thumbnail: None,
+        footer: None,
+        author: None,
+        url: None,
+        fields: None,
+    }
+}
+
+fn get_version_title(
+    deck_name: &str,
+    version: u32,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> String {
+    env.locales.lookup_with_args(
+        lang_id,
+        "deck_history_version_title",
+        &HashMap::from([
+            ("deck_name", FluentValue::from(deck_name)),
+            ("version", FluentValue::from(version)),
+        ]),
+    )
+}
+
+fn build_response(embed: DiscordEmbed) -> MessageResponse {
+    MessageResponse {
+        r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+            content: None,
+            embeds: Some(vec![embed]),
+            flags: Some(DiscordMessageFlag::EphemeralMessage as i32),
+            components: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::binahbot::BinahBotLocale;
+    use crate::models::deck::Deck;
+    use crate::models::deck::DeckData;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    fn build_deck_version(
+        version: u32,
+        timestamp: Option<u64>,
+        editor_id: Option<&str>,
+    ) -> DeckVersion {
+        DeckVersion {
+            deck: Deck {
+                name: "Turbo Nikolai".to_string(),
+                author_id: "1".to_string(),
+                author_name: "gh".to_string(),
+                description: None,
+                deck_data: DeckData {
+                    keypage_id: None,
+                    passive_ids: Vec::new(),
+                    combat_page_ids: std::array::from_fn(|_| None),
+                },
+                tiph_deck: None,
+                version,
+                tags: Vec::new(),
+                forked_from: None,
+                owner_id: None,
+                editors: Vec::new(),
+            },
+            timestamp,
+            editor_id: editor_id.map(|x| x.to_string()),
+        }
+    }
+
+    #[test]
+    fn sanity_build_version_list_embed() {
+        let env = build_mocked_binahbot_env();
+        let versions = vec![
+            build_deck_version(1, None, None),
+            build_deck_version(2, Some(1700000000), Some("2")),
+        ];
+        let lang_id = LanguageIdentifier::from(&BinahBotLocale::EnglishUS);
+        let embed = build_version_list_embed(&versions, &lang_id, &env);
+        let description = embed.description.unwrap();
+        let lines = description.lines().collect::<Vec<_>>();
+
+        assert_eq!(Some("Turbo Nikolai".to_string()), embed.title);
+        assert_eq!(2, lines.len());
+        assert_eq!(
+            "- v\u{2068}2\u{2069} · \u{2068}<t:1700000000:f>\u{2069} · \u{2068}<@2>\u{2069} (current)",
+            lines[0]
+        );
+        assert_eq!(
+            "- v\u{2068}1\u{2069} · \u{2068}unknown date\u{2069} · \u{2068}<@1>\u{2069}",
+            lines[1]
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::Loader;
+use ruina::ruina_common::game_objects::common::PageType;
+use unic_langid::LanguageIdentifier;
+
+use crate::error::BinahBotError;
+use crate::macros::cast_enum_variant;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::deck::DeckVoteTotals;
+use crate::models::discord::ActionRowComponent;
+use crate::models::discord::AllowedMentions;
+use crate::models::discord::DiscordComponent;
+use crate::models::discord::DiscordComponentType;
+use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionOptionValue;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::MessageResponse;
+use crate::utils::build_delete_button_component;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
+use crate::utils::get_option_value;
+
+use super::deck_utils::get_chapter_index_key;
+use super::deck_utils::get_page_index_key;
+use super::deck_utils::search_decks;
+
+const FAVORITES_SORT: &str = "favorites";
+
+const MAX_LEADERBOARD_ENTRIES: usize = 10;
+
+pub async fn deck_leaderboard(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
+
+    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+
+    let keypage_option = get_option_value("keypage", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+    let chapter_option = get_option_value("chapter", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+    let sort_by_favorites = get_option_value("sort", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
+        .is_some_and(|x| x == FAVORITES_SORT);
+    let is_private = get_option_value("private", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Bool))
+        .is_some_and(|x| *x);
+
+    let mut totals = match env.deck_repository.list_deck_vote_totals().await {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "generic_error_message",
+                env,
+            ))
+        }
+    };
+
+    let index_keys = keypage_option
+        .map(|x| get_page_index_key(PageType::KeyPage, x))
+        .into_iter()
+        .chain(chapter_option.map(|x| get_chapter_index_key(x)))
+        .collect::<Vec<_>>();
+    if !index_keys.is_empty() {
+        let decks = match search_decks(&index_keys, env).await {
+            Ok(x) => x,
+            Err(_) => {
+                return Ok(build_error_message_response(
+                    &lang_id,
+                    "generic_error_message",
+                    env,
+                ))
+            }
+        };
+        totals.retain(|x| {
+            decks
+                .iter()
+                .any(|y| x.metadata.author_id == y.author_id && x.metadata.name == y.name)
+        });
+    }
+
+    rank_decks(&mut totals, sort_by_favorites);
+
+    let components =
+        (!is_private).then_some(vec![DiscordComponent::ActionRow(ActionRowComponent {
+            r#type: DiscordComponentType::ActionRow,
+            components: vec![DiscordComponent::Button(build_delete_button_component(
+                &lang_id, env,
+            ))],
+        })]);
+
+    Ok(MessageResponse {
+        r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+            content: None,
+            embeds: Some(vec![build_leaderboard_embed(&totals, &lang_id, env)]),
+            flags: is_private.then_some(DiscordMessageFlag::EphemeralMessage as i32),
+            components,
+        }),
+    })
+}
+
+fn rank_decks(totals: &mut Vec<DeckVoteTotals>, sort_by_favorites: bool) {
+    totals.retain(|x| x.upvotes > 0 || x.favorites > 0);
+    totals.sort_by_key(|x| {
+        if sort_by_favorites {
+            (-x.favorites, -x.upvotes)
+        } else {
+            (-x.upvotes, -x.favorites)
+        }
+    });
+}
+
+fn build_leaderboard_embed(
+    totals: &[DeckVoteTotals],
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> DiscordEmbed {
+    let entries = totals
+        .iter()
+        .take(MAX_LEADERBOARD_ENTRIES)
+        .enumerate()
+        .map(|(i, x)| {
+            env.locales.lookup_with_args(
+                lang_id,
+                "deck_leaderboard_entry",
+                &HashMap::from([
+                    ("rank", FluentValue::from(i + 1)),
+                    ("deck_name", FluentValue::from(&x.metadata.name)),
+                    ("author", FluentValue::from(&x.metadata.author_name)),
+                    ("upvotes", FluentValue::from(x.upvotes)),
+                    ("favorites", FluentValue::from(x.favorites)),
+                ]),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    DiscordEmbed {
+        title: Some(env.locales.lookup(lang_id, "deck_leaderboard_title")),
+        description: Some(if entries.is_empty() {
+            env.locales.lookup(lang_id, "deck_leaderboard_empty")
+        } else {
+            entries.join("\n")
+        }),
+        color: Some(DiscordEmbedColors::Default as i32),
+        image: None,
+        thumbnail: None,
+        footer: None,
+        author: None,
+        url: None,
+        fields: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::binahbot::BinahBotLocale;
+    use crate::models::deck::DeckMetadata;
+    use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordGuildMember;
+    use crate::models::discord::DiscordInteractionData;
+    use crate::models::discord::DiscordInteractionOptions;
+    use crate::models::discord::DiscordInteractionType;
+    use crate::models::discord::DiscordUser;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    fn build_totals(name: &str, upvotes: i64, favorites: i64) -> DeckVoteTotals {
+        DeckVoteTotals {
+            metadata: DeckMetadata {
+                name: name.to_string(),
+                author_id: "1".to_string(),
+                author_name: "gh".to_string(),
+            },
+            upvotes,
+            favorites,
+        }
+    }
+
+    #[test]
+    fn sanity_rank_decks() {
+        let build = || {
+            vec![
+                build_totals("a", 1, 5),
+                build_totals("b", 3, 0),
+                build_totals("c", 0, 0),
+                build_totals("d", 3, 1),
+            ]
+        };
+
+        let mut totals = build();
+        rank_decks(&mut totals, false);
+        assert_eq!(
+            vec!["d", "b", "a"],
+            totals
+                .iter()
+                .map(|x| x.metadata.name.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        let mut totals = build();
+        rank_decks(&mut totals, true);
+        assert_eq!(
+            vec!["a", "d", "b"],
+            totals
+                .iter()
+                .map(|x| x.metadata.name.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_deck_leaderboard() {
+        let env = build_mocked_binahbot_env();
+        for (totals, votes) in [
+            (build_totals("a", 1, 5), [(1, 1), (0, 4)]),
+            (build_totals("b", 3, 0), [(3, 0), (0, 0)]),
+            (build_totals("c", 0, 0), [(1, 1), (-1, -1)]),
+            (build_totals("d", 3, 1), [(2, 1), (1, 0)]),
+        ] {
+            for (upvote_delta, favorite_delta) in votes {
+                env.deck_repository
+                    .update_deck_vote_totals(&totals.metadata, upvote_delta, favorite_delta)
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let lang_id = LanguageIdentifier::from(&BinahBotLocale::EnglishUS);
+        let mut expected = vec![
+            build_totals("d", 3, 1),
+            build_totals("b", 3, 0),
+            build_totals("a", 1, 5),
+        ];
+        let response = deck_leaderboard(&build_discord_interaction(None), &env)
+            .await
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", build_leaderboard_embed(&expected, &lang_id, &env)),
+            format!("{:?}", get_embed(response))
+        );
+
+        expected.sort_by_key(|x| -x.favorites);
+        let response = deck_leaderboard(&build_discord_interaction(Some(FAVORITES_SORT)), &env)
+            .await
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", build_leaderboard_embed(&expected, &lang_id, &env)),
+            format!("{:?}", get_embed(response))
+        );
+    }
+
+    fn get_embed(response: MessageResponse) -> DiscordEmbed {
+        response
+            .data
+            .and_then(|x| x.embeds)
+            .and_then(|x| x.into_iter().next())
+            .expect("no embeds found")
+    }
+
+    fn build_discord_interaction(sort: Option<&str>) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommand,
+            data: Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: "decktop".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
+                    options: Some(
+                        sort.into_iter()
+                            .map(|x| DiscordInteractionOptions {
+                                name: "sort".to_string(),
+                                name_localizations: None,
+                                value: DiscordInteractionOptionValue::String(x.to_string()),
+                                focused: None,
+                            })
+                            .collect(),
+                    ),
+                    target_id: None,
+                    resolved: None,
+                },
+            )),
+            channel_id: None,
+            guild_id: None,
+            token: "token".to_string(),
+            locale: Some("en-US".to_string()),
+            guild_locale: None,
+            user: None,
+            member: Some(DiscordGuildMember {
+                user: Some(DiscordUser {
+                    id: "snowflake".to_string(),
+                    username: "username".to_string(),
+                    avatar: None,
+                }),
+                permissions: None,
+            }),
+            message: None,
+        }
+    }
+}
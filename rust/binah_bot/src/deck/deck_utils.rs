@@ -1,10 +1,27 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::error::Error;
+use std::iter;
 
+use ruina::ruina_common::game_objects::common::PageType;
+use ruina::ruina_index::models::ParsedTypedId;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::deck::Deck;
 use crate::models::deck::DeckData;
+use crate::models::deck::DeckMetadata;
+use crate::models::deck::DeckVersion;
 use crate::models::discord::DiscordUser;
+use crate::utils::get_epoch_time;
+
+use super::read_deck::calculate_deck_chapter;
 
 static BASE_DISCORD_URL: &str = "https://discord.com/api/v10";
 
+const MAX_TAGS: usize = 5;
+const MAX_TAG_LENGTH: usize = 32;
+
 pub struct DeckKey(pub String, pub String);
 
 pub async fn get_user(
@@ -62,9 +79,176 @@ pub fn parse_deck_name_option(name_option: &str) -> Result<DeckKey, ()> {
     }
 }
 
+// The deck put is conditional on `previous_version` (see `DeckRepository::put_deck`), so only
+// one of two concurrent edits gets to write the next version and its history
+pub async fn put_deck_with_history(
+    deck: &Deck,
+    previous_version: Option<u32>,
+    editor_id: &str,
+    env: &BinahBotEnvironment,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    env.deck_repository.put_deck(deck, previous_version).await?;
+    env.deck_repository
+        .put_deck_version(
+            &DeckVersion {
+                deck: deck.clone(),
+                timestamp: Some(get_epoch_time()),
+                editor_id: Some(editor_id.to_string()),
+            },
+            true,
+        )
+        .await?;
+    env.deck_repository
+        .put_deck_index(deck, &get_deck_index_keys(deck))
+        .await
+}
+
+// short, stable reference to a deck for places where the full key doesn't fit, like button custom ids
+pub fn get_deck_ref(author_id: &str, deck_name: &str) -> String {
+    format!(
+        "{:X}",
+        xxh3_64(format!("{}#{}", author_id, deck_name).as_bytes())
+    )
+}
+
+// decks are owned by their original author until ownership is transferred
+pub fn get_deck_owner(deck: &Deck) -> &str {
+    deck.owner_id.as_deref().unwrap_or(&deck.author_id)
+}
+
+pub fn can_edit_deck(deck: &Deck, user_id: &str) -> bool {
+    get_deck_owner(deck) == user_id || deck.editors.iter().any(|x| x == user_id)
+}
+
+pub fn get_ref_index_key(deck_ref: &str) -> String {
+    format!("ref#{}", deck_ref)
+}
+
+// lets users find decks they can edit but didn't author
+pub fn get_editor_index_key(user_id: &str) -> String {
+    format!("editor#{}", user_id)
+}
+
+pub fn get_tag_index_key(tag: &str) -> String {
+    format!("tag#{}", tag.trim().to_lowercase())
+}
+
+// chapter is the name of the `Chapter` variant, e.g. "UrbanNightmare"
+pub fn get_chapter_index_key(chapter: &str) -> String {
+    format!("chapter#{}", chapter)
+}
+
+pub fn get_page_index_key(page_type: PageType, id: &str) -> String {
+    ParsedTypedId(page_type, id.to_string()).to_string()
+}
+
+pub fn get_deck_index_keys(deck: &Deck) -> Vec<String> {
+    let mut index_keys = iter::once(get_ref_index_key(&get_deck_ref(
+        &deck.author_id,
+        &deck.name,
+    )))
+    .chain(deck.tags.iter().map(|x| get_tag_index_key(x)))
+    .chain(
+        deck.owner_id
+            .iter()
+            .chain(&deck.editors)
+            .map(|x| get_editor_index_key(x)),
+    )
+    .chain(
+        deck.deck_data
+            .keypage_id
+            .iter()
+            .map(|x| get_page_index_key(PageType::KeyPage, x)),
+    )
+    .chain([get_chapter_index_key(
+        &calculate_deck_chapter(&deck.deck_data).to_string(),
+    )])
+    .chain(
+        deck.deck_data
+            .combat_page_ids
+            .iter()
+            .flatten()
+            .map(|x| get_page_index_key(PageType::CombatPage, x)),
+    )
+    .chain(
+        deck.deck_data
+            .passive_ids
+            .iter()
+            .map(|x| get_page_index_key(PageType::Passive, x)),
+    )
+    .collect::<Vec<_>>();
+    dedup_preserve_order(&mut index_keys);
+
+    index_keys
+}
+
+// returns decks matching every given index key
+pub async fn search_decks(
+    index_keys: &[String],
+    env: &BinahBotEnvironment,
+) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+    let mut results: Option<Vec<DeckMetadata>> = None;
+    for index_key in index_keys {
+        let decks = env.deck_repository.search_deck_index(index_key).await?;
+        results = Some(match results {
+            Some(x) => x
+                .into_iter()
+                .filter(|x| {
+                    decks
+                        .iter()
+                        .any(|y| x.author_id == y.author_id && x.name == y.name)
+                })
+                .collect(),
+            None => decks,
+        });
+    }
+
+    Ok(results.unwrap_or_default())
+}
+
+// tags are comma separated, e.g. "bleed, Urban Nightmare clear"
+pub fn parse_tags(tags: &str) -> Result<Vec<String>, ()> {
+    let mut tags = tags
+        .split(',')
+        .map(|x| x.trim().to_lowercase())
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>();
+    dedup_preserve_order(&mut tags);
+
+    if tags.len() > MAX_TAGS || tags.iter().any(|x| x.chars().count() > MAX_TAG_LENGTH) {
+        return Err(());
+    }
+
+    Ok(tags)
+}
+
+pub fn format_to_list<T: AsRef<str>>(v: &[T]) -> String {
+    v.iter()
+        .map(|x| format!("- {}", x.as_ref()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn aggregate_count<T: AsRef<str> + Eq + std::hash::Hash>(v: &[T]) -> HashMap<&T, i32> {
+    let mut ret_val = HashMap::new();
+
+    v.iter().for_each(|x| {
+        *ret_val.entry(x).or_insert(0) += 1;
+    });
+
+    ret_val
+}
+
+pub fn dedup_preserve_order<T: AsRef<str> + Eq + std::hash::Hash + Clone>(v: &mut Vec<T>) {
+    let mut set = HashSet::new();
+
+    v.retain(|x| set.insert(x.clone()));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::build_deck;
 
     fn full_combat_pages() -> [Option<String>; 9] {
         std::array::from_fn(|i| Some(i.to_string()))
@@ -115,6 +299,92 @@ mod tests {
         assert_eq!(err.as_error_key(), "missing_keypage_error_message");
     }
 
+    #[test]
+    fn sanity_parse_tags() {
+        assert_eq!(
+            Ok(vec![
+                "bleed".to_string(),
+                "urban nightmare clear".to_string()
+            ]),
+            parse_tags(" Bleed, ,Urban Nightmare clear,bleed")
+        );
+    }
+
+    #[test]
+    fn should_err_on_too_many_tags() {
+        assert!(parse_tags("a,b,c,d,e,f").is_err());
+        assert!(parse_tags(&"a".repeat(MAX_TAG_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn sanity_get_deck_index_keys() {
+        let deck = Deck {
+            tags: vec!["bleed".to_string()],
+            ..build_deck(
+                "1",
+                "Turbo Nikolai",
+                DeckData {
+                    keypage_id: None,
+                    passive_ids: vec!["p1".to_string()],
+                    combat_page_ids: std::array::from_fn(|i| Some((i % 3).to_string())),
+                },
+            )
+        };
+
+        let index_keys = get_deck_index_keys(&deck);
+        assert_eq!(
+            get_ref_index_key(&get_deck_ref("1", "Turbo Nikolai")),
+            index_keys[0]
+        );
+        assert_eq!(
+            vec![
+                "tag#bleed",
+                "chapter#ImpuritasCivitatis",
+                "c#0",
+                "c#1",
+                "c#2",
+                "p#p1"
+            ],
+            index_keys[1..]
+        );
+    }
+
+    #[test]
+    fn sanity_can_edit_deck() {
+        let mut deck = Deck {
+            editors: vec!["2".to_string()],
+            ..build_deck(
+                "1",
+                "Turbo Nikolai",
+                DeckData {
+                    keypage_id: None,
+                    passive_ids: Vec::new(),
+                    combat_page_ids: full_combat_pages(),
+                },
+            )
+        };
+        assert!(can_edit_deck(&deck, "1"));
+        assert!(can_edit_deck(&deck, "2"));
+        assert!(!can_edit_deck(&deck, "3"));
+
+        deck.owner_id = Some("3".to_string());
+        assert_eq!("3", get_deck_owner(&deck));
+        assert!(!can_edit_deck(&deck, "1"));
+        assert!(can_edit_deck(&deck, "3"));
+
+        let index_keys = get_deck_index_keys(&deck);
+        assert!(index_keys.contains(&get_editor_index_key("2")));
+        assert!(index_keys.contains(&get_editor_index_key("3")));
+        assert!(!index_keys.contains(&get_editor_index_key("1")));
+    }
+
+    #[test]
+    fn should_fit_deck_ref_in_custom_id() {
+        let deck_ref = get_deck_ref(&"9".repeat(20), &"a".repeat(100));
+        assert!(deck_ref.len() <= 16);
+        assert_ne!(deck_ref, get_deck_ref("1", "Turbo Nikolai"));
+    }
+
     #[test]
     fn should_err_on_missing_combat_pages_when_both_missing() {
         let deck_data = DeckData {
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::Loader;
+use unic_langid::LanguageIdentifier;
+
+use crate::error::BinahBotError;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::deck::Deck;
+use crate::models::discord::ActionRowComponent;
+use crate::models::discord::AllowedMentions;
+use crate::models::discord::ButtonComponent;
+use crate::models::discord::ButtonStyle;
+use crate::models::discord::DiscordComponent;
+use crate::models::discord::DiscordComponentType;
+use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::MessageResponse;
+use crate::utils::build_delete_button_component;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_component_data;
+use crate::utils::get_interaction_user;
+
+use super::deck_utils::get_deck_ref;
+use super::deck_utils::get_ref_index_key;
+
+// format: deckvote#<action>#<deck ref>
+pub const DECK_VOTE_BUTTON_PREFIX: &str = "deckvote#";
+const UPVOTE_ACTION: &str = "up";
+const FAVORITE_ACTION: &str = "fav";
+const SEPARATOR: &str = "#";
+
+pub fn build_deck_components(
+    deck: &Deck,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> Vec<DiscordComponent> {
+    let deck_ref = get_deck_ref(&deck.author_id, &deck.name);
+
+    vec![DiscordComponent::ActionRow(ActionRowComponent {
+        r#type: DiscordComponentType::ActionRow,
+        components: vec![
+            DiscordComponent::Button(ButtonComponent {
+                r#type: DiscordComponentType::Button,
+                style: ButtonStyle::Secondary,
+                label: Some(env.locales.lookup(lang_id, "read_deck_upvote_button_text")),
+                custom_id: Some(build_custom_id(UPVOTE_ACTION, &deck_ref)),
+                disabled: None,
+            }),
+            DiscordComponent::Button(ButtonComponent {
+                r#type: DiscordComponentType::Button,
+                style: ButtonStyle::Secondary,
+                label: Some(
+                    env.locales
+                        .lookup(lang_id, "read_deck_favorite_button_text"),
+                ),
+                custom_id: Some(build_custom_id(FAVORITE_ACTION, &deck_ref)),
+                disabled: None,
+            }),
+            DiscordComponent::Button(build_delete_button_component(lang_id, env)),
+        ],
+    })]
+}
+
+pub async fn deck_vote_button(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let data = get_component_data(interaction)?;
+    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+
+    let Some((action, deck_ref)) = parse_custom_id(&data.custom_id) else {
+        tracing::error!("malformed deck vote custom_id={}", data.custom_id);
+        return Ok(build_error_message_response(
+            &lang_id,
+            "generic_error_message",
+            env,
+        ));
+    };
+
+    let metadata = match env
+        .deck_repository
+        .search_deck_index(&get_ref_index_key(deck_ref))
+        .await
+        .map(|x| x.into_iter().next())
+    {
+        Ok(Some(x)) => x,
+        _ => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "deck_not_found_error_message",
+                env,
+            ))
+        }
+    };
+
+    let user_id = &get_interaction_user(interaction)?.id;
+
+    let mut vote = match env
+        .deck_repository
+        .get_deck_vote(&metadata.name, &metadata.author_id, user_id)
+        .await
+    {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "generic_error_message",
+                env,
+            ))
+        }
+    };
+
+    let previous_vote = vote.clone();
+    let (upvote_delta, favorite_delta, message_key) = match action {
+        UPVOTE_ACTION => {
+            vote.upvote = !vote.upvote;
+            if vote.upvote {
+                (1, 0, "deck_vote_upvoted")
+            } else {
+                (-1, 0, "deck_vote_upvote_removed")
+            }
+        }
+        _ => {
+            vote.favorite = !vote.favorite;
+            if vote.favorite {
+                (0, 1, "deck_vote_favorited")
+            } else {
+                (0, -1, "deck_vote_favorite_removed")
+            }
+        }
+    };
+
+    // the totals only move if this exact toggle was written, so double clicks can't count twice
+    let put_deck_vote_result = env
+        .deck_repository
+        .put_deck_vote(
+            &metadata.name,
+            &metadata.author_id,
+            user_id,
+            &vote,
+            &previous_vote,
+        )
+        .await;
+    if put_deck_vote_result.is_err() {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "generic_error_message",
+            env,
+        ));
+    }
+
+    let totals = match env
+        .deck_repository
+        .update_deck_vote_totals(&metadata, upvote_delta, favorite_delta)
+        .await
+    {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "generic_error_message",
+                env,
+            ))
+        }
+    };
+
+    Ok(MessageResponse {
+        r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+            content: None,
+            embeds: Some(vec![DiscordEmbed {
+                title: None,
+                description: Some(env.locales.lookup_with_args(
+                    &lang_id,
+                    message_key,
+                    &HashMap::from([
+                        ("deck_name", FluentValue::from(&metadata.name)),
+                        ("upvotes", FluentValue::from(totals.upvotes)),
+                        ("favorites", FluentValue::from(totals.favorites)),
+                    ]),
+                )),
+                color: Some(DiscordEmbedColors::Default as i32),
+                image: None,
+                thumbnail: None,
+                footer: None,
+                author: None,
+                url: None,
+                fields: None,
+            }]),
+            flags: Some(DiscordMessageFlag::EphemeralMessage as i32),
+            components: None,
+        }),
+    })
+}
+
+fn build_custom_id(action: &str, deck_ref: &str) -> String {
+    format!(
+        "{}{}{}{}",
+        DECK_VOTE_BUTTON_PREFIX, action, SEPARATOR, deck_ref
+    )
+}
+
+fn parse_custom_id(custom_id: &str) -> Option<(&str, &str)> {
+    custom_id
+        .strip_prefix(DECK_VOTE_BUTTON_PREFIX)
+        .and_then(|x| x.split_once(SEPARATOR))
+        .filter(|(action, _)| [UPVOTE_ACTION, FAVORITE_ACTION].contains(action))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanity_custom_id() {
+        let deck_ref = get_deck_ref("1", "Turbo Nikolai");
+        let custom_id = build_custom_id(FAVORITE_ACTION, &deck_ref);
+
+        assert!(custom_id.len() <= 100);
+        assert_eq!(
+            Some((FAVORITE_ACTION, deck_ref.as_str())),
+            parse_custom_id(&custom_id)
+        );
+    }
+
+    #[test]
+    fn should_reject_unknown_actions() {
+        assert_eq!(None, parse_custom_id("deckvote#down#ABC"));
+        assert_eq!(None, parse_custom_id("lc#e#1#en#0"));
+    }
+}
@@ -4,62 +4,86 @@ use fluent_templates::fluent_bundle::FluentValue;
 use fluent_templates::Loader;
 use unic_langid::LanguageIdentifier;
 
-use crate::macros::cast_enum_variant;
+use crate::error::BinahBotError;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::DiscordEmbedColors;
 use crate::models::discord::AllowedMentions;
 use crate::models::discord::DiscordEmbed;
 use crate::models::discord::DiscordInteraction;
-use crate::models::discord::DiscordInteractionData;
-use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionResponseMessage;
 use crate::models::discord::DiscordInteractionResponseType;
 use crate::models::discord::DiscordMessageFlag;
 use crate::models::discord::MessageResponse;
 use crate::utils::build_error_message_response;
 use crate::utils::get_binahbot_locale;
-use crate::utils::get_option_value;
+use crate::utils::get_command_options;
+use crate::utils::get_interaction_user;
+use crate::utils::get_required_string_option;
 
+use super::deck_utils::get_deck_owner;
 use super::deck_utils::parse_deck_name_option;
 
 pub async fn delete_deck(
     interaction: &DiscordInteraction,
     env: &BinahBotEnvironment,
-) -> MessageResponse {
-    let command_args = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-        .and_then(|x| x.options.as_ref())
-        .unwrap();
-
-    let deck_name = get_option_value("name", command_args)
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
-        .unwrap();
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
+
+    let deck_name = get_required_string_option("name", command_args)?;
 
     let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
 
     let deck_key = match parse_deck_name_option(deck_name) {
         Ok(x) => x,
         Err(_) => {
-            return build_error_message_response(&lang_id, "deck_not_found_error_message", env)
+            return Ok(build_error_message_response(
+                &lang_id,
+                "deck_not_found_error_message",
+                env,
+            ))
         }
     };
-    let author_id = &interaction
-        .user
-        .as_ref()
-        .unwrap_or(interaction.member.as_ref().unwrap().user.as_ref().unwrap())
-        .id;
-
-    let delete_deck_result = crate::ddb::delete_deck(
-        env.ddb_client.as_ref().expect("no ddb client"),
-        &env.ddb_table_name,
-        &deck_key.1,
-        author_id,
-    )
-    .await;
-
-    match delete_deck_result {
+    let user_id = &get_interaction_user(interaction)?.id;
+    let author_id = &deck_key.0;
+
+    let deck = match env.deck_repository.get_deck(&deck_key.1, author_id).await {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "deck_not_found_error_message",
+                env,
+            ))
+        }
+    };
+
+    if get_deck_owner(&deck) != user_id {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "deck_not_owner_error_message",
+            env,
+        ));
+    }
+
+    let delete_deck_result = env
+        .deck_repository
+        .delete_deck(&deck_key.1, author_id)
+        .await;
+
+    if delete_deck_result.is_ok() {
+        let _ = tokio::join!(
+            env.deck_repository
+                .delete_deck_versions(&deck_key.1, author_id),
+            env.deck_repository
+                .delete_deck_index(&deck_key.1, author_id),
+            env.deck_repository
+                .delete_deck_votes(&deck_key.1, author_id),
+            env.deck_repository
+                .delete_deck_from_guild_collections(&deck_key.1, author_id)
+        );
+    }
+
+    Ok(match delete_deck_result {
         Ok(_) => MessageResponse {
             r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
             data: Some(DiscordInteractionResponseMessage {
@@ -88,5 +112,137 @@ pub async fn delete_deck(
             // todo: check for error type
             build_error_message_response(&lang_id, "generic_error_message", env)
         }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::deck::DeckData;
+    use crate::models::deck::DeckMetadata;
+    use crate::models::deck::DeckVote;
+    use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordGuildMember;
+    use crate::models::discord::DiscordInteractionData;
+    use crate::models::discord::DiscordInteractionOptionValue;
+    use crate::models::discord::DiscordInteractionOptions;
+    use crate::models::discord::DiscordInteractionType;
+    use crate::models::discord::DiscordUser;
+    use crate::test_utils::build_deck;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    #[tokio::test]
+    async fn sanity_delete_deck() {
+        let env = build_mocked_binahbot_env();
+        let deck = build_deck("snowflake", "Turbo Nikolai", build_deck_data());
+        env.deck_repository.put_deck(&deck, None).await.unwrap();
+        env.deck_repository
+            .put_deck_index(&deck, &["tag#burn".to_string()])
+            .await
+            .unwrap();
+        env.deck_repository
+            .put_deck_vote(
+                &deck.name,
+                &deck.author_id,
+                "voter",
+                &DeckVote::default(),
+                &DeckVote::default(),
+            )
+            .await
+            .unwrap();
+        env.deck_repository
+            .put_guild_collection_deck("guild", &DeckMetadata::from(&deck), "snowflake")
+            .await
+            .unwrap();
+
+        let response = delete_deck(&build_discord_interaction("snowflake#Turbo Nikolai"), &env)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some(DiscordMessageFlag::EphemeralMessage as i32),
+            response.data.unwrap().flags
+        );
+        assert!(env
+            .deck_repository
+            .get_deck("Turbo Nikolai", "snowflake")
+            .await
+            .is_err());
+        assert!(env
+            .deck_repository
+            .search_deck_index("tag#burn")
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(env
+            .deck_repository
+            .list_guild_collection("guild")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn sanity_delete_deck_not_owner() {
+        let env = build_mocked_binahbot_env();
+        let deck = build_deck("1", "Turbo Nikolai", build_deck_data());
+        env.deck_repository.put_deck(&deck, None).await.unwrap();
+
+        delete_deck(&build_discord_interaction("1#Turbo Nikolai"), &env)
+            .await
+            .unwrap();
+
+        assert!(env
+            .deck_repository
+            .get_deck("Turbo Nikolai", "1")
+            .await
+            .is_ok());
+    }
+
+    fn build_deck_data() -> DeckData {
+        DeckData {
+            keypage_id: Some("250023".to_string()),
+            passive_ids: Vec::new(),
+            combat_page_ids: std::array::from_fn(|_| Some("608014".to_string())),
+        }
+    }
+
+    fn build_discord_interaction(deck_name: &str) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommand,
+            data: Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: "deletedeck".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
+                    options: Some(vec![DiscordInteractionOptions {
+                        name: "name".to_string(),
+                        name_localizations: None,
+                        value: DiscordInteractionOptionValue::String(deck_name.to_string()),
+                        focused: None,
+                    }]),
+                    target_id: None,
+                    resolved: None,
+                },
+            )),
+            channel_id: None,
+            guild_id: Some("guild".to_string()),
+            token: "token".to_string(),
+            locale: None,
+            guild_locale: None,
+            user: None,
+            member: Some(DiscordGuildMember {
+                user: Some(DiscordUser {
+                    id: "snowflake".to_string(),
+                    username: "username".to_string(),
+                    avatar: Some("hash".to_string()),
+                }),
+                permissions: None,
+            }),
+            message: None,
+        }
     }
 }
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::Loader;
+use ruina::ruina_common::localizations::common::Locale;
+use unic_langid::LanguageIdentifier;
+
+use crate::error::BinahBotError;
+use crate::macros::cast_enum_variant;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::deck::Deck;
+use crate::models::deck::DeckOrigin;
+use crate::models::discord::AllowedMentions;
+use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionOptionValue;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::MessageResponse;
+use crate::thumbnail::generate_thumbnail;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
+use crate::utils::get_interaction_user;
+use crate::utils::get_option_value;
+use crate::utils::get_required_string_option;
+
+use super::deck_utils::parse_deck_name_option;
+use super::deck_utils::put_deck_with_history;
+
+pub async fn fork_deck(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
+
+    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+
+    let name_option = get_required_string_option("name", command_args)?;
+    let deck_key = match parse_deck_name_option(name_option) {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "deck_not_found_error_message",
+                env,
+            ))
+        }
+    };
+    let new_name_option = get_option_value("new_name", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+
+    let author = get_interaction_user(interaction)?;
+
+    let source = match env.deck_repository.get_deck(&deck_key.1, &deck_key.0).await {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "deck_not_found_error_message",
+                env,
+            ))
+        }
+    };
+
+    let new_name = new_name_option.unwrap_or(&source.name);
+    if source.author_id == author.id && &source.name == new_name {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "fork_deck_same_name_error_message",
+            env,
+        ));
+    }
+
+    let deck = build_fork(&source, &author.id, &author.username, new_name);
+
+    let _ = generate_thumbnail(
+        env.lambda_client.as_ref(),
+        &env.thumbnail_lambda_name,
+        &deck,
+        &Locale::from(&get_binahbot_locale(interaction)),
+    )
+    .await;
+
+    Ok(
+        match put_deck_with_history(&deck, None, &author.id, env).await {
+            Ok(_) => MessageResponse {
+                r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+                data: Some(DiscordInteractionResponseMessage {
+                    allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+                    content: None,
+                    embeds: Some(vec![DiscordEmbed {
+                        title: None,
+                        description: Some(env.locales.lookup_with_args(
+                            &lang_id,
+                            "fork_deck_success",
+                            &HashMap::from([
+                                ("source_name", FluentValue::from(&source.name)),
+                                ("deck_name", FluentValue::from(&deck.name)),
+                            ]),
+                        )),
+                        color: Some(DiscordEmbedColors::Default as i32),
+                        image: None,
+                        thumbnail: None,
+                        footer: None,
+                        author: None,
+                        url: None,
+                        fields: None,
+                    }]),
+                    flags: Some(DiscordMessageFlag::EphemeralMessage as i32),
+                    components: None,
+                }),
+            },
+            Err(_) => {
+                // todo: check for error type
+                build_error_message_response(&lang_id, "generic_error_message", env)
+            }
+        },
+    )
+}
+
+fn build_fork(source: &Deck, author_id: &str, author_name: &str, name: &str) -> Deck {
+    Deck {
+        name: name.to_string(),
+        author_id: author_id.to_string(),
+        author_name: author_name.to_string(),
+        description: source.description.clone(),
+        deck_data: source.deck_data.clone(),
+        tiph_deck: source.tiph_deck.clone(),
+        version: 1,
+        tags: source.tags.clone(),
+        forked_from: Some(DeckOrigin {
+            author_id: source.author_id.clone(),
+            deck_name: source.name.clone(),
+            version: source.version,
+        }),
+        owner_id: None,
+        editors: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::deck::DeckData;
+
+    #[test]
+    fn sanity_build_fork() {
+        let source = Deck {
+            name: "Turbo Nikolai".to_string(),
+            author_id: "1".to_string(),
+            author_name: "gh".to_string(),
+            description: Some("burn".to_string()),
+            deck_data: DeckData {
+                keypage_id: Some("250023".to_string()),
+                passive_ids: vec!["230018".to_string()],
+                combat_page_ids: std::array::from_fn(|_| Some("608014".to_string())),
+            },
+            tiph_deck: None,
+            version: 4,
+            tags: vec!["burn".to_string()],
+            forked_from: None,
+            owner_id: None,
+            editors: Vec::new(),
+        };
+
+        let fork = build_fork(&source, "2", "al", "Turbo Nikolai but better");
+        assert_eq!("Turbo Nikolai but better", fork.name);
+        assert_eq!("2", fork.author_id);
+        assert_eq!(1, fork.version);
+        assert_eq!(source.deck_data, fork.deck_data);
+        assert_eq!(source.tags, fork.tags);
+        assert_eq!(
+            Some(DeckOrigin {
+                author_id: "1".to_string(),
+                deck_name: "Turbo Nikolai".to_string(),
+                version: 4,
+            }),
+            fork.forked_from
+        );
+    }
+}
@@ -8,32 +8,33 @@ use ruina::ruina_common::game_objects::common::PageType;
 use ruina::ruina_common::localizations::common::Locale;
 use unic_langid::LanguageIdentifier;
 
-use crate::ddb::list_decks;
+use crate::error::BinahBotError;
 use crate::macros::cast_enum_variant;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::BinahBotLocale;
 use crate::models::deck::DeckMetadata;
 use crate::models::discord::AutocompleteResponse;
 use crate::models::discord::DiscordInteraction;
-use crate::models::discord::DiscordInteractionData;
 use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionOptions;
 use crate::models::discord::DiscordInteractionResponseAutocomplete;
 use crate::models::discord::DiscordInteractionResponseType;
+use crate::utils::get_command_options;
 use crate::utils::get_disambiguation_format;
 use crate::utils::get_focused_option;
+use crate::utils::get_interaction_user;
 use crate::utils::get_option_value;
 
+use super::deck_utils::get_editor_index_key;
+use super::deck_utils::get_page_index_key;
+use super::deck_utils::get_tag_index_key;
+use super::deck_utils::search_decks;
+
 pub async fn list_deck(
     interaction: &DiscordInteraction,
     env: &BinahBotEnvironment,
-) -> AutocompleteResponse {
-    let command_args = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-        .and_then(|x| x.options.as_ref())
-        .unwrap();
+) -> Result<AutocompleteResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
 
     let binah_locale: BinahBotLocale = interaction
         .locale
@@ -44,29 +45,69 @@ pub async fn list_deck(
     let card_locale = Locale::from(&binah_locale);
     let lang_id = LanguageIdentifier::from(&binah_locale);
 
-    let incomplete_name = get_option_value("name", command_args)
+    let focused = get_focused_option(command_args).map(|x| x.name.as_str());
+    // deck name options are "name" for most commands, but /deckdiff takes two decks
+    let incomplete_name = focused
+        .and_then(|x| get_option_value(x, command_args))
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
     let keypage_option = get_option_value("keypage", command_args)
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
     let author_id_option = get_option_value("author", command_args)
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
-    let focused = get_focused_option(command_args).map(|x| x.name.as_str());
+    let tag_option = get_option_value("tag", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+    let page_option = get_option_value("page", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+    let passive_option = get_option_value("passive", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
 
-    tracing::info!("Got the following: incomplete_name={:?}, keypage_option={:?}, author_id_option={:?}, focused={:?}",
+    tracing::info!("Got the following: incomplete_name={:?}, keypage_option={:?}, author_id_option={:?}, tag_option={:?}, page_option={:?}, passive_option={:?}, focused={:?}",
         incomplete_name,
         keypage_option,
         author_id_option,
+        tag_option,
+        page_option,
+        passive_option,
         focused
     );
 
+    let index_keys = tag_option
+        .map(|x| get_tag_index_key(x))
+        .into_iter()
+        .chain(page_option.map(|x| get_page_index_key(PageType::CombatPage, x)))
+        .chain(passive_option.map(|x| get_page_index_key(PageType::Passive, x)))
+        .collect::<Vec<_>>();
+
     let choices = match focused {
         Some(x) => match x {
-            "keypage" => get_choices_by_keypage_query(keypage_option, &card_locale, &lang_id, env),
+            "keypage" => get_choices_by_page_query(
+                PageType::KeyPage,
+                incomplete_name,
+                &card_locale,
+                &lang_id,
+                env,
+            ),
+            "page" => get_choices_by_page_query(
+                PageType::CombatPage,
+                incomplete_name,
+                &card_locale,
+                &lang_id,
+                env,
+            ),
+            "passive" => get_choices_by_page_query(
+                PageType::Passive,
+                incomplete_name,
+                &card_locale,
+                &lang_id,
+                env,
+            ),
             _ => {
                 get_choices_by_deck_name(
                     incomplete_name,
                     author_id_option,
                     keypage_option,
+                    &index_keys,
+                    interaction.guild_id.as_ref(),
                     &lang_id,
                     env,
                 )
@@ -78,24 +119,19 @@ pub async fn list_deck(
 
     tracing::info!("Returning choices={:?}", choices);
 
-    AutocompleteResponse {
+    Ok(AutocompleteResponse {
         r#type: DiscordInteractionResponseType::ApplicationCommandAutocompleteResult,
         data: Some(DiscordInteractionResponseAutocomplete {
             choices: Some(choices),
         }),
-    }
+    })
 }
 
 pub async fn list_my_decks(
     interaction: &DiscordInteraction,
     env: &BinahBotEnvironment,
-) -> AutocompleteResponse {
-    let command_args = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-        .and_then(|x| x.options.as_ref())
-        .unwrap();
+) -> Result<AutocompleteResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
 
     let binah_locale: BinahBotLocale = interaction
         .locale
@@ -107,11 +143,7 @@ pub async fn list_my_decks(
 
     let deck_name_query = get_option_value("name", command_args)
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
-    let author_id = &interaction
-        .user
-        .as_ref()
-        .unwrap_or(interaction.member.as_ref().unwrap().user.as_ref().unwrap())
-        .id;
+    let author_id = &get_interaction_user(interaction)?.id;
 
     let focused = get_focused_option(command_args).map(|x| x.name.as_str());
 
@@ -123,10 +155,7 @@ pub async fn list_my_decks(
     );
     let choices = match focused {
         Some(x) => match x {
-            "name" => {
-                get_choices_by_deck_name(deck_name_query, Some(author_id), None, &lang_id, env)
-                    .await
-            }
+            "name" => get_choices_by_editor(deck_name_query, author_id, &lang_id, env).await,
             _ => Vec::new(),
         },
         None => unreachable!(),
@@ -134,15 +163,16 @@ pub async fn list_my_decks(
 
     tracing::info!("Returning choices={:?}", choices);
 
-    AutocompleteResponse {
+    Ok(AutocompleteResponse {
         r#type: DiscordInteractionResponseType::ApplicationCommandAutocompleteResult,
         data: Some(DiscordInteractionResponseAutocomplete {
             choices: Some(choices),
         }),
-    }
+    })
 }
 
-fn get_choices_by_keypage_query(
+fn get_choices_by_page_query(
+    page_type: PageType,
     query: Option<&String>,
     card_locale: &Locale,
     lang_id: &LanguageIdentifier,
@@ -151,7 +181,7 @@ fn get_choices_by_keypage_query(
     let ids = ruina::ruina_index::query(query.unwrap_or(&"".to_string()));
 
     ids.iter()
-        .filter(|x| x.0 == PageType::KeyPage)
+        .filter(|x| x.0 == page_type)
         .take(10)
         .map(|parsed_id| {
             let display_name = get_disambiguation_format(parsed_id, card_locale, lang_id, env);
@@ -170,45 +200,111 @@ async fn get_choices_by_deck_name(
     query: Option<&String>,
     author_id_option: Option<&String>,
     keypage_option: Option<&String>,
+    index_keys: &[String],
+    guild_id_option: Option<&String>,
     lang_id: &LanguageIdentifier,
     env: &BinahBotEnvironment,
 ) -> Vec<DiscordInteractionOptions> {
-    let decks = list_decks(
-        env.ddb_client.as_ref().expect("no ddb client"),
-        &env.ddb_table_name,
-        author_id_option.map(|x| x.as_str()),
-        keypage_option.map(|x| x.as_str()),
-    )
-    .await;
+    let collection = match guild_id_option {
+        Some(guild_id) => env
+            .deck_repository
+            .list_guild_collection(guild_id)
+            .await
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    let decks = if index_keys.is_empty() {
+        env.deck_repository
+            .list_decks(
+                author_id_option.map(|x| x.as_str()),
+                keypage_option.map(|x| x.as_str()),
+            )
+            .await
+    } else {
+        let index_keys = index_keys
+            .iter()
+            .cloned()
+            .chain(keypage_option.map(|x| get_page_index_key(PageType::KeyPage, x)))
+            .collect::<Vec<_>>();
+        search_decks(&index_keys, env).await.map(|x| {
+            x.into_iter()
+                .filter(|y| author_id_option.is_none_or(|z| &y.author_id == z))
+                .collect()
+        })
+    };
 
     tracing::info!("got decks={:?}", decks);
 
+    match decks {
+        Ok(results) => build_deck_choices(query, &results, &collection, lang_id, env),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn build_deck_choices(
+    query: Option<&String>,
+    decks: &[DeckMetadata],
+    collection: &[DeckMetadata],
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> Vec<DiscordInteractionOptions> {
     let binding = &"".to_string();
     let query = query.unwrap_or(binding).to_lowercase();
 
-    match decks {
-        Ok(results) => get_top_matches(&query, &results, 10)
-            .into_iter()
-            .map(|x| {
-                let display_name = env.locales.lookup_with_args(
-                    lang_id,
-                    "list_deck_name_author",
-                    &HashMap::from([
-                        ("deck_name", FluentValue::from(&x.name)),
-                        ("author", FluentValue::from(&x.author_name)),
-                    ]),
-                );
-
-                DiscordInteractionOptions {
-                    name: display_name,
-                    name_localizations: None,
-                    value: DiscordInteractionOptionValue::String(get_discord_option_value(x)),
-                    focused: None,
-                }
-            })
-            .collect::<Vec<_>>(),
-        Err(_) => Vec::new(),
+    get_top_matches_collection_first(&query, decks, collection, 10)
+        .into_iter()
+        .map(|(x, in_collection)| {
+            let display_name = env.locales.lookup_with_args(
+                lang_id,
+                if in_collection {
+                    "list_deck_collection_name_author"
+                } else {
+                    "list_deck_name_author"
+                },
+                &HashMap::from([
+                    ("deck_name", FluentValue::from(&x.name)),
+                    ("author", FluentValue::from(&x.author_name)),
+                ]),
+            );
+
+            DiscordInteractionOptions {
+                name: display_name,
+                name_localizations: None,
+                value: DiscordInteractionOptionValue::String(get_discord_option_value(&x)),
+                focused: None,
+            }
+        })
+        .collect::<Vec<_>>()
+}
+
+// decks the user authored, plus decks they were made an editor or owner of
+async fn get_choices_by_editor(
+    query: Option<&String>,
+    user_id: &str,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> Vec<DiscordInteractionOptions> {
+    let editor_index_key = get_editor_index_key(user_id);
+
+    let (authored, editable) = tokio::join!(
+        env.deck_repository.list_decks(Some(user_id), None),
+        env.deck_repository.search_deck_index(&editor_index_key)
+    );
+
+    tracing::info!("got authored={:?}, editable={:?}", authored, editable);
+
+    let mut decks = authored.unwrap_or_default();
+    for deck in editable.unwrap_or_default() {
+        if !decks
+            .iter()
+            .any(|x| x.author_id == deck.author_id && x.name == deck.name)
+        {
+            decks.push(deck);
+        }
     }
+
+    build_deck_choices(query, &decks, &[], lang_id, env)
 }
 
 fn get_top_matches<'a>(
@@ -230,6 +326,31 @@ fn get_top_matches<'a>(
         .collect()
 }
 
+// decks curated by the current server are suggested ahead of everything else
+fn get_top_matches_collection_first(
+    query: &str,
+    decks: &[DeckMetadata],
+    collection: &[DeckMetadata],
+    top: usize,
+) -> Vec<(DeckMetadata, bool)> {
+    let (preferred, others): (Vec<_>, Vec<_>) = decks.iter().cloned().partition(|x| {
+        collection
+            .iter()
+            .any(|y| x.author_id == y.author_id && x.name == y.name)
+    });
+
+    get_top_matches(query, &preferred, top)
+        .into_iter()
+        .map(|x| (x.clone(), true))
+        .chain(
+            get_top_matches(query, &others, top)
+                .into_iter()
+                .map(|x| (x.clone(), false)),
+        )
+        .take(top)
+        .collect()
+}
+
 fn get_discord_option_value(deck: &DeckMetadata) -> String {
     format!("{}#{}", deck.author_id, deck.name)
 }
@@ -237,6 +358,17 @@ fn get_discord_option_value(deck: &DeckMetadata) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::deck::deck_utils::put_deck_with_history;
+    use crate::models::deck::Deck;
+    use crate::models::deck::DeckData;
+    use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordGuildMember;
+    use crate::models::discord::DiscordInteractionData;
+    use crate::models::discord::DiscordInteractionType;
+    use crate::models::discord::DiscordUser;
+    use crate::test_utils::build_deck;
+    use crate::test_utils::build_mocked_binahbot_env;
 
     #[test]
     fn sanity_get_top_matches() {
@@ -293,4 +425,185 @@ mod tests {
         assert_eq!(1, top.len());
         assert_eq!(Some(&"Crimson Claws"), top.get(0));
     }
+
+    #[test]
+    fn should_rank_collection_decks_first() {
+        let build = |name: &str, author_id: &str| DeckMetadata {
+            name: name.to_string(),
+            author_id: author_id.to_string(),
+            author_name: "gh".to_string(),
+        };
+        let decks = vec![
+            build("Turbo Nikolai", "1"),
+            build("Turbo Nikolai", "2"),
+            build("Crimson Claws", "3"),
+        ];
+        let collection = vec![build("Turbo Nikolai", "2"), build("Unrelated", "4")];
+
+        let top = get_top_matches_collection_first("turbo nikolai", &decks, &collection, 10)
+            .into_iter()
+            .map(|(x, in_collection)| (x.author_id, in_collection))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![("2".to_string(), true), ("1".to_string(), false)], top);
+
+        let top = get_top_matches_collection_first("turbo nikolai", &decks, &collection, 1);
+        assert_eq!(1, top.len());
+        assert!(top[0].1);
+    }
+
+    #[tokio::test]
+    async fn should_filter_by_page_and_passive() {
+        let env = build_mocked_binahbot_env();
+        let decks = [
+            build_deck(
+                "1",
+                "Turbo Nikolai",
+                build_deck_data("250023", &["230018", "240118"], "608014"),
+            ),
+            build_deck(
+                "1",
+                "Turbo Xiao",
+                build_deck_data("250036", &["240118", "250025"], "608004"),
+            ),
+            build_deck(
+                "1",
+                "Turbo Claws",
+                build_deck_data("250036", &["250025"], "608014"),
+            ),
+        ];
+        for deck in decks.iter() {
+            put_deck_with_history(deck, None, &deck.author_id, &env)
+                .await
+                .unwrap();
+        }
+
+        let passive_240118 = list_deck_names(&[("passive", "240118")], &env).await;
+        assert_eq!(vec!["Turbo Nikolai", "Turbo Xiao"], passive_240118);
+
+        let page_608014 = list_deck_names(&[("page", "608014")], &env).await;
+        assert_eq!(vec!["Turbo Claws", "Turbo Nikolai"], page_608014);
+
+        let both = list_deck_names(&[("page", "608014"), ("passive", "250025")], &env).await;
+        assert_eq!(vec!["Turbo Claws"], both);
+
+        let none = list_deck_names(&[("passive", "230018"), ("keypage", "250036")], &env).await;
+        assert!(none.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sanity_list_my_decks() {
+        let env = build_mocked_binahbot_env();
+        let decks = [
+            Deck {
+                author_id: "snowflake".to_string(),
+                ..build_deck(
+                    "1",
+                    "Turbo Nikolai",
+                    build_deck_data("250023", &[], "608014"),
+                )
+            },
+            Deck {
+                editors: vec!["snowflake".to_string()],
+                ..build_deck("1", "Turbo Xiao", build_deck_data("250036", &[], "608004"))
+            },
+            build_deck("1", "Turbo Claws", build_deck_data("250036", &[], "608014")),
+        ];
+        for deck in decks.iter() {
+            put_deck_with_history(deck, None, &deck.author_id, &env)
+                .await
+                .unwrap();
+        }
+
+        let interaction = build_autocomplete_interaction(&[]);
+        let response = list_my_decks(&interaction, &env).await.unwrap();
+
+        let mut values = response
+            .data
+            .and_then(|x| x.choices)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|x| cast_enum_variant!(x.value, DiscordInteractionOptionValue::String))
+            .collect::<Vec<_>>();
+        values.sort();
+        assert_eq!(vec!["1#Turbo Xiao", "snowflake#Turbo Nikolai"], values);
+    }
+
+    async fn list_deck_names(options: &[(&str, &str)], env: &BinahBotEnvironment) -> Vec<String> {
+        let response = list_deck(&build_autocomplete_interaction(options), env)
+            .await
+            .unwrap();
+        let mut names = response
+            .data
+            .and_then(|x| x.choices)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|x| {
+                let value = cast_enum_variant!(x.value, DiscordInteractionOptionValue::String)
+                    .expect("non-string choice");
+                value.split_once('#').unwrap().1.to_string()
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    fn build_deck_data(keypage_id: &str, passive_ids: &[&str], page_id: &str) -> DeckData {
+        DeckData {
+            keypage_id: Some(keypage_id.to_string()),
+            passive_ids: passive_ids.iter().map(|x| x.to_string()).collect(),
+            combat_page_ids: std::array::from_fn(|_| Some(page_id.to_string())),
+        }
+    }
+
+    fn build_autocomplete_interaction(options: &[(&str, &str)]) -> DiscordInteraction {
+        let options = build_options(options);
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommandAutocomplete,
+            data: Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: "deck".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
+                    options: Some(options),
+                    target_id: None,
+                    resolved: None,
+                },
+            )),
+            channel_id: None,
+            guild_id: None,
+            token: "token".to_string(),
+            locale: Some("en-US".to_string()),
+            guild_locale: None,
+            user: None,
+            member: Some(DiscordGuildMember {
+                user: Some(DiscordUser {
+                    id: "snowflake".to_string(),
+                    username: "username".to_string(),
+                    avatar: None,
+                }),
+                permissions: None,
+            }),
+            message: None,
+        }
+    }
+
+    fn build_options(options: &[(&str, &str)]) -> Vec<DiscordInteractionOptions> {
+        options
+            .iter()
+            .map(|(name, value)| DiscordInteractionOptions {
+                name: name.to_string(),
+                name_localizations: None,
+                value: DiscordInteractionOptionValue::String(value.to_string()),
+                focused: None,
+            })
+            .chain([DiscordInteractionOptions {
+                name: "name".to_string(),
+                name_localizations: None,
+                value: DiscordInteractionOptionValue::String("turbo".to_string()),
+                focused: Some(true),
+            }])
+            .collect()
+    }
 }
@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::error::Error;
 use std::iter;
 
@@ -15,58 +14,62 @@ use ruina::ruina_reparser::get_passive_by_id;
 use ruina::ruina_reparser::get_passive_locales_by_id;
 use unic_langid::LanguageIdentifier;
 
-use crate::ddb::get_deck;
 use crate::deck::deck_utils::get_user;
+use crate::error::BinahBotError;
+use crate::guild_config::find_exceeded_spoiler_limit;
+use crate::guild_config::get_spoiler_limits;
+use crate::guild_config::SpoilerLimit;
 use crate::macros::cast_enum_variant;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::BinahBotLocale;
 use crate::models::binahbot::DiscordEmbedColors;
 use crate::models::deck::Deck;
 use crate::models::deck::DeckData;
-use crate::models::discord::ActionRowComponent;
+use crate::models::deck::DeckIndexEntry;
+use crate::models::deck::DeckMetadata;
 use crate::models::discord::AllowedMentions;
-use crate::models::discord::DiscordComponent;
-use crate::models::discord::DiscordComponentType;
 use crate::models::discord::DiscordEmbed;
 use crate::models::discord::DiscordEmbedAuthor;
 use crate::models::discord::DiscordEmbedFields;
 use crate::models::discord::DiscordEmbedFooter;
 use crate::models::discord::DiscordEmbedImage;
 use crate::models::discord::DiscordInteraction;
-use crate::models::discord::DiscordInteractionData;
 use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionResponseMessage;
 use crate::models::discord::DiscordInteractionResponseType;
 use crate::models::discord::DiscordMessageFlag;
 use crate::models::discord::MessageResponse;
 use crate::thumbnail::generate_thumb_name;
-use crate::utils::build_delete_button_component;
+use crate::thumbnail::generate_thumbnail;
 use crate::utils::build_error_message_response;
 use crate::utils::get_binahbot_locale;
+use crate::utils::get_choice_display_name;
+use crate::utils::get_command_options;
 use crate::utils::get_option_value;
-
+use crate::utils::get_required_string_option;
+use crate::utils::get_reqwest_client;
+
+use super::deck_utils::aggregate_count;
+use super::deck_utils::dedup_preserve_order;
+use super::deck_utils::format_to_list;
+use super::deck_utils::get_deck_ref;
+use super::deck_utils::get_ref_index_key;
 use super::deck_utils::parse_deck_name_option;
+use super::deck_vote::build_deck_components;
 
 pub async fn read_deck(
     interaction: &DiscordInteraction,
     env: &BinahBotEnvironment,
-) -> MessageResponse {
-    let command_args = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-        .and_then(|x| x.options.as_ref())
-        .unwrap();
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
 
     let request_locale = get_binahbot_locale(interaction);
     let lang_id = LanguageIdentifier::from(&request_locale);
 
-    let name_option = get_option_value("name", command_args)
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
-        .unwrap();
+    let name_option = get_required_string_option("name", command_args)?;
     let deck_key = match parse_deck_name_option(name_option) {
         Ok(x) => x,
-        Err(_) => return deck_not_found(&lang_id, env),
+        Err(_) => return Ok(deck_not_found(&lang_id, env)),
     };
 
     let is_private = get_option_value("private", command_args)
@@ -75,38 +78,56 @@ pub async fn read_deck(
 
     let flags = is_private.then_some(DiscordMessageFlag::EphemeralMessage as i32);
 
-    let deck_result = get_deck(
-        env.ddb_client.as_ref().unwrap(),
-        &env.ddb_table_name,
-        &deck_key.1,
-        &deck_key.0,
-    )
-    .await;
-
-    let components =
-        (!is_private).then_some(vec![DiscordComponent::ActionRow(ActionRowComponent {
-            r#type: DiscordComponentType::ActionRow,
-            components: vec![DiscordComponent::Button(build_delete_button_component(
-                &lang_id, env,
-            ))],
-        })]);
-
-    match deck_result {
+    let deck_result = env.deck_repository.get_deck(&deck_key.1, &deck_key.0).await;
+
+    Ok(match deck_result {
         Ok(x) => {
-            let max_spoiler_chapter = &interaction
-                .channel_id
-                .as_ref()
-                .and_then(|x| env.spoiler_config.get(&x));
             let chapter = calculate_deck_chapter(&x.deck_data);
-            if let Some(max_spoiler_chapter) = max_spoiler_chapter {
-                if !is_private && chapter > **max_spoiler_chapter {
-                    return spoiler_found(&x.name, &chapter, max_spoiler_chapter, &lang_id, env);
+            let spoiler_limits = get_spoiler_limits(interaction, is_private, env).await;
+            if let Some(spoiler_limit) = find_exceeded_spoiler_limit(&chapter, &spoiler_limits) {
+                return Ok(spoiler_found(
+                    &x.name,
+                    &chapter,
+                    spoiler_limit,
+                    &lang_id,
+                    env,
+                ));
+            }
+
+            // decks saved before the index existed still need a reference for their vote buttons
+            let _ = env
+                .deck_repository
+                .put_deck_index_entry(&DeckIndexEntry {
+                    index_key: get_ref_index_key(&get_deck_ref(&x.author_id, &x.name)),
+                    metadata: DeckMetadata {
+                        name: x.name.clone(),
+                        author_id: x.author_id.clone(),
+                        author_name: x.author_name.clone(),
+                    },
+                })
+                .await;
+
+            // decks saved before the deck card template existed only have a grid thumbnail
+            let _ = generate_thumbnail(
+                env.lambda_client.as_ref(),
+                &env.thumbnail_lambda_name,
+                &x,
+                &Locale::from(&request_locale),
+            )
+            .await;
+
+            let embed = match transform_deck(&x, &request_locale, env).await {
+                Ok(x) => x,
+                Err(e) => {
+                    tracing::error!("Failed to transform deck: {}", e);
+                    return Ok(build_error_message_response(
+                        &lang_id,
+                        "generic_error_message",
+                        env,
+                    ));
                 }
             };
-
-            let embed = transform_deck(&x, &request_locale, env)
-                .await
-                .expect("failed deck processing");
+            let components = (!is_private).then(|| build_deck_components(&x, &lang_id, env));
             MessageResponse {
                 r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
                 data: Some(DiscordInteractionResponseMessage {
@@ -122,10 +143,10 @@ pub async fn read_deck(
             // todo: check for error type
             build_error_message_response(&lang_id, "generic_error_message", env)
         }
-    }
+    })
 }
 
-async fn transform_deck(
+pub(crate) async fn transform_deck(
     deck: &Deck,
     request_locale: &BinahBotLocale,
     env: &BinahBotEnvironment,
@@ -142,17 +163,18 @@ async fn transform_deck(
     let deck_preview_img = format!(
         "https://{0}.s3.amazonaws.com/deck_thumbnails/{1}.png",
         env.s3_bucket_name,
-        generate_thumb_name(&deck.deck_data.combat_page_ids)
+        generate_thumb_name(deck, &card_locale)
     );
 
-    let user = get_user(
-        env.reqwest_client.as_ref().expect("no http client"),
-        &env.discord_secrets.bot_token,
-        &deck.author_id,
-    )
-    .await;
+    // the author's current name and avatar are optional, so decks still show without a client
+    let user = match get_reqwest_client(env) {
+        Ok(client) => get_user(client, &env.discord_secrets.bot_token, &deck.author_id)
+            .await
+            .ok(),
+        Err(_) => None,
+    };
 
-    let avatar_hash = user.as_ref().ok().and_then(|x| x.avatar.clone()).map(|x| {
+    let avatar_hash = user.as_ref().and_then(|x| x.avatar.clone()).map(|x| {
         format!(
             "https://cdn.discordapp.com/avatars/{0}/{1}.png",
             &deck.author_id, &x
@@ -246,6 +268,33 @@ async fn transform_deck(
         inline: Some(true),
     });
 
+    if !deck.tags.is_empty() {
+        fields.push(DiscordEmbedFields {
+            name: env.locales.lookup(&lang_id, "read_deck_tags_header"),
+            value: deck.tags.join(", "),
+            inline: Some(false),
+        });
+    };
+
+    if let Some(origin) = deck.forked_from.as_ref() {
+        fields.push(DiscordEmbedFields {
+            name: env.locales.lookup(&lang_id, "read_deck_forked_from_header"),
+            value: env.locales.lookup_with_args(
+                &lang_id,
+                "read_deck_forked_from",
+                &HashMap::from([
+                    (
+                        "author",
+                        FluentValue::from(format!("<@{}>", origin.author_id)),
+                    ),
+                    ("deck_name", FluentValue::from(&origin.deck_name)),
+                    ("version", FluentValue::from(origin.version)),
+                ]),
+            ),
+            inline: Some(false),
+        });
+    };
+
     Ok(DiscordEmbed {
         title: Some(deck.name.clone()),
         description: deck.description.clone(),
@@ -268,31 +317,7 @@ async fn transform_deck(
     })
 }
 
-// todo: move to utils
-fn format_to_list<T: AsRef<str>>(v: &[T]) -> String {
-    v.iter()
-        .map(|x| format!("- {}", x.as_ref()))
-        .collect::<Vec<_>>()
-        .join("\n")
-}
-
-fn aggregate_count<T: AsRef<str> + Eq + std::hash::Hash>(v: &[T]) -> HashMap<&T, i32> {
-    let mut ret_val = HashMap::new();
-
-    v.iter().for_each(|x| {
-        *ret_val.entry(x).or_insert(0) += 1;
-    });
-
-    ret_val
-}
-
-fn dedup_preserve_order<T: AsRef<str> + Eq + std::hash::Hash + Clone>(v: &mut Vec<T>) {
-    let mut set = HashSet::new();
-
-    v.retain(|x| set.insert(x.clone()));
-}
-
-fn calculate_deck_chapter(deck: &DeckData) -> Chapter {
+pub fn calculate_deck_chapter(deck: &DeckData) -> Chapter {
     let mut passive_costs = 0;
     let keypage_chapter = deck
         .keypage_id
@@ -345,13 +370,17 @@ fn deck_not_found(lang_id: &LanguageIdentifier, env: &BinahBotEnvironment) -> Me
     }
 }
 
-fn spoiler_found(
+pub fn spoiler_found(
     deck_name: &str,
     chapter: &Chapter,
-    configured_chapter: &Chapter,
+    spoiler_limit: &SpoilerLimit,
     lang_id: &LanguageIdentifier,
     env: &BinahBotEnvironment,
 ) -> MessageResponse {
+    let message_key = match spoiler_limit {
+        SpoilerLimit::Channel(_) => "deck_spoiler_enforcement_message",
+        SpoilerLimit::Progress(_) => "deck_progress_enforcement_message",
+    };
     MessageResponse {
         r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
         data: Some(DiscordInteractionResponseMessage {
@@ -361,13 +390,24 @@ fn spoiler_found(
                 title: None,
                 description: Some(env.locales.lookup_with_args(
                     &lang_id,
-                    "deck_spoiler_enforcement_message",
+                    message_key,
                     &HashMap::from([
                         ("deck_name", FluentValue::from(deck_name)),
-                        ("chapter", FluentValue::from(chapter.to_string())),
+                        (
+                            "chapter",
+                            FluentValue::from(get_choice_display_name(
+                                &chapter.to_string(),
+                                lang_id,
+                                env,
+                            )),
+                        ),
                         (
                             "configured_chapter",
-                            FluentValue::from(configured_chapter.to_string()),
+                            FluentValue::from(get_choice_display_name(
+                                &spoiler_limit.chapter().to_string(),
+                                lang_id,
+                                env,
+                            )),
                         ),
                     ]),
                 )),
@@ -388,6 +428,16 @@ fn spoiler_found(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::deck::deck_utils::put_deck_with_history;
+    use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordGuildMember;
+    use crate::models::discord::DiscordInteractionData;
+    use crate::models::discord::DiscordInteractionOptions;
+    use crate::models::discord::DiscordInteractionType;
+    use crate::models::discord::DiscordUser;
+    use crate::test_utils::build_deck;
+    use crate::test_utils::build_mocked_binahbot_env;
 
     #[test]
     fn sanity_parse_deck_name_option() {
@@ -414,4 +464,78 @@ mod tests {
         assert_eq!(4, vec.len());
         assert_eq!(vec!["a", "b", "d", "c"], vec);
     }
+
+    #[tokio::test]
+    async fn sanity_read_deck() {
+        let env = build_mocked_binahbot_env();
+        let deck = Deck {
+            description: Some("Burn it all".to_string()),
+            tags: vec!["burn".to_string()],
+            ..build_deck(
+                "1",
+                "Turbo Nikolai",
+                DeckData {
+                    keypage_id: Some("250023".to_string()),
+                    passive_ids: vec!["230018".to_string()],
+                    combat_page_ids: std::array::from_fn(|_| Some("608014".to_string())),
+                },
+            )
+        };
+        put_deck_with_history(&deck, None, &deck.author_id, &env)
+            .await
+            .unwrap();
+
+        let response = read_deck(&build_discord_interaction("1#Turbo Nikolai"), &env)
+            .await
+            .unwrap();
+
+        let data = response.data.unwrap();
+        let expected = transform_deck(&deck, &BinahBotLocale::EnglishUS, &env)
+            .await
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", Some(vec![expected])),
+            format!("{:?}", data.embeds)
+        );
+        assert!(data.components.is_some());
+        assert_eq!(None, data.flags);
+    }
+
+    fn build_discord_interaction(name: &str) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommand,
+            data: Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: "deck".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
+                    options: Some(vec![DiscordInteractionOptions {
+                        name: "name".to_string(),
+                        name_localizations: None,
+                        value: DiscordInteractionOptionValue::String(name.to_string()),
+                        focused: None,
+                    }]),
+                    target_id: None,
+                    resolved: None,
+                },
+            )),
+            channel_id: None,
+            guild_id: None,
+            token: "token".to_string(),
+            locale: Some("en-US".to_string()),
+            guild_locale: None,
+            user: None,
+            member: Some(DiscordGuildMember {
+                user: Some(DiscordUser {
+                    id: "snowflake".to_string(),
+                    username: "username".to_string(),
+                    avatar: None,
+                }),
+                permissions: None,
+            }),
+            message: None,
+        }
+    }
 }
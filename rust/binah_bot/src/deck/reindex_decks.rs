@@ -0,0 +1,67 @@
+use std::error::Error;
+
+use crate::repository::DeckRepository;
+
+use super::deck_utils::get_deck_index_keys;
+
+pub static REINDEX_DECKS_COMMAND: &str = "reindex-decks";
+
+// Index rows are only written when a deck is saved, so decks saved before an index key was
+// introduced have to be reindexed once to show up in searches that use it
+pub async fn reindex_decks(
+    deck_repository: &dyn DeckRepository,
+) -> Result<usize, Box<dyn Error + Send + Sync>> {
+    let decks = deck_repository.list_decks(None, None).await?;
+    for metadata in decks.iter() {
+        let deck = deck_repository
+            .get_deck(&metadata.name, &metadata.author_id)
+            .await?;
+        deck_repository
+            .put_deck_index(&deck, &get_deck_index_keys(&deck))
+            .await?;
+    }
+    Ok(decks.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::deck_utils::get_tag_index_key;
+    use crate::models::deck::Deck;
+    use crate::models::deck::DeckData;
+    use crate::test_utils::build_deck;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    #[tokio::test]
+    async fn sanity_reindex_decks() {
+        let env = build_mocked_binahbot_env();
+        let deck = Deck {
+            tags: vec!["bleed".to_string()],
+            ..build_deck(
+                "1",
+                "Turbo Nikolai",
+                DeckData {
+                    keypage_id: Some("250023".to_string()),
+                    passive_ids: Vec::new(),
+                    combat_page_ids: std::array::from_fn(|_| Some("608014".to_string())),
+                },
+            )
+        };
+        // saved without index rows, like decks from before the index existed
+        env.deck_repository.put_deck(&deck, None).await.unwrap();
+
+        assert_eq!(
+            1,
+            reindex_decks(env.deck_repository.as_ref()).await.unwrap()
+        );
+        let found = env
+            .deck_repository
+            .search_deck_index(&get_tag_index_key("bleed"))
+            .await
+            .unwrap();
+        assert_eq!(
+            vec!["Turbo Nikolai".to_string()],
+            found.into_iter().map(|x| x.name).collect::<Vec<_>>()
+        );
+    }
+}
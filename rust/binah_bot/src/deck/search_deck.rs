@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::Loader;
+use ruina::ruina_common::game_objects::common::PageType;
+use unic_langid::LanguageIdentifier;
+
+use crate::error::BinahBotError;
+use crate::macros::cast_enum_variant;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::deck::DeckMetadata;
+use crate::models::discord::ActionRowComponent;
+use crate::models::discord::AllowedMentions;
+use crate::models::discord::DiscordComponent;
+use crate::models::discord::DiscordComponentType;
+use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionOptionValue;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::MessageResponse;
+use crate::utils::build_delete_button_component;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
+use crate::utils::get_option_value;
+
+use super::deck_utils::format_to_list;
+use super::deck_utils::get_chapter_index_key;
+use super::deck_utils::get_page_index_key;
+use super::deck_utils::get_tag_index_key;
+use super::deck_utils::search_decks;
+
+// keeps the listing under the embed description limit
+const MAX_SEARCH_RESULTS: usize = 25;
+
+pub async fn search_deck(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
+
+    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+
+    let tag_option = get_option_value("tag", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+    let chapter_option = get_option_value("chapter", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+    let page_option = get_option_value("page", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+    let passive_option = get_option_value("passive", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
+    let is_private = get_option_value("private", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Bool))
+        .is_some_and(|x| *x);
+
+    let index_keys = tag_option
+        .map(|x| get_tag_index_key(x))
+        .into_iter()
+        .chain(chapter_option.map(|x| get_chapter_index_key(x)))
+        .chain(page_option.map(|x| get_page_index_key(PageType::CombatPage, x)))
+        .chain(passive_option.map(|x| get_page_index_key(PageType::Passive, x)))
+        .collect::<Vec<_>>();
+
+    if index_keys.is_empty() {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "deck_search_no_filters_error_message",
+            env,
+        ));
+    }
+
+    let decks = match search_decks(&index_keys, env).await {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "generic_error_message",
+                env,
+            ))
+        }
+    };
+
+    let components =
+        (!is_private).then_some(vec![DiscordComponent::ActionRow(ActionRowComponent {
+            r#type: DiscordComponentType::ActionRow,
+            components: vec![DiscordComponent::Button(build_delete_button_component(
+                &lang_id, env,
+            ))],
+        })]);
+
+    Ok(MessageResponse {
+        r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+            content: None,
+            embeds: Some(vec![build_search_results_embed(&decks, &lang_id, env)]),
+            flags: is_private.then_some(DiscordMessageFlag::EphemeralMessage as i32),
+            components,
+        }),
+    })
+}
+
+fn build_search_results_embed(
+    decks: &[DeckMetadata],
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> DiscordEmbed {
+    let entries = decks
+        .iter()
+        .take(MAX_SEARCH_RESULTS)
+        .map(|x| {
+            env.locales.lookup_with_args(
+                lang_id,
+                "list_deck_name_author",
+                &HashMap::from([
+                    ("deck_name", FluentValue::from(&x.name)),
+                    ("author", FluentValue::from(&x.author_name)),
+                ]),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    DiscordEmbed {
+        title: Some(env.locales.lookup_with_args(
+            lang_id,
+            "deck_search_title",
+            &HashMap::from([("count", FluentValue::from(decks.len()))]),
+        )),
+        description: Some(if entries.is_empty() {
+            env.locales.lookup(lang_id, "deck_search_no_results")
+        } else {
+            format_to_list(&entries)
+        }),
+        color: Some(DiscordEmbedColors::Default as i32),
+        image: None,
+        thumbnail: None,
+        footer: None,
+        author: None,
+        url: None,
+        fields: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::binahbot::BinahBotLocale;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    #[test]
+    fn sanity_build_search_results_embed() {
+        let env = build_mocked_binahbot_env();
+        let lang_id = LanguageIdentifier::from(&BinahBotLocale::EnglishUS);
+        let decks = vec![DeckMetadata {
+            name: "Turbo Nikolai".to_string(),
+            author_id: "1".to_string(),
+            author_name: "gh".to_string(),
+        }];
+
+        let embed = build_search_results_embed(&decks, &lang_id, &env);
+        assert_eq!(
+            Some("- \u{2068}Turbo Nikolai\u{2069} (\u{2068}gh\u{2069})".to_string()),
+            embed.description
+        );
+
+        let embed = build_search_results_embed(&[], &lang_id, &env);
+        assert_eq!(
+            Some("No decks matched your search".to_string()),
+            embed.description
+        );
+    }
+}
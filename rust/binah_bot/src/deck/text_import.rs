@@ -0,0 +1,392 @@
+use ruina::ruina_common::game_objects::common::PageType;
+use ruina::ruina_common::localizations::common::Locale;
+use ruina::ruina_index::models::ParsedTypedId;
+
+use crate::lor::lookup::lookup;
+use crate::models::deck::DeckData;
+use crate::utils::get_display_name_locale;
+
+const MAX_COMBAT_PAGES: usize = 9;
+
+#[derive(Debug, PartialEq)]
+pub struct ImportEntry {
+    pub page_type: PageType,
+    pub name: String,
+    pub count: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TextImportError {
+    UnparseableEntry(String),
+    PageNotFound(String),
+    TooManyCombatPages,
+    TooManyKeyPages,
+}
+
+impl TextImportError {
+    pub fn as_error_key(&self) -> &'static str {
+        match self {
+            TextImportError::UnparseableEntry(_) => "import_deck_unparseable_entry_error_message",
+            TextImportError::PageNotFound(_) => "import_deck_page_not_found_error_message",
+            TextImportError::TooManyCombatPages => {
+                "import_deck_too_many_combat_pages_error_message"
+            }
+            TextImportError::TooManyKeyPages => "import_deck_too_many_key_pages_error_message",
+        }
+    }
+
+    pub fn entry(&self) -> Option<&str> {
+        match self {
+            TextImportError::UnparseableEntry(x) | TextImportError::PageNotFound(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+// section labels are compared after lowercasing and stripping whitespace
+pub struct SectionLabels {
+    pub key_page: Vec<String>,
+    pub passive: Vec<String>,
+    pub combat_page: Vec<String>,
+}
+
+impl SectionLabels {
+    pub fn new(key_page: &str, passive: &str, combat_page: &str) -> Self {
+        let mut labels = SectionLabels::default();
+        labels.key_page.push(normalize_label(key_page));
+        labels.passive.push(normalize_label(passive));
+        labels.combat_page.push(normalize_label(combat_page));
+        labels
+    }
+}
+
+impl Default for SectionLabels {
+    fn default() -> Self {
+        SectionLabels {
+            key_page: vec!["keypage".to_string(), "key".to_string()],
+            passive: vec!["passive".to_string(), "passives".to_string()],
+            combat_page: vec![
+                "combatpage".to_string(),
+                "combatpages".to_string(),
+                "pages".to_string(),
+            ],
+        }
+    }
+}
+
+pub struct TextImport {
+    pub deck_data: DeckData,
+    // entries without an exact, unique match; (user input, best guess)
+    pub ambiguous: Vec<(String, ParsedTypedId)>,
+}
+
+enum Resolution {
+    Exact(ParsedTypedId),
+    Ambiguous(ParsedTypedId),
+}
+
+// tiph deck codes and urls never contain whitespace, while any usable text list does
+pub fn is_text_list(input: &str) -> bool {
+    input.trim().contains(char::is_whitespace)
+}
+
+pub fn parse_text_list(
+    input: &str,
+    labels: &SectionLabels,
+) -> Result<Vec<ImportEntry>, TextImportError> {
+    let mut entries = Vec::new();
+
+    for raw_entry in split_entries(input) {
+        let entry = raw_entry.trim().trim_start_matches(['-', '*', '•']).trim();
+        if entry.is_empty() {
+            continue;
+        }
+
+        let (page_type, value) = match entry.split_once(':') {
+            Some((label, value)) => match get_section(label, labels) {
+                Some(page_type) => (page_type, value.trim()),
+                None => (PageType::CombatPage, entry),
+            },
+            None => (PageType::CombatPage, entry),
+        };
+
+        // section header without any pages, e.g. "Combat pages:"
+        if value.is_empty() {
+            continue;
+        }
+
+        match page_type {
+            PageType::KeyPage => entries.push(ImportEntry {
+                page_type,
+                name: value.to_string(),
+                count: 1,
+            }),
+            PageType::Passive => value
+                .split(',')
+                .map(|x| x.trim())
+                .filter(|x| !x.is_empty())
+                .for_each(|x| {
+                    entries.push(ImportEntry {
+                        page_type: PageType::Passive,
+                        name: x.to_string(),
+                        count: 1,
+                    })
+                }),
+            _ => {
+                let (name, count) = parse_count(value);
+                if name.is_empty() || count == 0 {
+                    return Err(TextImportError::UnparseableEntry(entry.to_string()));
+                }
+                entries.push(ImportEntry {
+                    page_type,
+                    name: name.to_string(),
+                    count,
+                })
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+pub fn resolve_entries(
+    entries: &[ImportEntry],
+    locale: &Locale,
+) -> Result<TextImport, TextImportError> {
+    let mut keypage_id = None;
+    let mut passive_ids = Vec::new();
+    let mut combat_page_ids = Vec::new();
+    let mut ambiguous = Vec::new();
+
+    for entry in entries {
+        let id = match resolve_entry(entry, locale)? {
+            Resolution::Exact(id) => id,
+            Resolution::Ambiguous(id) => {
+                ambiguous.push((entry.name.clone(), id.clone()));
+                id
+            }
+        };
+
+        match entry.page_type {
+            PageType::KeyPage => {
+                if keypage_id.is_some() {
+                    return Err(TextImportError::TooManyKeyPages);
+                }
+                keypage_id = Some(id.1);
+            }
+            PageType::Passive => passive_ids.push(id.1),
+            _ => {
+                if combat_page_ids.len() + entry.count > MAX_COMBAT_PAGES {
+                    return Err(TextImportError::TooManyCombatPages);
+                }
+                combat_page_ids.extend(std::iter::repeat_n(id.1, entry.count));
+            }
+        }
+    }
+
+    Ok(TextImport {
+        deck_data: DeckData {
+            keypage_id,
+            passive_ids,
+            combat_page_ids: std::array::from_fn(|i| combat_page_ids.get(i).cloned()),
+        },
+        ambiguous,
+    })
+}
+
+fn resolve_entry(entry: &ImportEntry, locale: &Locale) -> Result<Resolution, TextImportError> {
+    let candidates = lookup(&entry.name, locale, false)
+        .filter(|x| x.0 == entry.page_type)
+        .collect::<Vec<_>>();
+
+    let exact_matches = candidates
+        .iter()
+        .filter(|x| {
+            get_display_name_locale(x, locale)
+                .is_some_and(|display| normalize_name(&display) == normalize_name(&entry.name))
+        })
+        .collect::<Vec<_>>();
+
+    match (exact_matches.as_slice(), candidates.first()) {
+        ([exact], _) => Ok(Resolution::Exact((*exact).clone())),
+        ([first, ..], _) => Ok(Resolution::Ambiguous((*first).clone())),
+        ([], Some(best_guess)) => Ok(Resolution::Ambiguous(best_guess.clone())),
+        ([], None) => Err(TextImportError::PageNotFound(entry.name.clone())),
+    }
+}
+
+// slashes are only treated as separators when surrounded by whitespace since some page names contain them
+fn split_entries(input: &str) -> Vec<&str> {
+    input
+        .split(['\n', ';'])
+        .flat_map(|x| x.split(" / "))
+        .collect()
+}
+
+fn get_section(label: &str, labels: &SectionLabels) -> Option<PageType> {
+    let label = normalize_label(label);
+    if labels.key_page.contains(&label) {
+        Some(PageType::KeyPage)
+    } else if labels.passive.contains(&label) {
+        Some(PageType::Passive)
+    } else if labels.combat_page.contains(&label) {
+        Some(PageType::CombatPage)
+    } else {
+        None
+    }
+}
+
+// page names use typographic apostrophes, which users rarely type
+fn normalize_name(name: &str) -> String {
+    name.trim().replace('’', "'").to_lowercase()
+}
+
+fn normalize_label(label: &str) -> String {
+    label
+        .chars()
+        .filter(|x| !x.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+// accepts "3x Name", "3 x Name", "Name x3" and "Name"
+fn parse_count(value: &str) -> (&str, usize) {
+    if let Some((head, tail)) = value.split_once(char::is_whitespace) {
+        let head_count = head
+            .strip_suffix(['x', 'X', '×'])
+            .unwrap_or(head)
+            .parse::<usize>();
+        if let Ok(count) = head_count {
+            let tail = tail.trim();
+            let tail = tail
+                .strip_prefix(['x', 'X', '×'])
+                .filter(|x| x.starts_with(char::is_whitespace))
+                .unwrap_or(tail);
+            return (tail.trim(), count);
+        }
+    }
+    if let Some((head, tail)) = value.rsplit_once(char::is_whitespace) {
+        let tail_count = tail
+            .strip_prefix(['x', 'X', '×'])
+            .and_then(|x| x.parse::<usize>().ok());
+        if let Some(count) = tail_count {
+            return (head.trim(), count);
+        }
+    }
+    (value, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanity_is_text_list() {
+        assert!(!is_text_list("CS-iRmsieV9ddwW4-BA1C~n"));
+        assert!(!is_text_list(
+            "https://tiphereth.zasz.su/u/decks/CS-iRmsieV9ddwW4-BA1C~n/"
+        ));
+        assert!(is_text_list("Key page: Xiao's Page / 9x Focus Spirit"));
+    }
+
+    #[test]
+    fn sanity_parse_count() {
+        assert_eq!(("Degraded Pillar", 3), parse_count("3x Degraded Pillar"));
+        assert_eq!(("Degraded Pillar", 3), parse_count("3 x Degraded Pillar"));
+        assert_eq!(("Degraded Pillar", 3), parse_count("Degraded Pillar x3"));
+        assert_eq!(("Degraded Pillar", 1), parse_count("Degraded Pillar"));
+        assert_eq!(("Xiao", 1), parse_count("Xiao"));
+    }
+
+    #[test]
+    fn sanity_parse_text_list() {
+        let entries = parse_text_list(
+            "Key page: Xiao's Page / Passives: Bladed Whirlwind, Tigermark / 3x Focus Spirit\n- Degraded Pillar x2\nCombat pages:",
+            &SectionLabels::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                ImportEntry {
+                    page_type: PageType::KeyPage,
+                    name: "Xiao's Page".to_string(),
+                    count: 1
+                },
+                ImportEntry {
+                    page_type: PageType::Passive,
+                    name: "Bladed Whirlwind".to_string(),
+                    count: 1
+                },
+                ImportEntry {
+                    page_type: PageType::Passive,
+                    name: "Tigermark".to_string(),
+                    count: 1
+                },
+                ImportEntry {
+                    page_type: PageType::CombatPage,
+                    name: "Focus Spirit".to_string(),
+                    count: 3
+                },
+                ImportEntry {
+                    page_type: PageType::CombatPage,
+                    name: "Degraded Pillar".to_string(),
+                    count: 2
+                },
+            ],
+            entries
+        );
+    }
+
+    #[test]
+    fn should_use_localized_labels() {
+        let labels = SectionLabels::new("핵심 책장", "지속능력", "전투 책장");
+        let entries = parse_text_list("핵심 책장: 샤오의 책장", &labels).unwrap();
+        assert_eq!(PageType::KeyPage, entries[0].page_type);
+        assert_eq!("샤오의 책장", entries[0].name);
+    }
+
+    #[test]
+    fn should_err_on_zero_count() {
+        let err = parse_text_list("0x Degraded Pillar", &SectionLabels::default()).unwrap_err();
+        assert_eq!(
+            TextImportError::UnparseableEntry("0x Degraded Pillar".to_string()),
+            err
+        );
+    }
+
+    #[test]
+    fn sanity_resolve_entries() {
+        let entries = parse_text_list("9x Degraded Pillar", &SectionLabels::default()).unwrap();
+        let import = resolve_entries(&entries, &Locale::English).unwrap();
+        assert!(import.ambiguous.is_empty());
+        assert!(import.deck_data.combat_page_ids.iter().all(|x| x.is_some()));
+        assert!(import.deck_data.keypage_id.is_none());
+    }
+
+    #[test]
+    fn should_match_names_with_straight_apostrophes() {
+        let entries = parse_text_list("Key page: xiao's page", &SectionLabels::default()).unwrap();
+        let import = resolve_entries(&entries, &Locale::English).unwrap();
+        assert!(import.ambiguous.is_empty());
+        assert!(import.deck_data.keypage_id.is_some());
+    }
+
+    #[test]
+    fn should_err_on_too_many_combat_pages() {
+        let entries = parse_text_list(
+            "5x Degraded Pillar / 5x Degraded Pillar",
+            &SectionLabels::default(),
+        )
+        .unwrap();
+        let err = resolve_entries(&entries, &Locale::English).err().unwrap();
+        assert_eq!(TextImportError::TooManyCombatPages, err);
+    }
+
+    #[test]
+    fn should_mark_inexact_matches_as_ambiguous() {
+        let entries = parse_text_list("9x degraded pilar", &SectionLabels::default()).unwrap();
+        let import = resolve_entries(&entries, &Locale::English).unwrap();
+        assert_eq!(1, import.ambiguous.len());
+        assert_eq!("degraded pilar", import.ambiguous[0].0);
+    }
+}
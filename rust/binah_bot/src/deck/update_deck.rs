@@ -2,18 +2,18 @@ use std::collections::HashMap;
 
 use fluent_templates::fluent_bundle::FluentValue;
 use fluent_templates::Loader;
+use ruina::ruina_common::localizations::common::Locale;
 use unic_langid::LanguageIdentifier;
 
-use crate::ddb::get_deck;
-use crate::ddb::put_deck;
+use crate::error::BinahBotError;
 use crate::macros::cast_enum_variant;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::deck::DeckVersion;
 use crate::models::deck::TiphDeck;
 use crate::models::discord::AllowedMentions;
 use crate::models::discord::DiscordEmbed;
 use crate::models::discord::DiscordInteraction;
-use crate::models::discord::DiscordInteractionData;
 use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionResponseMessage;
 use crate::models::discord::DiscordInteractionResponseType;
@@ -23,35 +23,39 @@ use crate::thumbnail::generate_thumbnail;
 use crate::tiph::decode;
 use crate::utils::build_error_message_response;
 use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
+use crate::utils::get_interaction_user;
 use crate::utils::get_option_value;
+use crate::utils::get_required_string_option;
+use crate::utils::get_reqwest_client;
 use crate::utils::parse_tiph_deck_id;
 
+use super::deck_utils::can_edit_deck;
+use super::deck_utils::parse_deck_name_option;
+use super::deck_utils::parse_tags;
+use super::deck_utils::put_deck_with_history;
 use super::deck_utils::validate_deck;
 
 static DEFAULT_TIPH_DECK_VERSION: i32 = 1;
-struct DeckKey((), String);
 
 pub async fn update_deck(
     interaction: &DiscordInteraction,
     env: &BinahBotEnvironment,
-) -> MessageResponse {
-    let command_args = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-        .and_then(|x| x.options.as_ref())
-        .unwrap();
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
 
     let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
 
-    let deck_name = get_option_value("name", command_args)
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
-        .unwrap();
+    let deck_name = get_required_string_option("name", command_args)?;
 
     let deck_key = match parse_deck_name_option(deck_name) {
         Ok(x) => x,
         Err(_) => {
-            return build_error_message_response(&lang_id, "deck_not_found_error_message", env)
+            return Ok(build_error_message_response(
+                &lang_id,
+                "deck_not_found_error_message",
+                env,
+            ))
         }
     };
 
@@ -62,68 +66,106 @@ pub async fn update_deck(
     let description_option = get_option_value("description", command_args)
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String));
 
-    let author_id = &interaction
-        .user
-        .as_ref()
-        .unwrap_or(interaction.member.as_ref().unwrap().user.as_ref().unwrap())
-        .id;
-
-    let get_deck_result = get_deck(
-        env.ddb_client.as_ref().unwrap(),
-        &env.ddb_table_name,
-        &deck_key.1,
-        author_id,
-    )
-    .await;
+    let tags_option = match get_option_value("tags", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
+        .map(|x| parse_tags(x))
+        .transpose()
+    {
+        Ok(x) => x,
+        Err(_) => {
+            return Ok(build_error_message_response(
+                &lang_id,
+                "invalid_tags_error_message",
+                env,
+            ))
+        }
+    };
+
+    let editor_id = &get_interaction_user(interaction)?.id;
+
+    let get_deck_result = env.deck_repository.get_deck(&deck_key.1, &deck_key.0).await;
 
     let mut deck = match get_deck_result {
         Ok(x) => x,
         Err(_) => {
-            return build_error_message_response(&lang_id, "cant_parse_deck_error_message", env)
+            return Ok(build_error_message_response(
+                &lang_id,
+                "cant_parse_deck_error_message",
+                env,
+            ))
         }
     };
 
+    if !can_edit_deck(&deck, editor_id) {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "deck_not_editor_error_message",
+            env,
+        ));
+    }
+
+    // decks created before versioning have no snapshot of their current contents yet
+    let _ = env
+        .deck_repository
+        .put_deck_version(
+            &DeckVersion {
+                deck: deck.clone(),
+                timestamp: None,
+                editor_id: None,
+            },
+            false,
+        )
+        .await;
+
     if description_option.is_some() {
         deck.description = description_option.cloned()
     }
 
+    if let Some(tags) = tags_option {
+        deck.tags = tags;
+    }
+
     if let Some(tiph_deck) = tiph_deck_option {
-        let deck_data_result = decode(
-            env.reqwest_client.as_ref().expect("no reqwest client"),
-            &tiph_deck,
-        )
-        .await;
+        let deck_data_result = decode(get_reqwest_client(env)?, &tiph_deck).await;
 
         let deck_data = match deck_data_result {
             Ok(x) => x,
             Err(_) => {
-                return build_error_message_response(&lang_id, "cant_parse_deck_error_message", env)
+                return Ok(build_error_message_response(
+                    &lang_id,
+                    "cant_parse_deck_error_message",
+                    env,
+                ))
             }
         };
 
         if let Err(e) = validate_deck(&deck_data) {
-            return build_error_message_response(&lang_id, e.as_error_key(), env);
+            return Ok(build_error_message_response(
+                &lang_id,
+                e.as_error_key(),
+                env,
+            ));
         }
 
-        let _ = generate_thumbnail(
-            env.lambda_client.as_ref().expect("no aws lambda client"),
-            &env.thumbnail_lambda_name,
-            &deck_data.combat_page_ids,
-        )
-        .await;
         deck.tiph_deck = Some(tiph_deck);
         deck.deck_data = deck_data;
     }
 
-    let put_deck_result = put_deck(
-        env.ddb_client.as_ref().expect("no ddb client"),
-        &env.ddb_table_name,
+    let previous_version = deck.version;
+    deck.version += 1;
+
+    let _ = generate_thumbnail(
+        env.lambda_client.as_ref(),
+        &env.thumbnail_lambda_name,
         &deck,
-        true,
+        &Locale::from(&get_binahbot_locale(interaction)),
     )
     .await;
 
-    match put_deck_result {
+    let put_deck_result =
+        put_deck_with_history(&deck, Some(previous_version), editor_id, env).await;
+
+    Ok(match put_deck_result {
         Ok(_) => MessageResponse {
             r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
             data: Some(DiscordInteractionResponseMessage {
@@ -152,16 +194,158 @@ pub async fn update_deck(
             // todo: check for error type
             build_error_message_response(&lang_id, "generic_error_message", env)
         }
-    }
+    })
 }
 
-// todo: move to utils
-fn parse_deck_name_option(name_option: &str) -> Result<DeckKey, ()> {
-    let mut split: Vec<_> = name_option.split('#').collect();
-    if split.len() >= 2 {
-        let split2 = split.split_off(1);
-        Ok(DeckKey((), split2.join("#")))
-    } else {
-        Err(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::binahbot::BinahBotLocale;
+    use crate::models::deck::DeckData;
+    use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordGuildMember;
+    use crate::models::discord::DiscordInteractionData;
+    use crate::models::discord::DiscordInteractionOptions;
+    use crate::models::discord::DiscordInteractionType;
+    use crate::models::discord::DiscordUser;
+    use crate::test_utils::build_deck;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    #[tokio::test]
+    async fn sanity_update_deck() {
+        let env = build_mocked_binahbot_env();
+        let deck = build_deck("snowflake", "Turbo Nikolai", build_deck_data());
+        put_deck_with_history(&deck, None, &deck.author_id, &env)
+            .await
+            .unwrap();
+
+        let response = update_deck(
+            &build_discord_interaction(&[
+                ("name", "snowflake#Turbo Nikolai"),
+                ("description", "Burn it all"),
+                ("tags", "burn"),
+            ]),
+            &env,
+        )
+        .await
+        .unwrap();
+
+        let embed = response
+            .data
+            .and_then(|x| x.embeds)
+            .and_then(|x| x.into_iter().next())
+            .expect("no embeds found");
+        assert_eq!(
+            Some(env.locales.lookup_with_args(
+                &LanguageIdentifier::from(&BinahBotLocale::EnglishUS),
+                "update_deck_success",
+                &HashMap::from([("deck_name", FluentValue::from("snowflake#Turbo Nikolai"))]),
+            )),
+            embed.description
+        );
+        let updated = env
+            .deck_repository
+            .get_deck("Turbo Nikolai", "snowflake")
+            .await
+            .unwrap();
+        assert_eq!(2, updated.version);
+        assert_eq!(Some("Burn it all".to_string()), updated.description);
+        assert_eq!(vec!["burn".to_string()], updated.tags);
+        assert_eq!(deck.deck_data.keypage_id, updated.deck_data.keypage_id);
+        assert_eq!(
+            2,
+            env.deck_repository
+                .list_deck_versions("Turbo Nikolai", "snowflake")
+                .await
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            1,
+            env.deck_repository
+                .search_deck_index("tag#burn")
+                .await
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_not_update_others_deck() {
+        let env = build_mocked_binahbot_env();
+        let deck = build_deck("1", "Turbo Nikolai", build_deck_data());
+        put_deck_with_history(&deck, None, &deck.author_id, &env)
+            .await
+            .unwrap();
+
+        update_deck(
+            &build_discord_interaction(&[
+                ("name", "1#Turbo Nikolai"),
+                ("description", "Burn it all"),
+            ]),
+            &env,
+        )
+        .await
+        .unwrap();
+
+        let stored = env
+            .deck_repository
+            .get_deck("Turbo Nikolai", "1")
+            .await
+            .unwrap();
+        assert_eq!(1, stored.version);
+        assert_eq!(None, stored.description);
+    }
+
+    fn build_deck_data() -> DeckData {
+        DeckData {
+            keypage_id: Some("250023".to_string()),
+            passive_ids: Vec::new(),
+            combat_page_ids: std::array::from_fn(|_| Some("608014".to_string())),
+        }
+    }
+
+    fn build_discord_interaction(options: &[(&str, &str)]) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommand,
+            data: Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: "updatedeck".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
+                    options: Some(
+                        options
+                            .iter()
+                            .map(|(name, value)| DiscordInteractionOptions {
+                                name: name.to_string(),
+                                name_localizations: None,
+                                value: DiscordInteractionOptionValue::String(value.to_string()),
+                                focused: None,
+                            })
+                            .collect(),
+                    ),
+                    target_id: None,
+                    resolved: None,
+                },
+            )),
+            channel_id: None,
+            guild_id: None,
+            token: "token".to_string(),
+            locale: Some("en-US".to_string()),
+            guild_locale: None,
+            user: None,
+            member: Some(DiscordGuildMember {
+                user: Some(DiscordUser {
+                    id: "snowflake".to_string(),
+                    username: "username".to_string(),
+                    avatar: None,
+                }),
+                permissions: None,
+            }),
+            message: None,
+        }
     }
 }
@@ -1,6 +1,9 @@
 use std::error::Error;
 
 use crate::models::binahbot::DiscordSecrets;
+use crate::models::discord::DiscordApplicationCommand;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::MessageResponse;
 
 pub async fn delete_interaction(
     client: &reqwest::Client,
@@ -18,3 +21,69 @@ pub async fn delete_interaction(
 
     Ok(())
 }
+
+// Acknowledges an interaction out of band, for when the answer will come later through
+// `edit_original_response`
+pub async fn create_interaction_response(
+    client: &reqwest::Client,
+    interaction_id: &str,
+    token: &str,
+    response: &MessageResponse,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let url = format!(
+        "https://discord.com/api/v10/interactions/{0}/{1}/callback",
+        interaction_id, token
+    );
+
+    client
+        .post(url)
+        .json(response)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+pub async fn edit_original_response(
+    client: &reqwest::Client,
+    secrets: &DiscordSecrets,
+    token: &str,
+    message: &DiscordInteractionResponseMessage,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let url = format!(
+        "https://discord.com/api/v10/webhooks/{0}/{1}/messages/@original",
+        secrets.application_id, token
+    );
+
+    client
+        .patch(url)
+        .json(message)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+// Replaces every global command with `commands`; commands missing from the list are deleted
+pub async fn overwrite_global_commands(
+    client: &reqwest::Client,
+    secrets: &DiscordSecrets,
+    commands: &[DiscordApplicationCommand],
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let url = format!(
+        "https://discord.com/api/v10/applications/{0}/commands",
+        secrets.application_id
+    );
+
+    client
+        .put(url)
+        .header("Authorization", format!("Bot {}", secrets.bot_token))
+        .json(commands)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::error::Error;
+use std::fmt;
+
+use unic_langid::LanguageIdentifier;
+
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::MessageResponse;
+use crate::utils::build_error_message_response;
+
+/**
+ * Failures caused by the interaction itself rather than by the bot, e.g. stale buttons from an
+ * older deployment or options missing from an outdated command registration. These are shown
+ * to the user as an error embed instead of crashing the lambda.
+ */
+#[derive(Debug, PartialEq, strum_macros::IntoStaticStr)]
+pub enum BinahBotError {
+    MissingInteractionData,
+    MissingOption(&'static str),
+    MissingUser,
+    MissingMessage,
+    MissingHttpClient,
+    UnknownCommand(String),
+    CommandDisabled(String),
+    UnknownComponent(String),
+    UnsupportedInteractionType(String),
+    NotInteractionAuthor,
+    InteractionExpired,
+}
+
+impl BinahBotError {
+    pub fn as_error_key(&self) -> &'static str {
+        match self {
+            BinahBotError::MissingInteractionData
+            | BinahBotError::MissingOption(_)
+            | BinahBotError::MissingUser
+            | BinahBotError::MissingMessage => "malformed_interaction_error_message",
+            BinahBotError::UnknownCommand(_)
+            | BinahBotError::UnknownComponent(_)
+            | BinahBotError::UnsupportedInteractionType(_) => "stale_interaction_error_message",
+            BinahBotError::CommandDisabled(_) => "command_disabled_error_message",
+            BinahBotError::NotInteractionAuthor => "not_interaction_author_error_message",
+            BinahBotError::InteractionExpired => "interaction_expired_error_message",
+            BinahBotError::MissingHttpClient => "generic_error_message",
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        self.into()
+    }
+
+    pub fn log(&self, interaction: &DiscordInteraction) {
+        tracing::warn!(
+            error.kind = self.kind(),
+            error.detail = %self,
+            interaction.id = %interaction.id,
+            interaction.kind = ?interaction.r#type,
+            "Failed to handle interaction"
+        );
+    }
+
+    pub fn to_message_response(
+        &self,
+        lang_id: &LanguageIdentifier,
+        env: &BinahBotEnvironment,
+    ) -> MessageResponse {
+        build_error_message_response(lang_id, self.as_error_key(), env)
+    }
+}
+
+impl fmt::Display for BinahBotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinahBotError::MissingInteractionData => write!(f, "interaction has no usable data"),
+            BinahBotError::MissingOption(x) => write!(f, "missing required option {}", x),
+            BinahBotError::MissingUser => write!(f, "interaction has no user"),
+            BinahBotError::MissingMessage => write!(f, "interaction has no message metadata"),
+            BinahBotError::MissingHttpClient => write!(f, "no http client provided"),
+            BinahBotError::UnknownCommand(x) => write!(f, "unknown command with name={}", x),
+            BinahBotError::CommandDisabled(x) => write!(f, "command with name={} is disabled", x),
+            BinahBotError::UnknownComponent(x) => {
+                write!(f, "unknown component with custom_id={}", x)
+            }
+            BinahBotError::UnsupportedInteractionType(x) => {
+                write!(f, "unsupported interaction type={}", x)
+            }
+            BinahBotError::NotInteractionAuthor => {
+                write!(
+                    f,
+                    "component used by someone other than the original author"
+                )
+            }
+            BinahBotError::InteractionExpired => write!(f, "interaction state has expired"),
+        }
+    }
+}
+
+impl Error for BinahBotError {}
@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use ruina::ruina_common::game_objects::common::Chapter;
+
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::config::ConfigScope;
+use crate::models::config::GuildConfig;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionData;
+use crate::models::discord::DiscordInteractionOptionValue;
+use crate::models::discord::DiscordInteractionOptions;
+use crate::models::discord::DiscordInteractionType;
+use crate::utils::get_interaction_user;
+use crate::utils::get_option_value;
+
+// Channel settings take precedence over the server's
+pub async fn get_guild_config(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> GuildConfig {
+    let (channel_config, guild_config) = get_channel_and_guild_configs(interaction, env).await;
+    channel_config.or(guild_config)
+}
+
+// Configs are only read inside servers, and a config that can't be read is treated as empty
+// rather than failing the interaction
+async fn get_channel_and_guild_configs(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> (GuildConfig, GuildConfig) {
+    let Some(guild_id) = interaction.guild_id.as_ref() else {
+        return (GuildConfig::default(), GuildConfig::default());
+    };
+
+    let get_config = |scope: Option<ConfigScope>| async move {
+        let Some(scope) = scope else {
+            return GuildConfig::default();
+        };
+        env.config_repository
+            .get_config(&scope)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to get config with scope={:?}: {}", scope, e);
+                GuildConfig::default()
+            })
+    };
+
+    tokio::join!(
+        get_config(interaction.channel_id.clone().map(ConfigScope::Channel)),
+        get_config(Some(ConfigScope::Guild(guild_id.clone())))
+    )
+}
+
+// Defaults are applied to the interaction itself so commands pick them up like any value
+// Discord sent. The default locale replaces the user's own, and the default privacy only
+// applies when the user didn't choose
+pub fn apply_guild_config(interaction: &mut DiscordInteraction, config: &GuildConfig) {
+    if let Some(default_locale) = config.default_locale.as_ref() {
+        interaction.locale = Some(default_locale.to_string());
+    }
+
+    if interaction.r#type != DiscordInteractionType::ApplicationCommand {
+        return;
+    }
+    let Some(default_private) = config.default_private else {
+        return;
+    };
+    if let Some(DiscordInteractionData::ApplicationCommand(data)) = interaction.data.as_mut() {
+        let options = data.options.get_or_insert_with(Vec::new);
+        if get_option_value("private", options).is_none() {
+            options.push(DiscordInteractionOptions {
+                name: "private".to_string(),
+                name_localizations: None,
+                value: DiscordInteractionOptionValue::Bool(default_private),
+                focused: None,
+            });
+        }
+    }
+}
+
+// A channel configured at runtime takes precedence, then channel_config.toml, and only then the
+// server-wide setting, so a server default can't loosen a channel's own limit
+pub async fn get_max_spoiler_chapter(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Option<Chapter> {
+    let (channel_config, guild_config) = get_channel_and_guild_configs(interaction, env).await;
+    channel_config
+        .max_spoiler_chapter
+        .or_else(|| {
+            interaction
+                .channel_id
+                .as_ref()
+                .and_then(|x| env.spoiler_config.get(x))
+                .cloned()
+        })
+        .or(guild_config.max_spoiler_chapter)
+}
+
+// The user's progress through the game, set with /progress
+pub async fn get_user_progress(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Option<Chapter> {
+    let user_id = &get_interaction_user(interaction).ok()?.id;
+    let scope = ConfigScope::User(user_id.clone());
+    env.config_repository
+        .get_config(&scope)
+        .await
+        .map_err(|e| tracing::error!("Failed to get config with scope={:?}: {}", scope, e))
+        .ok()?
+        .max_spoiler_chapter
+}
+
+// Autocomplete runs on every keystroke, so it reuses a user's progress for a while instead of
+// reading it each time. Only this instance forgets an entry when /progress changes it, so other
+// instances may suggest pages by the old progress until the entry expires; the command itself
+// always reads the progress again
+const PROGRESS_CACHE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+pub struct ProgressCache(Mutex<HashMap<String, (Instant, Option<Chapter>)>>);
+
+impl ProgressCache {
+    fn get(&self, user_id: &str) -> Option<Option<Chapter>> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(user_id)
+            .filter(|(cached_at, _)| cached_at.elapsed() < PROGRESS_CACHE_TTL)
+            .map(|(_, progress)| progress.clone())
+    }
+
+    fn put(&self, user_id: &str, progress: Option<Chapter>) {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(user_id.to_string(), (Instant::now(), progress));
+    }
+
+    pub fn invalidate(&self, user_id: &str) {
+        self.0.lock().unwrap().remove(user_id);
+    }
+}
+
+pub async fn get_cached_user_progress(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Option<Chapter> {
+    let user_id = &get_interaction_user(interaction).ok()?.id;
+    if let Some(progress) = env.progress_cache.get(user_id) {
+        return progress;
+    }
+
+    let progress = get_user_progress(interaction, env).await;
+    env.progress_cache.put(user_id, progress.clone());
+    progress
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpoilerLimit {
+    Channel(Chapter),
+    Progress(Chapter),
+}
+
+impl SpoilerLimit {
+    pub fn chapter(&self) -> &Chapter {
+        match self {
+            SpoilerLimit::Channel(x) => x,
+            SpoilerLimit::Progress(x) => x,
+        }
+    }
+}
+
+// The user's progress applies to every response, while the channel's limit only applies to
+// responses everyone in the channel can see
+pub async fn get_spoiler_limits(
+    interaction: &DiscordInteraction,
+    is_private: bool,
+    env: &BinahBotEnvironment,
+) -> Vec<SpoilerLimit> {
+    let (max_spoiler_chapter, progress) = tokio::join!(
+        async {
+            match is_private {
+                true => None,
+                false => get_max_spoiler_chapter(interaction, env).await,
+            }
+        },
+        get_user_progress(interaction, env)
+    );
+
+    max_spoiler_chapter
+        .map(SpoilerLimit::Channel)
+        .into_iter()
+        .chain(progress.map(SpoilerLimit::Progress))
+        .collect()
+}
+
+pub fn find_exceeded_spoiler_limit<'a>(
+    chapter: &Chapter,
+    limits: &'a [SpoilerLimit],
+) -> Option<&'a SpoilerLimit> {
+    limits.iter().find(|x| chapter > x.chapter())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::binahbot::BinahBotLocale;
+    use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordUser;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn sanity_channel_config_overrides_guild_config() {
+        let env = build_mocked_binahbot_env();
+        // configured in channel_config.toml as StarOfTheCity
+        let interaction = build_interaction(Some("1234567890123456789"));
+
+        assert_eq!(
+            Some(Chapter::StarOfTheCity),
+            get_max_spoiler_chapter(&interaction, &env).await
+        );
+
+        env.config_repository
+            .put_config(
+                &ConfigScope::Guild("guild".to_string()),
+                &GuildConfig {
+                    max_spoiler_chapter: Some(Chapter::UrbanMyth),
+                    default_locale: Some(BinahBotLocale::Japanese),
+                    ..GuildConfig::default()
+                },
+            )
+            .await
+            .unwrap();
+        env.config_repository
+            .put_config(
+                &ConfigScope::Channel("1234567890123456789".to_string()),
+                &GuildConfig {
+                    max_spoiler_chapter: Some(Chapter::UrbanPlague),
+                    ..GuildConfig::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let config = get_guild_config(&interaction, &env).await;
+        assert_eq!(Some(Chapter::UrbanPlague), config.max_spoiler_chapter);
+        assert_eq!(Some(BinahBotLocale::Japanese), config.default_locale);
+        assert_eq!(
+            Some(Chapter::UrbanMyth),
+            get_max_spoiler_chapter(&build_interaction(Some("other")), &env).await
+        );
+    }
+
+    #[tokio::test]
+    async fn should_prefer_channel_config_toml_over_guild_config() {
+        let env = build_mocked_binahbot_env();
+        // configured in channel_config.toml as StarOfTheCity
+        let interaction = build_interaction(Some("1234567890123456789"));
+
+        env.config_repository
+            .put_config(
+                &ConfigScope::Guild("guild".to_string()),
+                &GuildConfig {
+                    max_spoiler_chapter: Some(Chapter::ImpuritasCivitatis),
+                    ..GuildConfig::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some(Chapter::StarOfTheCity),
+            get_max_spoiler_chapter(&interaction, &env).await
+        );
+        assert_eq!(
+            Some(Chapter::ImpuritasCivitatis),
+            get_max_spoiler_chapter(&build_interaction(Some("other")), &env).await
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_get_spoiler_limits() {
+        let env = build_mocked_binahbot_env();
+        let mut interaction = build_interaction(Some("1234567890123456789"));
+        interaction.user = Some(DiscordUser {
+            id: "user".to_string(),
+            username: "user".to_string(),
+            avatar: None,
+        });
+
+        env.config_repository
+            .put_config(
+                &ConfigScope::User("user".to_string()),
+                &GuildConfig {
+                    max_spoiler_chapter: Some(Chapter::UrbanLegend),
+                    ..GuildConfig::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let limits = get_spoiler_limits(&interaction, false, &env).await;
+        assert_eq!(
+            vec![
+                SpoilerLimit::Channel(Chapter::StarOfTheCity),
+                SpoilerLimit::Progress(Chapter::UrbanLegend)
+            ],
+            limits
+        );
+        assert_eq!(
+            Some(&SpoilerLimit::Progress(Chapter::UrbanLegend)),
+            find_exceeded_spoiler_limit(&Chapter::UrbanPlague, &limits)
+        );
+        assert_eq!(
+            None,
+            find_exceeded_spoiler_limit(&Chapter::UrbanLegend, &limits)
+        );
+
+        assert_eq!(
+            vec![SpoilerLimit::Progress(Chapter::UrbanLegend)],
+            get_spoiler_limits(&interaction, true, &env).await
+        );
+    }
+
+    #[tokio::test]
+    async fn should_cache_user_progress_until_invalidated() {
+        let env = build_mocked_binahbot_env();
+        let mut interaction = build_interaction(None);
+        interaction.user = Some(DiscordUser {
+            id: "user".to_string(),
+            username: "user".to_string(),
+            avatar: None,
+        });
+        put_progress(&env, Chapter::UrbanLegend).await;
+        assert_eq!(
+            Some(Chapter::UrbanLegend),
+            get_cached_user_progress(&interaction, &env).await
+        );
+
+        put_progress(&env, Chapter::UrbanPlague).await;
+        assert_eq!(
+            Some(Chapter::UrbanLegend),
+            get_cached_user_progress(&interaction, &env).await
+        );
+
+        env.progress_cache.invalidate("user");
+        assert_eq!(
+            Some(Chapter::UrbanPlague),
+            get_cached_user_progress(&interaction, &env).await
+        );
+    }
+
+    #[test]
+    fn sanity_apply_guild_config() {
+        let mut interaction = build_interaction(None);
+        apply_guild_config(
+            &mut interaction,
+            &GuildConfig {
+                default_locale: Some(BinahBotLocale::Korean),
+                default_private: Some(true),
+                ..GuildConfig::default()
+            },
+        );
+
+        assert_eq!(Some("ko".to_string()), interaction.locale);
+        let options = interaction
+            .data
+            .and_then(|x| match x {
+                DiscordInteractionData::ApplicationCommand(y) => y.options,
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(
+            Some(&DiscordInteractionOptionValue::Bool(true)),
+            get_option_value("private", &options)
+        );
+    }
+
+    async fn put_progress(env: &BinahBotEnvironment, chapter: Chapter) {
+        env.config_repository
+            .put_config(
+                &ConfigScope::User("user".to_string()),
+                &GuildConfig {
+                    max_spoiler_chapter: Some(chapter),
+                    ..GuildConfig::default()
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    fn build_interaction(channel_id: Option<&str>) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommand,
+            data: Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: "lor".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
+                    options: None,
+                    target_id: None,
+                    resolved: None,
+                },
+            )),
+            channel_id: channel_id.map(|x| x.to_string()),
+            guild_id: Some("guild".to_string()),
+            token: "token".to_string(),
+            locale: Some("en-US".to_string()),
+            guild_locale: None,
+            user: None,
+            member: None,
+            message: None,
+        }
+    }
+}
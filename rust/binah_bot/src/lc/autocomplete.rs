@@ -7,17 +7,18 @@ use lobocorp::lobocorp_common::localizations::common::Locale;
 use lobocorp::lobocorp_reparser::get_abno_localization;
 use unic_langid::LanguageIdentifier;
 
+use crate::error::BinahBotError;
 use crate::macros::cast_enum_variant;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::BinahBotLocale;
 use crate::models::discord::AutocompleteResponse;
 use crate::models::discord::DiscordInteraction;
-use crate::models::discord::DiscordInteractionData;
 use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionOptions;
 use crate::models::discord::DiscordInteractionResponseAutocomplete;
 use crate::models::discord::DiscordInteractionResponseType;
 use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
 use crate::utils::get_option_value;
 
 static MAX_AUTOCOMPLETE_OPTIONS: usize = 10;
@@ -25,13 +26,8 @@ static MAX_AUTOCOMPLETE_OPTIONS: usize = 10;
 pub fn lc_autocomplete(
     interaction: &DiscordInteraction,
     env: &BinahBotEnvironment,
-) -> AutocompleteResponse {
-    let command_args = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-        .and_then(|x| x.options.as_ref())
-        .unwrap();
+) -> Result<AutocompleteResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
 
     tracing::info!("Lc autocomplete: command args: {:#?}", command_args);
 
@@ -73,10 +69,10 @@ pub fn lc_autocomplete(
         })
         .collect();
 
-    AutocompleteResponse {
+    Ok(AutocompleteResponse {
         r#type: DiscordInteractionResponseType::ApplicationCommandAutocompleteResult,
         data: Some(DiscordInteractionResponseAutocomplete {
             choices: Some(options),
         }),
-    }
+    })
 }
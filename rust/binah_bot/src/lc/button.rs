@@ -12,7 +12,7 @@ use lobocorp::lobocorp_reparser::get_encyclopedia_info;
 use lobocorp::lobocorp_reparser::get_localization;
 use unic_langid::LanguageIdentifier;
 
-use crate::macros::cast_enum_variant;
+use crate::error::BinahBotError;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::BinahBotLocale;
 use crate::models::discord::ActionRowComponent;
@@ -22,12 +22,13 @@ use crate::models::discord::ButtonStyle;
 use crate::models::discord::DiscordComponent;
 use crate::models::discord::DiscordComponentType;
 use crate::models::discord::DiscordInteraction;
-use crate::models::discord::DiscordInteractionData;
 use crate::models::discord::DiscordInteractionResponseMessage;
 use crate::models::discord::DiscordInteractionResponseType;
 use crate::models::discord::MessageResponse;
 use crate::utils::build_delete_button_component;
 use crate::utils::get_binahbot_locale;
+use crate::utils::get_component_data;
+use crate::utils::get_interaction_user;
 
 use super::transformers::transform_breaching_entity;
 use super::transformers::transform_donttouchme;
@@ -63,44 +64,28 @@ const SEPERATOR: &str = "#";
 pub fn lc_button(
     interaction: &DiscordInteraction,
     env: &BinahBotEnvironment,
-) -> Result<MessageResponse, ()> {
+) -> Result<MessageResponse, BinahBotError> {
     tracing::info!("Lc button: interaction={:#?}", interaction);
 
-    let data = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::MessageComponent))
-        .unwrap();
+    let data = get_component_data(interaction)?;
     let custom_id = &data.custom_id;
+    let unknown_component = || BinahBotError::UnknownComponent(custom_id.to_string());
     if !custom_id.starts_with(LC_BUTTON_PREFIX) {
-        tracing::error!("custom id did not start with {}", LC_BUTTON_PREFIX);
-        return Err(());
+        return Err(unknown_component());
     };
     let original_user_id = interaction
         .message
         .as_ref()
         .and_then(|x| x.interaction_metadata.as_ref())
         .map(|x| &x.user.id);
-    let user_id = interaction.user.as_ref().map(|x| &x.id).unwrap_or_else(|| {
-        &interaction
-            .member
-            .as_ref()
-            .and_then(|x| x.user.as_ref())
-            .map(|x| &x.id)
-            .unwrap()
-    });
+    let user_id = &get_interaction_user(interaction)?.id;
     if original_user_id.is_some_and(|x| x != user_id) {
-        tracing::error!(
-            "interaction author differs: user_id={}; original_user_id={:?}",
-            user_id,
-            original_user_id
-        );
-        return Err(());
+        return Err(BinahBotError::NotInteractionAuthor);
     };
 
     let binahbot_locale = get_binahbot_locale(&interaction);
-    let (code, id, locale, index) = parse_custom_id(&custom_id);
-    let entry = get_encyclopedia_info(&id).expect("couldn't find entry");
+    let (code, id, locale, index) = parse_custom_id(custom_id).ok_or_else(unknown_component)?;
+    let entry = get_encyclopedia_info(&id).ok_or_else(unknown_component)?;
 
     let embed = match (&code, &entry, &index) {
         (Code::Encyclopedia, EncyclopediaInfo::Normal(x), &ENCYCLOPEDIA_MAIN_PAGE_INDEX) => {
@@ -118,53 +103,53 @@ pub fn lc_button(
             transform_donttouchme(&x, &locale, &binahbot_locale, env)
         }
         (Code::Weapon, EncyclopediaInfo::Normal(x), _) => transform_weapon(
-            &x.weapon.as_ref().expect("no weapon"),
+            x.weapon.as_ref().ok_or_else(unknown_component)?,
             &locale,
             &binahbot_locale,
             env,
         ),
         (Code::Suit, EncyclopediaInfo::Normal(x), _) => transform_suit(
-            &x.suit.as_ref().expect("no suit"),
+            x.suit.as_ref().ok_or_else(unknown_component)?,
             &locale,
             &binahbot_locale,
             env,
         ),
         (Code::Gift, EncyclopediaInfo::Normal(x), _) => transform_gift(
-            &x.gifts.get(index).expect("bad gift index"),
+            x.gifts.get(index).ok_or_else(unknown_component)?,
             &locale,
             &binahbot_locale,
             env,
         ),
         (Code::BreachingEntity, EncyclopediaInfo::Normal(x), _) => {
-            let localization = get_abno_localization(&id, &locale).expect("no localization found");
+            let localization = get_abno_localization(&id, &locale).ok_or_else(unknown_component)?;
             transform_breaching_entity(
-                &x.breaching_entities
+                x.breaching_entities
                     .get(index)
-                    .expect("bad breaching entity index"),
-                &localization
+                    .ok_or_else(unknown_component)?,
+                localization
                     .breaching_entity_localizations
                     .get(index)
-                    .expect("bad breaching entity index"),
+                    .ok_or_else(unknown_component)?,
                 &binahbot_locale,
                 env,
             )
         }
         (Code::BreachingEntity, EncyclopediaInfo::Tool(x), _) => {
             // yang
-            let localization = get_abno_localization(&id, &locale).expect("no localization found");
+            let localization = get_abno_localization(&id, &locale).ok_or_else(unknown_component)?;
             transform_breaching_entity(
-                &x.breaching_entities
+                x.breaching_entities
                     .get(index)
-                    .expect("bad breaching entity index"),
-                &localization
+                    .ok_or_else(unknown_component)?,
+                localization
                     .breaching_entity_localizations
                     .get(index)
-                    .expect("bad breaching entity index"),
+                    .ok_or_else(unknown_component)?,
                 &binahbot_locale,
                 env,
             )
         }
-        _ => panic!("encountered unexpected custom_id format"),
+        _ => return Err(unknown_component()),
     };
 
     let components = build_buttons(id, &locale, &binahbot_locale, &(code, index), env);
@@ -356,23 +341,15 @@ pub fn build_buttons(
         .collect::<Vec<_>>()
 }
 
-fn parse_custom_id(custom_id: &str) -> (Code, u32, Locale, usize) {
+fn parse_custom_id(custom_id: &str) -> Option<(Code, u32, Locale, usize)> {
     let vec = custom_id.split(SEPERATOR).collect::<Vec<&str>>();
     // discard lc# prefix
-    (
-        vec.get(1)
-            .and_then(|x| Code::from_str(x).ok())
-            .expect("couldn't get code"),
-        vec.get(2)
-            .and_then(|x| x.parse::<u32>().ok())
-            .expect("couldn't get id"),
-        vec.get(3)
-            .and_then(|x| Locale::from_str(x).ok())
-            .expect("couldn't get locale"),
-        vec.get(4)
-            .and_then(|x| x.parse::<usize>().ok())
-            .expect("couldn't get index"),
-    )
+    Some((
+        vec.get(1).and_then(|x| Code::from_str(x).ok())?,
+        vec.get(2).and_then(|x| x.parse::<u32>().ok())?,
+        vec.get(3).and_then(|x| Locale::from_str(x).ok())?,
+        vec.get(4).and_then(|x| x.parse::<usize>().ok())?,
+    ))
 }
 
 fn build_custom_id(code: &Code, id: &u32, locale: &Locale, index: &usize) -> String {
@@ -435,8 +412,9 @@ mod tests {
     fn sanity_parse_custom_id() {
         let str = "lc#e#100038#en#0";
         assert_eq!(
-            (Code::Encyclopedia, 100038, Locale::English, 0),
+            Some((Code::Encyclopedia, 100038, Locale::English, 0)),
             parse_custom_id(str)
         );
+        assert_eq!(None, parse_custom_id("lc#e#100038"));
     }
 }
@@ -4,6 +4,7 @@ use lobocorp::lobocorp_common::game_objects::abnormality::EncyclopediaInfo;
 use lobocorp::lobocorp_common::localizations::common::Locale;
 use lobocorp::lobocorp_reparser::get_encyclopedia_info;
 
+use crate::error::BinahBotError;
 use crate::lc::button::build_buttons;
 use crate::lc::button::Code;
 use crate::lc::transformers::transform_donttouchme;
@@ -19,22 +20,20 @@ use crate::models::binahbot::DiscordEmbedColors;
 use crate::models::discord::AllowedMentions;
 use crate::models::discord::DiscordEmbed;
 use crate::models::discord::DiscordInteraction;
-use crate::models::discord::DiscordInteractionData;
 use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionResponseMessage;
 use crate::models::discord::DiscordInteractionResponseType;
 use crate::models::discord::DiscordMessageFlag;
 use crate::models::discord::MessageResponse;
 use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
 use crate::utils::get_option_value;
 
-pub fn lc_command(interaction: &DiscordInteraction, env: &BinahBotEnvironment) -> MessageResponse {
-    let command_args = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-        .and_then(|x| x.options.as_ref())
-        .unwrap();
+pub fn lc_command(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
 
     tracing::info!("Lc command: command args: {:#?}", command_args);
 
@@ -56,11 +55,17 @@ pub fn lc_command(interaction: &DiscordInteraction, env: &BinahBotEnvironment) -
     let query = match query {
         Some(x) => x,
         None => {
-            return no_match_found(&lang_id, env);
+            return Ok(no_match_found(&lang_id, env));
         }
     };
 
-    let entry = get_encyclopedia_info(&query).expect("couldn't find entry");
+    // numeric queries skip the index, so they may not exist
+    let entry = match get_encyclopedia_info(&query) {
+        Some(x) => x,
+        None => {
+            return Ok(no_match_found(&lang_id, env));
+        }
+    };
 
     let embed: DiscordEmbed = match &entry {
         EncyclopediaInfo::Normal(x) => transform_normal_info(&x, &locale, &binah_locale, env),
@@ -76,7 +81,7 @@ pub fn lc_command(interaction: &DiscordInteraction, env: &BinahBotEnvironment) -
 
     let components = build_buttons(query, &locale, &binah_locale, &(Code::Encyclopedia, 0), env);
 
-    MessageResponse {
+    Ok(MessageResponse {
         r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
         data: Some(DiscordInteractionResponseMessage {
             allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
@@ -85,7 +90,7 @@ pub fn lc_command(interaction: &DiscordInteraction, env: &BinahBotEnvironment) -
             flags: flags,
             components: Some(components),
         }),
-    }
+    })
 }
 
 fn no_match_found(lang_id: &LanguageIdentifier, env: &BinahBotEnvironment) -> MessageResponse {
@@ -118,6 +123,8 @@ mod tests {
     use strum::IntoEnumIterator;
 
     use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordInteractionData;
     use crate::models::discord::DiscordInteractionOptions;
     use crate::models::discord::DiscordInteractionType;
     use crate::models::discord::DiscordUser;
@@ -144,7 +151,7 @@ mod tests {
                 })
                 .map(|name| build_discord_interaction(name.to_string(), locale.clone()))
                 .for_each(|interaction| {
-                    let _does_not_crash = lc_command(&interaction, env);
+                    assert!(lc_command(&interaction, env).is_ok());
                 })
         });
     }
@@ -158,6 +165,7 @@ mod tests {
                 DiscordApplicationCommandInteractionData {
                     id: "id".to_string(),
                     name: "lc".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
                     options: Some(vec![
                         DiscordInteractionOptions {
                             name: "query".to_string(),
@@ -172,9 +180,12 @@ mod tests {
                             focused: None,
                         },
                     ]),
+                    target_id: None,
+                    resolved: None,
                 },
             )),
             channel_id: None,
+            guild_id: None,
             token: "token".to_string(),
             locale: None,
             guild_locale: None,
@@ -1,4 +1,4 @@
 pub mod autocomplete;
 pub mod command;
-mod lookup;
+pub mod lookup;
 mod transformers;
@@ -4,33 +4,33 @@ use lambda_http::tracing;
 use ruina::ruina_common::localizations::common::Locale;
 use unic_langid::LanguageIdentifier;
 
+use crate::error::BinahBotError;
+use crate::guild_config::find_exceeded_spoiler_limit;
+use crate::guild_config::get_cached_user_progress;
+use crate::guild_config::SpoilerLimit;
+use crate::lor::lookup::get_page_chapter;
 use crate::lor::lookup::lookup;
 use crate::macros::cast_enum_variant;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::BinahBotLocale;
 use crate::models::discord::AutocompleteResponse;
-use crate::models::discord::DiscordInteractionData;
 use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionOptions;
 use crate::models::discord::DiscordInteractionResponseAutocomplete;
 use crate::models::discord::DiscordInteractionResponseType;
 use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_options;
 use crate::utils::get_disambiguation_format;
 use crate::utils::get_option_value;
 use crate::DiscordInteraction;
 
 static MAX_AUTOCOMPLETE_OPTIONS: usize = 10;
 
-pub fn lor_autocomplete(
+pub async fn lor_autocomplete(
     interaction: &DiscordInteraction,
     env: &BinahBotEnvironment,
-) -> AutocompleteResponse {
-    let command_args = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-        .and_then(|x| x.options.as_ref())
-        .unwrap();
+) -> Result<AutocompleteResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
 
     tracing::info!("Lor autocomplete: command args: {:#?}", command_args);
 
@@ -53,7 +53,15 @@ pub fn lor_autocomplete(
 
     let lang_id = LanguageIdentifier::from(&locale);
 
+    // whether the response will be private isn't known yet, so only the user's progress applies
+    let spoiler_limits: Vec<_> = get_cached_user_progress(interaction, env)
+        .await
+        .map(SpoilerLimit::Progress)
+        .into_iter()
+        .collect();
+
     let options: Vec<_> = lookup(query, &locale, all)
+        .filter(|x| find_exceeded_spoiler_limit(&get_page_chapter(x), &spoiler_limits).is_none())
         .take(MAX_AUTOCOMPLETE_OPTIONS)
         .map(|x| {
             let display_name = get_disambiguation_format(&x, &locale, &lang_id, env);
@@ -67,32 +75,38 @@ pub fn lor_autocomplete(
         })
         .collect();
 
-    AutocompleteResponse {
+    Ok(AutocompleteResponse {
         r#type: DiscordInteractionResponseType::ApplicationCommandAutocompleteResult,
         data: Some(DiscordInteractionResponseAutocomplete {
             choices: Some(options),
         }),
-    }
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::config::ConfigScope;
+    use crate::models::config::GuildConfig;
     use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
     use crate::models::discord::DiscordInteractionData;
     use crate::models::discord::DiscordInteractionType;
     use crate::models::discord::DiscordUser;
     use crate::test_utils::build_mocked_binahbot_env;
+    use ruina::ruina_common::game_objects::common::Chapter;
 
     // TODO: \u{2068} and \u{2069} are known as left-to-right marks. Their inclusion
     // is intentional w.r.t. localization. Implement helper test function to
     // remove these in order to increase test readability
-    #[test]
-    fn sanity_weight_of_sin() {
+    #[tokio::test]
+    async fn sanity_weight_of_sin() {
         let weight_of_sin_query = "the weight of sin";
         let interaction = build_discord_interaction(weight_of_sin_query.to_string(), true);
 
-        let response = lor_autocomplete(&interaction, &build_mocked_binahbot_env());
+        let response = lor_autocomplete(&interaction, &build_mocked_binahbot_env())
+            .await
+            .unwrap();
         let choices = response
             .data
             .as_ref()
@@ -112,12 +126,14 @@ mod tests {
             .contains(&"\u{2068}The Weight of Sin\u{2069} (\u{2068}passive\u{2069})".to_string()));
     }
 
-    #[test]
-    fn sanity_xiao() {
+    #[tokio::test]
+    async fn sanity_xiao() {
         let xiao_query = "Xiao";
         let interaction = build_discord_interaction(xiao_query.to_string(), true);
 
-        let response = lor_autocomplete(&interaction, &build_mocked_binahbot_env());
+        let response = lor_autocomplete(&interaction, &build_mocked_binahbot_env())
+            .await
+            .unwrap();
         let choices = response
             .data
             .as_ref()
@@ -136,12 +152,14 @@ mod tests {
         assert!(choices.contains(&"Xiao’s Page".to_string()));
     }
 
-    #[test]
-    fn sanity_should_keep_same_language_if_possible() {
+    #[tokio::test]
+    async fn sanity_should_keep_same_language_if_possible() {
         let kizuna_extreme_fatigue_query = "kizuna/extreme fatigue";
         let interaction = build_discord_interaction(kizuna_extreme_fatigue_query.to_string(), true);
 
-        let response = lor_autocomplete(&interaction, &build_mocked_binahbot_env());
+        let response = lor_autocomplete(&interaction, &build_mocked_binahbot_env())
+            .await
+            .unwrap();
         let choices = response
             .data
             .as_ref()
@@ -163,12 +181,14 @@ mod tests {
         ));
     }
 
-    #[test]
-    fn sanity_not_all() {
+    #[tokio::test]
+    async fn sanity_not_all() {
         let xiao_query = "Xiao";
         let interaction = build_discord_interaction(xiao_query.to_string(), false);
 
-        let response = lor_autocomplete(&interaction, &build_mocked_binahbot_env());
+        let response = lor_autocomplete(&interaction, &build_mocked_binahbot_env())
+            .await
+            .unwrap();
         let choices = response
             .data
             .as_ref()
@@ -187,6 +207,38 @@ mod tests {
         assert!(!choices.contains(&"Xiao’s Page".to_string()));
     }
 
+    #[tokio::test]
+    async fn sanity_progress() {
+        let env = build_mocked_binahbot_env();
+        let interaction = build_discord_interaction("Xiao".to_string(), true);
+
+        env.config_repository
+            .put_config(
+                &ConfigScope::User("snowflake".to_string()),
+                &GuildConfig {
+                    max_spoiler_chapter: Some(Chapter::Canard),
+                    ..GuildConfig::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let response = lor_autocomplete(&interaction, &env).await.unwrap();
+        let choices = response
+            .data
+            .as_ref()
+            .expect("no data field found")
+            .choices
+            .as_ref()
+            .expect("no embeds found")
+            .iter()
+            .map(|x| x.name.clone())
+            .collect::<Vec<_>>();
+
+        assert!(!choices
+            .contains(&"\u{2068}Xiao’s Page\u{2069} (\u{2068}collectable\u{2069})".to_string()));
+    }
+
     fn build_discord_interaction(query_string: String, is_all: bool) -> DiscordInteraction {
         DiscordInteraction {
             id: "id".to_string(),
@@ -196,6 +248,7 @@ mod tests {
                 DiscordApplicationCommandInteractionData {
                     id: "id".to_string(),
                     name: "lor".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
                     options: Some(vec![
                         DiscordInteractionOptions {
                             name: "query".to_string(),
@@ -210,9 +263,12 @@ mod tests {
                             focused: None,
                         },
                     ]),
+                    target_id: None,
+                    resolved: None,
                 },
             )),
             channel_id: None,
+            guild_id: None,
             token: "token".to_string(),
             locale: None,
             guild_locale: None,
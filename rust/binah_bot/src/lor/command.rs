@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::time::Duration;
 
 use fluent_templates::fluent_bundle::FluentValue;
 use fluent_templates::Loader;
@@ -8,12 +9,17 @@ use ruina::ruina_common::game_objects::common::Chapter;
 use ruina::ruina_common::game_objects::common::PageType;
 use ruina::ruina_common::localizations::common::Locale;
 use ruina::ruina_index::models::ParsedTypedId;
-use ruina::ruina_reparser::get_abno_page_by_internal_name;
 use ruina::ruina_reparser::get_combat_page_by_id;
 use ruina::ruina_reparser::get_key_page_by_id;
 use unic_langid::LanguageIdentifier;
 
+use crate::error::BinahBotError;
+use crate::guild_config::find_exceeded_spoiler_limit;
+use crate::guild_config::get_spoiler_limits;
+use crate::guild_config::SpoilerLimit;
+use crate::lor::lookup::get_page_chapter;
 use crate::lor::lookup::lookup;
+use crate::lor::lookup::page_exists;
 use crate::macros::cast_enum_variant;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::BinahBotLocale;
@@ -23,30 +29,39 @@ use crate::models::discord::AllowedMentions;
 use crate::models::discord::DiscordComponent;
 use crate::models::discord::DiscordComponentType;
 use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordEmbedImage;
 use crate::models::discord::DiscordInteraction;
-use crate::models::discord::DiscordInteractionData;
 use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionResponseMessage;
 use crate::models::discord::DiscordInteractionResponseType;
 use crate::models::discord::DiscordMessageFlag;
 use crate::models::discord::MessageResponse;
 
+use crate::lor::transformers::build_combat_page_card;
+use crate::lor::transformers::build_key_page_card;
 use crate::lor::transformers::transform_abno_page;
 use crate::lor::transformers::transform_battle_symbol;
 use crate::lor::transformers::transform_combat_page;
 use crate::lor::transformers::transform_key_page;
 use crate::lor::transformers::transform_passive;
+use crate::thumbnail::generate_card_image;
+use crate::thumbnail::generate_card_name;
+use crate::thumbnail::CardImage;
 use crate::utils::build_delete_button_component;
 use crate::utils::get_binahbot_locale;
+use crate::utils::get_choice_display_name;
+use crate::utils::get_command_options;
 use crate::utils::get_option_value;
+use crate::utils::get_reqwest_client;
 
-pub fn lor_command(interaction: &DiscordInteraction, env: &BinahBotEnvironment) -> MessageResponse {
-    let command_args = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-        .and_then(|x| x.options.as_ref())
-        .unwrap();
+// only decides whether to link the rendered card, so it mustn't hold up the response
+const CARD_IMAGE_CHECK_TIMEOUT: Duration = Duration::from_millis(500);
+
+pub async fn lor_command(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
 
     tracing::info!("Lor command: command args: {:#?}", command_args);
 
@@ -67,7 +82,7 @@ pub fn lor_command(interaction: &DiscordInteraction, env: &BinahBotEnvironment)
     let query = get_option_value("query", command_args)
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
         .and_then(|x| match ParsedTypedId::from_str(x) {
-            Ok(y) => Some(y),
+            Ok(y) => Some(y).filter(page_exists),
             Err(_) => lookup(&x, &locale, all).next(),
         });
 
@@ -75,46 +90,79 @@ pub fn lor_command(interaction: &DiscordInteraction, env: &BinahBotEnvironment)
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Bool))
         .is_some_and(|x| *x);
 
-    let typed_id = match query {
-        Some(x) => x,
-        None => return no_match_found(&lang_id, env),
-    };
+    Ok(match query {
+        Some(x) => build_lor_response(interaction, &x, &locale, is_private, env).await,
+        None => no_match_found(&lang_id, env),
+    })
+}
 
-    let max_spoiler_chapter = &interaction
-        .channel_id
-        .as_ref()
-        .and_then(|x| env.spoiler_config.get(&x));
-    let chapter = match typed_id.0 {
-        PageType::CombatPage => get_combat_page_by_id(&typed_id.1).and_then(|x| x.chapter.clone()),
-        PageType::KeyPage => get_key_page_by_id(&typed_id.1).and_then(|x| x.chapter.clone()),
-        PageType::AbnoPage => {
-            get_abno_page_by_internal_name(&typed_id.1).map(|x| x.abno.clone().into())
+// Embed for a resolved page, shared by the slash command and the message context menu
+pub async fn build_lor_response(
+    interaction: &DiscordInteraction,
+    typed_id: &ParsedTypedId,
+    locale: &Locale,
+    is_private: bool,
+    env: &BinahBotEnvironment,
+) -> MessageResponse {
+    let binah_locale = get_binahbot_locale(interaction);
+    let lang_id = LanguageIdentifier::from(&binah_locale);
+
+    let chapter = get_page_chapter(typed_id);
+    let spoiler_limits = get_spoiler_limits(interaction, is_private, env).await;
+    if let Some(spoiler_limit) = find_exceeded_spoiler_limit(&chapter, &spoiler_limits) {
+        return spoiler_found(&typed_id.1, &chapter, spoiler_limit, &lang_id, env);
+    }
+
+    // combat and key pages are looked up once for both the embed and the rendered card
+    let (mut embed, card): (DiscordEmbed, Option<CardImage>) = match typed_id.0 {
+        PageType::AbnoPage => (
+            transform_abno_page(&typed_id.1, locale, &binah_locale, env),
+            None,
+        ),
+        PageType::BattleSymbol => (
+            transform_battle_symbol(&typed_id.1, locale, &binah_locale, env),
+            None,
+        ),
+        PageType::CombatPage => {
+            let Some(page) = get_combat_page_by_id(&typed_id.1) else {
+                return no_match_found(&lang_id, env);
+            };
+            (
+                transform_combat_page(page, locale, &binah_locale, env),
+                Some(CardImage::CombatPage(build_combat_page_card(
+                    page,
+                    locale,
+                    &binah_locale,
+                    env,
+                ))),
+            )
         }
-        // todo: fix passive chapter mappings. seems to be a lot of mistakes
-        // PageType::Passive => get_passive_by_id(&typed_id.1).and_then(|x| x.chapter.clone()),
-        // todo: fine-grained spoilers for battle symbols
-        _ => Some(Chapter::Canard),
-    };
-    if let Some(max_spoiler_chapter) = max_spoiler_chapter {
-        let effective_chapter = chapter.unwrap_or(Chapter::ImpuritasCivitatis);
-        if !is_private && effective_chapter > **max_spoiler_chapter {
-            return spoiler_found(
-                &typed_id.1,
-                &effective_chapter,
-                max_spoiler_chapter,
-                &lang_id,
-                env,
-            );
+        PageType::KeyPage => {
+            let Some(page) = get_key_page_by_id(&typed_id.1) else {
+                return no_match_found(&lang_id, env);
+            };
+            (
+                transform_key_page(page, locale, &binah_locale, env),
+                Some(CardImage::KeyPage(Box::new(build_key_page_card(
+                    page,
+                    locale,
+                    &binah_locale,
+                    env,
+                )))),
+            )
         }
+        PageType::Passive => (
+            transform_passive(&typed_id.1, locale, &binah_locale, env),
+            None,
+        ),
     };
 
-    let embed: DiscordEmbed = match typed_id.0 {
-        PageType::AbnoPage => transform_abno_page,
-        PageType::BattleSymbol => transform_battle_symbol,
-        PageType::CombatPage => transform_combat_page,
-        PageType::KeyPage => transform_key_page,
-        PageType::Passive => transform_passive,
-    }(&typed_id.1, &locale, &binah_locale, env);
+    // the rendered card replaces the bare artwork once it's in the bucket
+    if let Some(card) = card {
+        if let Some(url) = get_card_image_url(&card, env).await {
+            embed.image = Some(DiscordEmbedImage { url });
+        }
+    }
 
     let flags = is_private.then_some(DiscordMessageFlag::EphemeralMessage as i32);
 
@@ -138,6 +186,38 @@ pub fn lor_command(interaction: &DiscordInteraction, env: &BinahBotEnvironment)
     }
 }
 
+// Cards are named by their contents, so one that was already rendered can be linked right away.
+// Otherwise it's rendered in the background for later lookups, and this response keeps the bare
+// artwork instead of waiting on the render
+async fn get_card_image_url(card: &CardImage, env: &BinahBotEnvironment) -> Option<String> {
+    let lambda_client = env.lambda_client.as_ref()?;
+    let name = generate_card_name(card).ok()?;
+    let url = format!(
+        "https://{0}.s3.amazonaws.com/{1}/{2}.png",
+        env.s3_bucket_name, env.card_directory, name
+    );
+
+    let is_rendered = match get_reqwest_client(env) {
+        Ok(client) => client
+            .head(&url)
+            .timeout(CARD_IMAGE_CHECK_TIMEOUT)
+            .send()
+            .await
+            .is_ok_and(|x| x.status().is_success()),
+        Err(_) => false,
+    };
+    if is_rendered {
+        return Some(url);
+    }
+
+    if let Err(e) =
+        generate_card_image(lambda_client, &env.thumbnail_lambda_name, &name, card).await
+    {
+        tracing::error!("Failed to start rendering card {}: {}", name, e);
+    }
+    None
+}
+
 fn no_match_found(lang_id: &LanguageIdentifier, env: &BinahBotEnvironment) -> MessageResponse {
     MessageResponse {
         r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
@@ -164,10 +244,15 @@ fn no_match_found(lang_id: &LanguageIdentifier, env: &BinahBotEnvironment) -> Me
 fn spoiler_found(
     card_id: &str,
     chapter: &Chapter,
-    configured_chapter: &Chapter,
+    spoiler_limit: &SpoilerLimit,
     lang_id: &LanguageIdentifier,
     env: &BinahBotEnvironment,
 ) -> MessageResponse {
+    let message_key = match spoiler_limit {
+        SpoilerLimit::Channel(_) => "spoiler_enforcement_message",
+        SpoilerLimit::Progress(_) => "progress_enforcement_message",
+    };
+
     MessageResponse {
         r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
         data: Some(DiscordInteractionResponseMessage {
@@ -177,13 +262,24 @@ fn spoiler_found(
                 title: None,
                 description: Some(env.locales.lookup_with_args(
                     &lang_id,
-                    "spoiler_enforcement_message",
+                    message_key,
                     &HashMap::from([
                         ("card_id", FluentValue::from(card_id)),
-                        ("chapter", FluentValue::from(chapter.to_string())),
+                        (
+                            "chapter",
+                            FluentValue::from(get_choice_display_name(
+                                &chapter.to_string(),
+                                lang_id,
+                                env,
+                            )),
+                        ),
                         (
                             "configured_chapter",
-                            FluentValue::from(configured_chapter.to_string()),
+                            FluentValue::from(get_choice_display_name(
+                                &spoiler_limit.chapter().to_string(),
+                                lang_id,
+                                env,
+                            )),
                         ),
                     ]),
                 )),
@@ -205,7 +301,10 @@ fn spoiler_found(
 mod tests {
     use super::*;
     use crate::lor::lookup::is_collectable_or_obtainable;
+    use crate::models::config::ConfigScope;
+    use crate::models::config::GuildConfig;
     use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
     use crate::models::discord::DiscordInteractionData;
     use crate::models::discord::DiscordInteractionOptions;
     use crate::models::discord::DiscordInteractionType;
@@ -220,8 +319,8 @@ mod tests {
     use strum::IntoEnumIterator;
     use unic_langid::langid;
 
-    #[test]
-    fn sanity_weight_of_sin() {
+    #[tokio::test]
+    async fn sanity_weight_of_sin() {
         let weight_of_sin_id = "a#LongBird_Sin";
         let interaction =
             build_discord_interaction(weight_of_sin_id.to_string(), Locale::English, None);
@@ -229,8 +328,8 @@ mod tests {
             build_discord_interaction(weight_of_sin_id.to_string(), Locale::Korean, None);
         let env = build_mocked_binahbot_env();
 
-        let response = lor_command(&interaction, &env);
-        let response_kr = lor_command(&interaction_kr, &env);
+        let response = lor_command(&interaction, &env).await.unwrap();
+        let response_kr = lor_command(&interaction_kr, &env).await.unwrap();
         assert_eq!(
             response
                 .data
@@ -283,14 +382,14 @@ mod tests {
         );
     }
 
-    #[test]
-    fn sanity_degraded_pillar() {
+    #[tokio::test]
+    async fn sanity_degraded_pillar() {
         let degraded_pillar_id = "c#607204";
         let interaction =
             build_discord_interaction(degraded_pillar_id.to_string(), Locale::English, None);
         let env = build_mocked_binahbot_env();
 
-        let response = lor_command(&interaction, &env);
+        let response = lor_command(&interaction, &env).await.unwrap();
         assert_eq!(
             response
                 .data
@@ -302,15 +401,15 @@ mod tests {
         );
     }
 
-    #[test]
+    #[tokio::test]
     #[ignore]
-    fn regenerative_mimicry_passive_realization() {
+    async fn regenerative_mimicry_passive_realization() {
         let regenerative = "p#605532";
         let interaction =
             build_discord_interaction(regenerative.to_string(), Locale::English, None);
         let env = build_mocked_binahbot_env();
 
-        let response = lor_command(&interaction, &env);
+        let response = lor_command(&interaction, &env).await.unwrap();
         assert_eq!(
             response
                 .data
@@ -322,8 +421,8 @@ mod tests {
         );
     }
 
-    #[test]
-    fn best_match() {
+    #[tokio::test]
+    async fn best_match() {
         let liu_section_1_collectable = "250019";
         let liu_section_1_enemy_query = "Liu Section 1 enemy";
         let env = build_mocked_binahbot_env();
@@ -332,7 +431,7 @@ mod tests {
         let interaction =
             build_discord_interaction(liu_section_1_enemy_query.to_string(), Locale::English, None);
 
-        let response = lor_command(&interaction, &env);
+        let response = lor_command(&interaction, &env).await.unwrap();
 
         assert_eq!(
             response
@@ -350,8 +449,8 @@ mod tests {
         );
     }
 
-    #[test]
-    fn card_script_without_locale() {
+    #[tokio::test]
+    async fn card_script_without_locale() {
         // Enemy-only FMF contains a card script and a die script that doesn't have
         // an associated locale with it.
         let enemy_fourth_match_flame = "c#9901101";
@@ -359,7 +458,7 @@ mod tests {
             build_discord_interaction(enemy_fourth_match_flame.to_string(), Locale::English, None);
         let env = build_mocked_binahbot_env();
 
-        let response = lor_command(&interaction, &env);
+        let response = lor_command(&interaction, &env).await.unwrap();
         assert_eq!(
             response
                 .data
@@ -371,8 +470,8 @@ mod tests {
         );
     }
 
-    #[test]
-    fn spoiler_enforcement() {
+    #[tokio::test]
+    async fn spoiler_enforcement() {
         let channel_id = "1234567890123456789".to_string();
         let true_trigram_formation = "c#701001";
         let interaction = build_discord_interaction(
@@ -382,12 +481,12 @@ mod tests {
         );
         let env = build_mocked_binahbot_env();
 
-        let response = lor_command(&interaction, &env);
+        let response = lor_command(&interaction, &env).await.unwrap();
 
         let expected = spoiler_found(
             "701001",
             &Chapter::ImpuritasCivitatis,
-            &Chapter::StarOfTheCity,
+            &SpoilerLimit::Channel(Chapter::StarOfTheCity),
             &langid!("en-US"),
             &env,
         );
@@ -411,27 +510,97 @@ mod tests {
         );
         let env = build_mocked_binahbot_env();
 
-        let response = lor_command(&interaction, &env);
+        let response = lor_command(&interaction, &env).await.unwrap();
 
         let expected = spoiler_found(
             "ApocalypseBird_Apocalypse",
             &Chapter::ImpuritasCivitatis,
-            &Chapter::StarOfTheCity,
+            &SpoilerLimit::Channel(Chapter::StarOfTheCity),
+            &langid!("en-US"),
+            &env,
+        );
+        assert_eq!(get_description(&expected), get_description(&response));
+
+        // passives follow the key pages that grant them
+        let passive = "p#260001";
+        let interaction = build_discord_interaction(
+            passive.to_string(),
+            Locale::English,
+            Some(channel_id.clone()),
+        );
+
+        let response = lor_command(&interaction, &env).await.unwrap();
+
+        let expected = spoiler_found(
+            "260001",
+            &Chapter::ImpuritasCivitatis,
+            &SpoilerLimit::Channel(Chapter::StarOfTheCity),
+            &langid!("en-US"),
+            &env,
+        );
+        assert_eq!(get_description(&expected), get_description(&response));
+
+        // battle symbols follow the receptions that grant them
+        let hana_battle_symbol = "b#Hana";
+        let interaction = build_discord_interaction(
+            hana_battle_symbol.to_string(),
+            Locale::English,
+            Some(channel_id.clone()),
+        );
+
+        let response = lor_command(&interaction, &env).await.unwrap();
+
+        let expected = spoiler_found(
+            "Hana",
+            &Chapter::ImpuritasCivitatis,
+            &SpoilerLimit::Channel(Chapter::StarOfTheCity),
             &langid!("en-US"),
             &env,
         );
         assert_eq!(get_description(&expected), get_description(&response));
     }
 
-    #[test]
-    fn no_spoiler_enforcement() {
+    #[tokio::test]
+    async fn progress_enforcement() {
+        // no channel, as in DMs
+        let true_trigram_formation = "c#701001";
+        let interaction =
+            build_discord_interaction(true_trigram_formation.to_string(), Locale::English, None);
+        let env = build_mocked_binahbot_env();
+        env.config_repository
+            .put_config(
+                &ConfigScope::User("snowflake".to_string()),
+                &GuildConfig {
+                    max_spoiler_chapter: Some(Chapter::UrbanNightmare),
+                    ..GuildConfig::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let typed_id = ParsedTypedId::from_str(true_trigram_formation).unwrap();
+        let response =
+            build_lor_response(&interaction, &typed_id, &Locale::English, true, &env).await;
+
+        let expected = spoiler_found(
+            "701001",
+            &Chapter::ImpuritasCivitatis,
+            &SpoilerLimit::Progress(Chapter::UrbanNightmare),
+            &langid!("en-US"),
+            &env,
+        );
+        assert_eq!(format!("{:?}", expected), format!("{:?}", response));
+    }
+
+    #[tokio::test]
+    async fn no_spoiler_enforcement() {
         let channel_id = "1234567890123456789".to_string();
         let pale_hands = "a#Bloodbath_Hands";
         let interaction =
             build_discord_interaction(pale_hands.to_string(), Locale::English, Some(channel_id));
         let env = build_mocked_binahbot_env();
 
-        let response = lor_command(&interaction, &env);
+        let response = lor_command(&interaction, &env).await.unwrap();
 
         let get_description = |x: &MessageResponse| -> Option<String> {
             x.data
@@ -444,9 +613,9 @@ mod tests {
         assert_eq!(None, get_description(&response));
     }
 
-    #[test]
-    fn check_no_crashing() {
-        Locale::iter().for_each(|locale: Locale| {
+    #[tokio::test]
+    async fn check_no_crashing() {
+        for locale in Locale::iter() {
             let abno_page_ids = get_all_abno_pages()
                 .iter()
                 .map(|x| ParsedTypedId(PageType::AbnoPage, x.internal_name.to_string()))
@@ -468,22 +637,21 @@ mod tests {
                 .map(|x| ParsedTypedId(PageType::Passive, x.id.to_string()))
                 .collect::<Vec<_>>();
 
-            abno_page_ids
+            let ids = abno_page_ids
                 .into_iter()
                 .chain(battle_symbol_ids)
                 .chain(combat_page_ids)
                 .chain(keypage_ids)
                 .chain(passive_ids)
                 .filter(is_collectable_or_obtainable)
-                .filter(|x| get_display_name_locale(x, &locale).is_some())
-                .for_each(|x| {
-                    let interaction =
-                        build_discord_interaction(x.to_string(), locale.clone(), None);
-                    let env = build_mocked_binahbot_env();
-
-                    let _does_not_crash = lor_command(&interaction, &env);
-                });
-        });
+                .filter(|x| get_display_name_locale(x, &locale).is_some());
+            for x in ids {
+                let interaction = build_discord_interaction(x.to_string(), locale.clone(), None);
+                let env = build_mocked_binahbot_env();
+
+                assert!(lor_command(&interaction, &env).await.is_ok());
+            }
+        }
     }
 
     fn build_discord_interaction(
@@ -499,6 +667,7 @@ mod tests {
                 DiscordApplicationCommandInteractionData {
                     id: "id".to_string(),
                     name: "lor".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
                     options: Some(vec![
                         DiscordInteractionOptions {
                             name: "query".to_string(),
@@ -513,9 +682,12 @@ mod tests {
                             focused: None,
                         },
                     ]),
+                    target_id: None,
+                    resolved: None,
                 },
             )),
             channel_id: channel_id,
+            guild_id: None,
             token: "token".to_string(),
             locale: None,
             guild_locale: None,
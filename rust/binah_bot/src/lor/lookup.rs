@@ -1,6 +1,10 @@
+use ruina::ruina_common::game_objects::common::Chapter;
 use ruina::ruina_common::game_objects::common::Collectability;
+use ruina::ruina_common::game_objects::common::PageType;
 use ruina::ruina_common::localizations::common::Locale;
 use ruina::ruina_index::models::ParsedTypedId;
+use ruina::ruina_reparser::get_abno_page_by_internal_name;
+use ruina::ruina_reparser::get_battle_symbol_by_internal_name;
 use ruina::ruina_reparser::get_combat_page_by_id;
 use ruina::ruina_reparser::get_key_page_by_id;
 use ruina::ruina_reparser::get_passive_by_id;
@@ -18,6 +22,35 @@ pub fn lookup<'a>(
         .filter(move |x| all || get_display_name_locale(x, &locale).is_some())
 }
 
+// typed ids can come straight from a stale autocomplete value, so they aren't trusted
+pub fn page_exists(parsed_typed_id: &ParsedTypedId) -> bool {
+    match parsed_typed_id.0 {
+        PageType::AbnoPage => get_abno_page_by_internal_name(&parsed_typed_id.1).is_some(),
+        PageType::BattleSymbol => get_battle_symbol_by_internal_name(&parsed_typed_id.1).is_some(),
+        PageType::CombatPage => get_combat_page_by_id(&parsed_typed_id.1).is_some(),
+        PageType::KeyPage => get_key_page_by_id(&parsed_typed_id.1).is_some(),
+        PageType::Passive => get_passive_by_id(&parsed_typed_id.1).is_some(),
+    }
+}
+
+// Pages without a known chapter are treated as the latest one
+pub fn get_page_chapter(parsed_typed_id: &ParsedTypedId) -> Chapter {
+    match parsed_typed_id.0 {
+        PageType::CombatPage => {
+            get_combat_page_by_id(&parsed_typed_id.1).and_then(|x| x.chapter.clone())
+        }
+        PageType::KeyPage => get_key_page_by_id(&parsed_typed_id.1).and_then(|x| x.chapter.clone()),
+        PageType::AbnoPage => {
+            get_abno_page_by_internal_name(&parsed_typed_id.1).map(|x| x.abno.clone().into())
+        }
+        PageType::Passive => get_passive_by_id(&parsed_typed_id.1).and_then(|x| x.chapter.clone()),
+        PageType::BattleSymbol => {
+            get_battle_symbol_by_internal_name(&parsed_typed_id.1).and_then(|x| x.chapter.clone())
+        }
+    }
+    .unwrap_or(Chapter::ImpuritasCivitatis)
+}
+
 pub fn is_collectable_or_obtainable(parsed_typed_id: &ParsedTypedId) -> bool {
     match parsed_typed_id.0 {
         ruina::ruina_common::game_objects::common::PageType::CombatPage => {
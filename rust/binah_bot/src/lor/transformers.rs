@@ -2,12 +2,14 @@ use std::string::ToString;
 
 use fluent_templates::Loader;
 use ruina::ruina_common::game_objects::battle_symbol::BattleSymbolSlot;
+use ruina::ruina_common::game_objects::combat_page::CombatPage;
 use ruina::ruina_common::game_objects::combat_page::CombatRange;
 use ruina::ruina_common::game_objects::combat_page::Die;
 use ruina::ruina_common::game_objects::combat_page::DieType;
 use ruina::ruina_common::game_objects::common::Floor;
 use ruina::ruina_common::game_objects::common::PageType;
 use ruina::ruina_common::game_objects::common::Rarity;
+use ruina::ruina_common::game_objects::key_page::KeyPage;
 use ruina::ruina_common::game_objects::key_page::Resistance;
 use ruina::ruina_common::localizations::combat_page_locale::CombatPageLocale;
 use ruina::ruina_common::localizations::common::Locale;
@@ -17,9 +19,7 @@ use ruina::ruina_reparser::get_abno_page_locales_by_internal_name;
 use ruina::ruina_reparser::get_battle_symbol_by_internal_name;
 use ruina::ruina_reparser::get_battle_symbol_locales_by_internal_name;
 use ruina::ruina_reparser::get_card_effect_locales_by_id;
-use ruina::ruina_reparser::get_combat_page_by_id;
 use ruina::ruina_reparser::get_combat_page_locales_by_id;
-use ruina::ruina_reparser::get_key_page_by_id;
 use ruina::ruina_reparser::get_passive_by_id;
 use ruina::ruina_reparser::get_passive_locales_by_id;
 use unic_langid::LanguageIdentifier;
@@ -33,6 +33,10 @@ use crate::models::discord::DiscordEmbed;
 use crate::models::discord::DiscordEmbedFields;
 use crate::models::discord::DiscordEmbedFooter;
 use crate::models::discord::DiscordEmbedImage;
+use crate::thumbnail::CardDie;
+use crate::thumbnail::CardResist;
+use crate::thumbnail::CombatPageCard;
+use crate::thumbnail::KeyPageCard;
 use crate::utils::get_disambiguation_format;
 
 static NOT_FOUND_IMAGE_NAME: &str = "404_Not_Found";
@@ -241,11 +245,12 @@ pub fn transform_battle_symbol(
 }
 
 pub fn transform_combat_page(
-    id: &str,
+    page: &CombatPage,
     card_locale: &Locale,
     request_locale: &BinahBotLocale,
     env: &BinahBotEnvironment,
 ) -> DiscordEmbed {
+    let id = page.id;
     tracing::info!(
         "Transforming combat page with id={}, card_locale={}, request_locale={}",
         id,
@@ -253,7 +258,6 @@ pub fn transform_combat_page(
         request_locale
     );
 
-    let page = get_combat_page_by_id(id).unwrap();
     let binding = get_combat_page_locales_by_id(id);
     let page_locale = binding.get(card_locale);
     let lang_id = LanguageIdentifier::from(request_locale);
@@ -294,26 +298,7 @@ pub fn transform_combat_page(
         },
     ];
 
-    let single_use_prefix = if page.options.contains(&"ExhaustOnUse") {
-        format!("{}\n", env.locales.lookup(&lang_id, "exhaust_on_use"))
-    } else {
-        "".to_string()
-    };
-
-    let page_desc = page
-        .script_id
-        .and_then(|x| {
-            get_card_effect_locales_by_id(x)
-                .get(card_locale)
-                .map(|y| y.desc.join("\n").to_string())
-        })
-        .or(page_locale
-            .as_ref()
-            .and_then(|x| x.card_effect)
-            .map(|x| x.to_string()))
-        .unwrap_or("".to_string());
-    let page_desc = format!("{}{}", single_use_prefix, page_desc);
-    let page_desc = page_desc.trim().to_string();
+    let page_desc = get_combat_page_description(page, card_locale, &page_locale, &lang_id, env);
 
     if !page_desc.is_empty() {
         fields.push(DiscordEmbedFields {
@@ -348,12 +333,90 @@ pub fn transform_combat_page(
     }
 }
 
+pub fn build_combat_page_card(
+    page: &CombatPage,
+    card_locale: &Locale,
+    request_locale: &BinahBotLocale,
+    env: &BinahBotEnvironment,
+) -> CombatPageCard {
+    let id = page.id;
+    let binding = get_combat_page_locales_by_id(id);
+    let page_locale = binding.get(card_locale);
+    let lang_id = LanguageIdentifier::from(request_locale);
+
+    CombatPageCard {
+        artwork: page.artwork.map(|x| x.to_string()),
+        name: page_locale
+            .map(|x| x.name.to_string())
+            .unwrap_or(id.to_string()),
+        cost: page.cost,
+        frame_color: DiscordEmbedColors::from(&page.rarity) as u32,
+        description: get_combat_page_description(page, card_locale, &page_locale, &lang_id, env),
+        dice: page
+            .dice
+            .iter()
+            .enumerate()
+            .map(|(i, die)| CardDie {
+                die_type: die.die_type.to_string(),
+                min: die.min,
+                max: die.max,
+                description: get_die_description(die, i, card_locale, &page_locale),
+            })
+            .collect(),
+    }
+}
+
+pub fn build_key_page_card(
+    page: &KeyPage,
+    card_locale: &Locale,
+    request_locale: &BinahBotLocale,
+    env: &BinahBotEnvironment,
+) -> KeyPageCard {
+    let id = page.id;
+    let lang_id = LanguageIdentifier::from(request_locale);
+    let resist = |x: &Resistance| CardResist {
+        resistance: x.to_string(),
+        label: env.locales.lookup(&lang_id, resistance_to_locale_key(x)),
+    };
+
+    KeyPageCard {
+        id: page.id.to_string(),
+        name: get_disambiguation_format(
+            &ParsedTypedId(PageType::KeyPage, id.to_string()),
+            card_locale,
+            &lang_id,
+            env,
+        ),
+        frame_color: DiscordEmbedColors::from(&page.rarity) as u32,
+        hp: page.hp,
+        stagger: page.stagger,
+        min_speed: page.min_speed,
+        max_speed: page.max_speed,
+        hp_label: env.locales.lookup(&lang_id, "key_page_hp_header"),
+        stagger_label: env.locales.lookup(&lang_id, "key_page_stagger_header"),
+        speed_label: env.locales.lookup(&lang_id, "key_page_speed_header"),
+        hp_resists: [
+            resist(&page.resists.hp_slash),
+            resist(&page.resists.hp_pierce),
+            resist(&page.resists.hp_blunt),
+        ],
+        stagger_resists: [
+            resist(&page.resists.stagger_slash),
+            resist(&page.resists.stagger_pierce),
+            resist(&page.resists.stagger_blunt),
+        ],
+        passives_label: env.locales.lookup(&lang_id, "key_page_passives_header"),
+        passives: get_passive_names(page.passive_ids, card_locale),
+    }
+}
+
 pub fn transform_key_page(
-    id: &str,
+    page: &KeyPage,
     card_locale: &Locale,
     request_locale: &BinahBotLocale,
     env: &BinahBotEnvironment,
 ) -> DiscordEmbed {
+    let id = page.id;
     tracing::info!(
         "Transforming key page with id={}, card_locale={}, request_locale={}",
         id,
@@ -361,7 +424,6 @@ pub fn transform_key_page(
         request_locale
     );
 
-    let page = get_key_page_by_id(id).unwrap();
     let lang_id = LanguageIdentifier::from(request_locale);
 
     let display_name = get_disambiguation_format(
@@ -468,16 +530,7 @@ pub fn transform_key_page(
         })
     }
     if !page.passive_ids.is_empty() {
-        let passive_names: Vec<_> = page
-            .passive_ids
-            .iter()
-            .map(|x| {
-                get_passive_locales_by_id(x)
-                    .get(card_locale)
-                    .map(|y| y.name.to_string())
-                    .unwrap_or(x.to_string())
-            })
-            .collect();
+        let passive_names = get_passive_names(page.passive_ids, card_locale);
         fields.push(DiscordEmbedFields {
             name: env.locales.lookup(&lang_id, "key_page_passives_header"),
             value: format_to_indented_list(&passive_names),
@@ -585,35 +638,73 @@ fn format_dice(
         .iter()
         .enumerate()
         .map(|(i, die)| {
-            let binding = die
-                .script
-                .map(get_card_effect_locales_by_id)
-                .and_then(|x| x.get(locale).map(|y| y.desc))
-                .map(|x| x.join("\n"));
-
-            let desc = combat_page_locale
-                .and_then(|x| {
-                    if x.dice_description_override.len() > i {
-                        x.dice_description_override[i]
-                    } else {
-                        None
-                    }
-                })
-                .or(binding.as_deref())
-                .unwrap_or("");
-
             format!(
                 "{} {}-{} {}",
                 get_dietype_emoji(emojis, &die.die_type),
                 die.min,
                 die.max,
-                desc
+                get_die_description(die, i, locale, combat_page_locale)
             )
         })
         .collect::<Vec<_>>();
     format_to_indented_list(&formatted_die)
 }
 
+fn get_die_description(
+    die: &Die,
+    index: usize,
+    locale: &Locale,
+    combat_page_locale: &Option<&&CombatPageLocale>,
+) -> String {
+    let binding = die
+        .script
+        .map(get_card_effect_locales_by_id)
+        .and_then(|x| x.get(locale).map(|y| y.desc))
+        .map(|x| x.join("\n"));
+
+    combat_page_locale
+        .and_then(|x| {
+            if x.dice_description_override.len() > index {
+                x.dice_description_override[index]
+            } else {
+                None
+            }
+        })
+        .or(binding.as_deref())
+        .unwrap_or("")
+        .to_string()
+}
+
+fn get_combat_page_description(
+    page: &CombatPage,
+    card_locale: &Locale,
+    page_locale: &Option<&&CombatPageLocale>,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> String {
+    let single_use_prefix = if page.options.contains(&"ExhaustOnUse") {
+        format!("{}\n", env.locales.lookup(lang_id, "exhaust_on_use"))
+    } else {
+        "".to_string()
+    };
+
+    let page_desc = page
+        .script_id
+        .and_then(|x| {
+            get_card_effect_locales_by_id(x)
+                .get(card_locale)
+                .map(|y| y.desc.join("\n").to_string())
+        })
+        .or(page_locale
+            .as_ref()
+            .and_then(|x| x.card_effect)
+            .map(|x| x.to_string()))
+        .unwrap_or("".to_string());
+    format!("{}{}", single_use_prefix, page_desc)
+        .trim()
+        .to_string()
+}
+
 fn floor_to_locale_key(floor: &Floor) -> &'static str {
     match floor {
         Floor::Malkuth => "floor_display_malkuth",
@@ -641,6 +732,18 @@ fn combat_range_to_locale_key(range: &CombatRange) -> &'static str {
     }
 }
 
+fn get_passive_names(passive_ids: &[&str], card_locale: &Locale) -> Vec<String> {
+    passive_ids
+        .iter()
+        .map(|x| {
+            get_passive_locales_by_id(x)
+                .get(card_locale)
+                .map(|y| y.name.to_string())
+                .unwrap_or(x.to_string())
+        })
+        .collect()
+}
+
 fn resistance_to_locale_key(resistance: &Resistance) -> &'static str {
     match resistance {
         Resistance::Fatal => "resistance_display_fatal",
@@ -685,6 +788,8 @@ fn format_to_indented_list(v: &[String]) -> String {
 
 #[cfg(test)]
 mod tests {
+    use ruina::ruina_reparser::get_combat_page_by_id;
+    use ruina::ruina_reparser::get_key_page_by_id;
     use unic_langid::langid;
 
     use crate::test_utils::build_mocked_binahbot_env;
@@ -753,7 +858,7 @@ mod tests {
             .locales
             .lookup(&langid!("en-US"), "combat_page_description_header");
         let embed = transform_combat_page(
-            "9910108",
+            get_combat_page_by_id("9910108").unwrap(),
             &Locale::English,
             &BinahBotLocale::EnglishUS,
             &env,
@@ -770,6 +875,46 @@ mod tests {
         )
     }
 
+    #[test]
+    fn sanity_build_combat_page_card() {
+        let env = build_mocked_binahbot_env();
+        let page = get_combat_page_by_id("504001").unwrap();
+
+        let card = build_combat_page_card(page, &Locale::English, &BinahBotLocale::EnglishUS, &env);
+        assert_eq!(page.cost, card.cost);
+        assert_eq!(
+            DiscordEmbedColors::from(&page.rarity) as u32,
+            card.frame_color
+        );
+        assert_eq!(page.dice.len(), card.dice.len());
+        assert!(card.description.contains("Single-use"));
+        assert!(card
+            .dice
+            .iter()
+            .zip(page.dice.iter())
+            .all(|(x, y)| x.die_type == y.die_type.to_string() && x.min == y.min));
+    }
+
+    #[test]
+    fn sanity_build_key_page_card() {
+        let env = build_mocked_binahbot_env();
+        let page = get_key_page_by_id("250023").unwrap();
+
+        let card = build_key_page_card(page, &Locale::English, &BinahBotLocale::EnglishUS, &env);
+        assert_eq!(page.hp, card.hp);
+        assert_eq!(page.stagger, card.stagger);
+        assert_eq!(page.passive_ids.len(), card.passives.len());
+        assert_eq!(
+            page.resists.hp_slash.to_string(),
+            card.hp_resists[0].resistance
+        );
+        assert_eq!(
+            page.resists.stagger_blunt.to_string(),
+            card.stagger_resists[2].resistance
+        );
+        assert_eq!("HP", card.hp_label);
+    }
+
     #[test]
     fn exhaust_on_use() {
         let env = build_mocked_binahbot_env();
@@ -777,8 +922,12 @@ mod tests {
         let en_header = env
             .locales
             .lookup(&langid!("en-US"), "combat_page_description_header");
-        let embed =
-            transform_combat_page("504001", &Locale::English, &BinahBotLocale::EnglishUS, &env);
+        let embed = transform_combat_page(
+            get_combat_page_by_id("504001").unwrap(),
+            &Locale::English,
+            &BinahBotLocale::EnglishUS,
+            &env,
+        );
 
         let binding = embed.fields.unwrap();
         let page_description = binding.iter().find(|y| y.name == en_header);
@@ -787,8 +936,12 @@ mod tests {
         let ja_header = env
             .locales
             .lookup(&langid!("ja"), "combat_page_description_header");
-        let embed =
-            transform_combat_page("504001", &Locale::Japanese, &BinahBotLocale::Japanese, &env);
+        let embed = transform_combat_page(
+            get_combat_page_by_id("504001").unwrap(),
+            &Locale::Japanese,
+            &BinahBotLocale::Japanese,
+            &env,
+        );
 
         let binding = embed.fields.unwrap();
         let page_description = binding.iter().find(|y| y.name == ja_header);
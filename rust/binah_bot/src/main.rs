@@ -1,32 +1,68 @@
 mod about_command;
+mod commands;
+mod config_command;
+mod context_menu_command;
 mod ddb;
 mod deck;
 mod discord;
+mod error;
+mod guild_config;
 mod lc;
 mod lor;
 mod macros;
 mod models;
+mod progress_command;
+mod repository;
 mod rollcalc_command;
 mod router;
 mod secrets;
+#[cfg(feature = "standalone")]
+mod server;
+mod spoilers_command;
 mod thumbnail;
 mod tiph;
 mod utils;
 
+use commands::REGISTER_COMMANDS_COMMAND;
+use deck::reindex_decks::reindex_decks;
+use deck::reindex_decks::REINDEX_DECKS_COMMAND;
 use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use guild_config::ProgressCache;
 use hex::FromHex;
 use http::HeaderMap;
-use lambda_http::{run, service_fn, tracing, Body, Request, Response};
+#[cfg(not(feature = "standalone"))]
+use lambda_http::{run, service_fn, Body, Request};
+use lambda_http::{tracing, Response};
 use models::binahbot::BinahBotEnvironment;
 use models::binahbot::DiscordSecrets;
 use models::binahbot::Emojis;
 use models::discord::DiscordInteraction;
 use models::discord::DiscordInteractionValidationData;
+use repository::dynamodb::DynamoDbConfigRepository;
+use repository::dynamodb::DynamoDbDeckRepository;
+use repository::dynamodb::DynamoDbInteractionStore;
+use repository::memory::InMemoryConfigRepository;
+use repository::memory::InMemoryDeckRepository;
+use repository::memory::InMemoryInteractionStore;
+#[cfg(feature = "sqlite")]
+use repository::sqlite::open_connection;
+#[cfg(feature = "sqlite")]
+use repository::sqlite::SqliteConfigRepository;
+#[cfg(feature = "sqlite")]
+use repository::sqlite::SqliteDeckRepository;
+#[cfg(feature = "sqlite")]
+use repository::sqlite::SqliteInteractionStore;
+use repository::ConfigRepository;
+use repository::DeckRepository;
+use repository::InteractionStore;
 use router::get_response;
 use ruina::ruina_common::game_objects::common::Chapter;
-use secrets::get_discord_secrets;
+use secrets::load_discord_secrets;
+#[cfg(feature = "standalone")]
+use server::serve;
 use std::env;
 use std::error::Error;
+#[cfg(not(feature = "standalone"))]
 use std::ops::Deref;
 
 fluent_templates::static_loader! {
@@ -41,6 +77,7 @@ include!(concat!(env!("OUT_DIR"), "/out.rs"));
 static TIMESTAMP_HEADER: &str = "x-signature-timestamp";
 static SIGNATURE_HEADER: &str = "x-signature-ed25519";
 
+#[cfg(not(feature = "standalone"))]
 async fn function_handler(
     event: Request,
     binahbot_env: &BinahBotEnvironment,
@@ -48,8 +85,24 @@ async fn function_handler(
     tracing::info!("Rust function invoked with event={:?}", event);
 
     let request_body: String = String::from_utf8(event.body().deref().to_vec()).unwrap();
-    let request_headers: &HeaderMap = event.headers();
+    let response = handle_interaction(event.headers(), request_body, binahbot_env).await?;
 
+    tracing::debug!("Rust function finished invocation");
+    Ok(response.map(|x| {
+        if x.is_empty() {
+            Body::Empty
+        } else {
+            Body::Text(x)
+        }
+    }))
+}
+
+// Validates and answers a single interaction, independent of how it was received
+async fn handle_interaction(
+    request_headers: &HeaderMap,
+    request_body: String,
+    binahbot_env: &BinahBotEnvironment,
+) -> Result<Response<String>, Box<dyn Error + Send + Sync>> {
     let event_metadata = DiscordInteractionValidationData {
         timestamp: get_header(request_headers, TIMESTAMP_HEADER),
         signature: get_header(request_headers, SIGNATURE_HEADER),
@@ -66,22 +119,20 @@ async fn function_handler(
         let resp = Response::builder()
             .status(401)
             .header("content-type", "application/json")
-            .body(Body::Empty)?;
+            .body(String::new())?;
         return Ok(resp);
     }
 
     let discord_interaction: DiscordInteraction = serde_json::from_str(request_body.as_str())?;
     tracing::info!("discord_interaction={:?}", discord_interaction);
 
-    let response = get_response(&discord_interaction, binahbot_env).await?;
+    let response = get_response(discord_interaction, binahbot_env).await?;
     tracing::info!("Returning response={:?}", response);
 
     let resp = Response::builder()
         .status(200)
         .header("content-type", "application/json")
-        .body(Body::Text(serde_json::to_string(&response).unwrap()))?;
-
-    tracing::debug!("Rust function finished invocation");
+        .body(serde_json::to_string(&response).unwrap())?;
     Ok(resp)
 }
 
@@ -96,13 +147,28 @@ async fn main() -> Result<(), lambda_http::Error> {
         .with_target(false)
         .init();
 
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.first().is_some_and(|x| x == REGISTER_COMMANDS_COMMAND) {
+        return commands::run(&args[1..], &LOCALES).await;
+    }
+    if args.first().is_some_and(|x| x == REINDEX_DECKS_COMMAND) {
+        let config = aws_config::load_from_env().await;
+        let (deck_repository, _, _) = build_storage(&config);
+        let count = reindex_decks(deck_repository.as_ref()).await?;
+        println!("Reindexed {} decks", count);
+        return Ok(());
+    }
+
+    // endpoints can be pointed elsewhere, e.g. DynamoDB Local, with AWS_ENDPOINT_URL_DYNAMODB
     let config = aws_config::load_from_env().await;
-    let asm = aws_sdk_secretsmanager::Client::new(&config);
-    let ddb = aws_sdk_dynamodb::Client::new(&config);
-    let lambda = aws_sdk_lambda::Client::new(&config);
     let http = reqwest::Client::new();
-    let discord_secrets =
-        get_discord_secrets(&asm, &env::var("SECRETS_ID").expect("no SECRETS_ID")).await;
+    let discord_secrets = load_discord_secrets(&config).await;
+    // without a thumbnail generator, decks are saved without rendering their thumbnails
+    let thumbnail_lambda_name = env::var("THUMBNAIL_LAMBDA_ARN").ok();
+    let card_directory = thumbnail_lambda_name
+        .as_ref()
+        .map(|_| env::var("CARD_DIRECTORY").expect("no CARD_DIRECTORY"));
+    let (deck_repository, interaction_store, config_repository) = build_storage(&config);
 
     let binahbot_env = BinahBotEnvironment {
         discord_secrets,
@@ -137,25 +203,95 @@ async fn main() -> Result<(), lambda_http::Error> {
             risk_aleph: env::var("RISK_ALEPH_EMOJI_ID").ok(),
         },
         locales: &LOCALES,
-        ddb_table_name: env::var("DECK_REPOSITORY_NAME").expect("no DECK_REPOSITORY_NAME"),
-        ddb_interaction_ttl_table_name: env::var("INTERACTION_TTL_NAME")
-            .expect("no INTERACTION_TTL_NAME"),
-        thumbnail_lambda_name: env::var("THUMBNAIL_LAMBDA_ARN").expect("no THUMBNAIL_LAMBDA_ARN"),
+        lambda_client: thumbnail_lambda_name
+            .is_some()
+            .then(|| aws_sdk_lambda::Client::new(&config)),
+        thumbnail_lambda_name: thumbnail_lambda_name.unwrap_or_default(),
+        card_directory: card_directory.unwrap_or_default(),
         spoiler_config: &SPOILER_CONFIG,
-        ddb_client: Some(ddb),
-        lambda_client: Some(lambda),
+        deck_repository,
+        interaction_store,
+        config_repository,
+        progress_cache: ProgressCache::default(),
         reqwest_client: Some(http),
     };
-    let binahbot_env_ref = &binahbot_env;
 
     tracing::debug!("Rust function setup complete");
 
+    serve(binahbot_env).await
+}
+
+#[cfg(not(feature = "standalone"))]
+async fn serve(binahbot_env: BinahBotEnvironment) -> Result<(), lambda_http::Error> {
+    let binahbot_env_ref = &binahbot_env;
+
     run(service_fn(move |event: Request| {
         function_handler(event, binahbot_env_ref)
     }))
     .await
 }
 
+// STORAGE_BACKEND picks where decks, interaction state and server configs live; DynamoDB
+// unless told otherwise
+fn build_storage(
+    config: &aws_config::SdkConfig,
+) -> (
+    Box<dyn DeckRepository>,
+    Box<dyn InteractionStore>,
+    Box<dyn ConfigRepository>,
+) {
+    match env::var("STORAGE_BACKEND").as_deref() {
+        Ok("memory") => (
+            Box::new(InMemoryDeckRepository::default()),
+            Box::new(InMemoryInteractionStore::default()),
+            Box::new(InMemoryConfigRepository::default()),
+        ),
+        #[cfg(feature = "sqlite")]
+        Ok("sqlite") => {
+            let path = env::var("SQLITE_PATH").unwrap_or("binah_bot.db".to_string());
+            let connection = open_connection(&path).expect("could not open SQLITE_PATH");
+            (
+                Box::new(
+                    SqliteDeckRepository::new(connection.clone())
+                        .expect("could not set up SQLITE_PATH"),
+                ),
+                Box::new(
+                    SqliteInteractionStore::new(connection.clone())
+                        .expect("could not set up SQLITE_PATH"),
+                ),
+                Box::new(
+                    SqliteConfigRepository::new(connection).expect("could not set up SQLITE_PATH"),
+                ),
+            )
+        }
+        Ok("dynamodb") | Err(_) => {
+            let ddb = aws_sdk_dynamodb::Client::new(config);
+            (
+                Box::new(DynamoDbDeckRepository {
+                    client: ddb.clone(),
+                    deck_table_name: env::var("DECK_REPOSITORY_NAME")
+                        .expect("no DECK_REPOSITORY_NAME"),
+                    deck_history_table_name: env::var("DECK_HISTORY_NAME")
+                        .expect("no DECK_HISTORY_NAME"),
+                    deck_index_table_name: env::var("DECK_INDEX_NAME").expect("no DECK_INDEX_NAME"),
+                    deck_votes_table_name: env::var("DECK_VOTES_NAME").expect("no DECK_VOTES_NAME"),
+                    guild_collection_table_name: env::var("GUILD_COLLECTION_NAME")
+                        .expect("no GUILD_COLLECTION_NAME"),
+                }),
+                Box::new(DynamoDbInteractionStore {
+                    client: ddb.clone(),
+                    table_name: env::var("INTERACTION_TTL_NAME").expect("no INTERACTION_TTL_NAME"),
+                }),
+                Box::new(DynamoDbConfigRepository {
+                    client: ddb,
+                    table_name: env::var("GUILD_CONFIG_NAME").expect("no GUILD_CONFIG_NAME"),
+                }),
+            )
+        }
+        Ok(x) => panic!("unsupported STORAGE_BACKEND {}", x),
+    }
+}
+
 fn get_header(header_map: &HeaderMap, header_key: &str) -> String {
     header_map
         .get(header_key)
@@ -182,9 +318,24 @@ fn validate_headers(
 
 #[cfg(test)]
 pub mod test_utils {
+    use crate::guild_config::ProgressCache;
     use crate::models::binahbot::BinahBotEnvironment;
     use crate::models::binahbot::DiscordSecrets;
     use crate::models::binahbot::Emojis;
+    use crate::models::deck::Deck;
+    use crate::models::deck::DeckData;
+    use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordGuildMember;
+    use crate::models::discord::DiscordInteraction;
+    use crate::models::discord::DiscordInteractionData;
+    use crate::models::discord::DiscordInteractionOptionValue;
+    use crate::models::discord::DiscordInteractionOptions;
+    use crate::models::discord::DiscordInteractionType;
+    use crate::models::discord::DiscordUser;
+    use crate::repository::memory::InMemoryConfigRepository;
+    use crate::repository::memory::InMemoryDeckRepository;
+    use crate::repository::memory::InMemoryInteractionStore;
     use crate::LOCALES;
     use crate::SPOILER_CONFIG;
 
@@ -227,13 +378,127 @@ pub mod test_utils {
                 risk_aleph: None,
             },
             locales: &LOCALES,
-            ddb_table_name: "table_name".to_string(),
-            ddb_interaction_ttl_table_name: "interaction_ttl_table_name".to_string(),
             thumbnail_lambda_name: "thumb_lambda_name".to_string(),
+            card_directory: "card_images".to_string(),
             spoiler_config: &SPOILER_CONFIG,
-            ddb_client: None,
+            deck_repository: Box::new(InMemoryDeckRepository::default()),
+            interaction_store: Box::new(InMemoryInteractionStore::default()),
+            config_repository: Box::new(InMemoryConfigRepository::default()),
+            progress_cache: ProgressCache::default(),
             lambda_client: None,
             reqwest_client: None,
         }
     }
+
+    // A first version deck without tags, editors or a fork, for tests to override as needed
+    pub fn build_deck(author_id: &str, name: &str, deck_data: DeckData) -> Deck {
+        Deck {
+            name: name.to_string(),
+            author_id: author_id.to_string(),
+            author_name: "username".to_string(),
+            description: None,
+            deck_data,
+            tiph_deck: None,
+            version: 1,
+            tags: Vec::new(),
+            forked_from: None,
+            owner_id: None,
+            editors: Vec::new(),
+        }
+    }
+
+    // A slash command sent by "user" in a server channel, with the given member permissions
+    // and string options
+    pub fn build_guild_command_interaction(
+        command_name: &str,
+        permissions: &str,
+        options: Vec<(&str, &str)>,
+    ) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommand,
+            data: Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: command_name.to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
+                    options: Some(
+                        options
+                            .into_iter()
+                            .map(|(name, value)| DiscordInteractionOptions {
+                                name: name.to_string(),
+                                name_localizations: None,
+                                value: DiscordInteractionOptionValue::String(value.to_string()),
+                                focused: None,
+                            })
+                            .collect(),
+                    ),
+                    target_id: None,
+                    resolved: None,
+                },
+            )),
+            channel_id: Some("channel".to_string()),
+            guild_id: Some("guild".to_string()),
+            token: "token".to_string(),
+            locale: Some("en-US".to_string()),
+            guild_locale: None,
+            user: None,
+            member: Some(DiscordGuildMember {
+                user: Some(DiscordUser {
+                    id: "user".to_string(),
+                    username: "user".to_string(),
+                    avatar: None,
+                }),
+                permissions: Some(permissions.to_string()),
+            }),
+            message: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::Signer;
+    use ed25519_dalek::SigningKey;
+    use http::HeaderMap;
+
+    use crate::handle_interaction;
+    use crate::test_utils::build_mocked_binahbot_env;
+    use crate::SIGNATURE_HEADER;
+    use crate::TIMESTAMP_HEADER;
+
+    static PING_BODY: &str = r#"{"id":"1","application_id":"app_id","type":1,"token":"token"}"#;
+
+    fn build_headers(signing_key: &SigningKey, timestamp: &str, body: &str) -> HeaderMap {
+        let signature = signing_key.sign(format!("{}{}", timestamp, body).as_bytes());
+        let mut headers = HeaderMap::new();
+        headers.insert(TIMESTAMP_HEADER, timestamp.parse().unwrap());
+        headers.insert(
+            SIGNATURE_HEADER,
+            hex::encode(signature.to_bytes()).parse().unwrap(),
+        );
+        headers
+    }
+
+    #[tokio::test]
+    async fn sanity_handle_interaction() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let mut env = build_mocked_binahbot_env();
+        env.discord_secrets.public_key = hex::encode(signing_key.verifying_key().to_bytes());
+
+        let headers = build_headers(&signing_key, "1700000000", PING_BODY);
+        let response = handle_interaction(&headers, PING_BODY.to_string(), &env)
+            .await
+            .unwrap();
+        assert_eq!(200, response.status());
+        assert_eq!(r#"{"type":1}"#, response.body());
+
+        let tampered_body = PING_BODY.replace("token", "other");
+        let response = handle_interaction(&headers, tampered_body, &env)
+            .await
+            .unwrap();
+        assert_eq!(401, response.status());
+        assert!(response.body().is_empty());
+    }
 }
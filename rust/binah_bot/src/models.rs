@@ -1,3 +1,4 @@
 pub mod binahbot;
+pub mod config;
 pub mod deck;
 pub mod discord;
@@ -9,6 +9,11 @@ use ruina::ruina_common::game_objects::common::Rarity;
 use ruina::ruina_common::localizations::common::Locale as RuinaLocale;
 use unic_langid::LanguageIdentifier;
 
+use crate::guild_config::ProgressCache;
+use crate::repository::ConfigRepository;
+use crate::repository::DeckRepository;
+use crate::repository::InteractionStore;
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DiscordSecrets {
@@ -53,16 +58,19 @@ pub struct BinahBotEnvironment {
     pub s3_bucket_name: String,
     pub emojis: Emojis,
     pub locales: &'static StaticLoader,
-    pub ddb_table_name: String,
-    pub ddb_interaction_ttl_table_name: String,
     pub thumbnail_lambda_name: String,
+    // where the thumbnail lambda writes rendered cards in the bucket
+    pub card_directory: String,
     pub spoiler_config: &'static phf::Map<&'static str, Chapter>,
-    pub ddb_client: Option<aws_sdk_dynamodb::Client>,
+    pub deck_repository: Box<dyn DeckRepository>,
+    pub interaction_store: Box<dyn InteractionStore>,
+    pub config_repository: Box<dyn ConfigRepository>,
+    pub progress_cache: ProgressCache,
     pub lambda_client: Option<aws_sdk_lambda::Client>,
     pub reqwest_client: Option<reqwest::Client>,
 }
 
-#[derive(Clone, Debug, strum::Display, strum_macros::EnumString)]
+#[derive(Clone, Debug, PartialEq, strum::Display, strum_macros::EnumString)]
 pub enum BinahBotLocale {
     #[strum(serialize = "en-US")]
     EnglishUS,
@@ -97,7 +105,7 @@ pub enum DiscordEmbedColors {
     Aleph = 0xfe0000,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct InteractionTtl {
     pub interaction_id: String,
     pub ttl: u64,
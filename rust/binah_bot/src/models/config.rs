@@ -0,0 +1,87 @@
+use ruina::ruina_common::game_objects::common::Chapter;
+
+use crate::models::binahbot::BinahBotLocale;
+
+// Settings a server changes at runtime, for itself or for one of its channels. Anything
+// left unset defers to the wider scope
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GuildConfig {
+    pub max_spoiler_chapter: Option<Chapter>,
+    pub default_locale: Option<BinahBotLocale>,
+    pub default_private: Option<bool>,
+    // command names; every command is enabled unless listed here
+    pub disabled_commands: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigScope {
+    Guild(String),
+    Channel(String),
+    // a user's own settings, which follow them everywhere. Only `max_spoiler_chapter` is used,
+    // as their progress through the game
+    User(String),
+}
+
+impl ConfigScope {
+    // all scopes share a table, so their ids are kept apart by prefix
+    pub fn key(&self) -> String {
+        match self {
+            ConfigScope::Guild(x) => format!("guild#{}", x),
+            ConfigScope::Channel(x) => format!("channel#{}", x),
+            ConfigScope::User(x) => format!("user#{}", x),
+        }
+    }
+}
+
+impl GuildConfig {
+    // fills in whatever this config leaves unset from `fallback`. Commands disabled in
+    // either are disabled in the result
+    pub fn or(self, fallback: GuildConfig) -> GuildConfig {
+        let mut disabled_commands = self.disabled_commands;
+        disabled_commands.extend(
+            fallback
+                .disabled_commands
+                .into_iter()
+                .filter(|x| !disabled_commands.contains(x))
+                .collect::<Vec<_>>(),
+        );
+
+        GuildConfig {
+            max_spoiler_chapter: self.max_spoiler_chapter.or(fallback.max_spoiler_chapter),
+            default_locale: self.default_locale.or(fallback.default_locale),
+            default_private: self.default_private.or(fallback.default_private),
+            disabled_commands,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanity_or() {
+        let channel = GuildConfig {
+            max_spoiler_chapter: Some(Chapter::UrbanLegend),
+            default_locale: None,
+            default_private: None,
+            disabled_commands: vec!["lc".to_string()],
+        };
+        let guild = GuildConfig {
+            max_spoiler_chapter: Some(Chapter::StarOfTheCity),
+            default_locale: Some(BinahBotLocale::Korean),
+            default_private: None,
+            disabled_commands: vec!["lc".to_string(), "rollcalc".to_string()],
+        };
+
+        assert_eq!(
+            GuildConfig {
+                max_spoiler_chapter: Some(Chapter::UrbanLegend),
+                default_locale: Some(BinahBotLocale::Korean),
+                default_private: None,
+                disabled_commands: vec!["lc".to_string(), "rollcalc".to_string()],
+            },
+            channel.or(guild)
+        );
+    }
+}
@@ -1,17 +1,17 @@
 use serde::Deserialize;
 use serde::Serialize;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct DeckData {
     pub keypage_id: Option<String>,
     pub passive_ids: Vec<String>,
     pub combat_page_ids: [Option<String>; 9],
 }
 
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct TiphDeck(pub String, pub i32);
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Deck {
     pub name: String,
     pub author_id: String,
@@ -19,11 +19,83 @@ pub struct Deck {
     pub description: Option<String>,
     pub deck_data: DeckData,
     pub tiph_deck: Option<TiphDeck>,
+    // decks saved before versioning was introduced are treated as the first version
+    #[serde(default = "first_deck_version")]
+    pub version: u32,
+    // lowercased, user-assigned labels such as "bleed" or "urban nightmare clear"
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub forked_from: Option<DeckOrigin>,
+    // set once ownership is transferred away from the original author
+    #[serde(default)]
+    pub owner_id: Option<String>,
+    // user ids the owner has granted edit rights to
+    #[serde(default)]
+    pub editors: Vec<String>,
 }
 
-#[derive(Debug)]
+fn first_deck_version() -> u32 {
+    1
+}
+
+// the deck and version a forked deck was copied from
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeckOrigin {
+    pub author_id: String,
+    pub deck_name: String,
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeckVersion {
+    pub deck: Deck,
+    // snapshots of decks created before history was tracked have no known timestamp
+    pub timestamp: Option<u64>,
+    // snapshots taken before co-editing was introduced were always saved by the author
+    pub editor_id: Option<String>,
+}
+
+#[derive(Debug, Clone)]
 pub struct DeckMetadata {
     pub name: String,
     pub author_id: String,
     pub author_name: String,
 }
+
+impl From<&Deck> for DeckMetadata {
+    fn from(value: &Deck) -> Self {
+        DeckMetadata {
+            name: value.name.clone(),
+            author_id: value.author_id.clone(),
+            author_name: value.author_name.clone(),
+        }
+    }
+}
+
+// one searchable attribute of a deck, e.g. a tag or a contained page
+#[derive(Debug)]
+pub struct DeckIndexEntry {
+    pub index_key: String,
+    pub metadata: DeckMetadata,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DeckVote {
+    pub upvote: bool,
+    pub favorite: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeckVoteTotals {
+    pub metadata: DeckMetadata,
+    pub upvotes: i64,
+    pub favorites: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PendingDeck {
+    pub interaction_id: String,
+    pub ttl: u64,
+    pub deck: Deck,
+}
@@ -64,6 +64,7 @@ pub struct DiscordInteraction {
     pub r#type: DiscordInteractionType,
     pub data: Option<DiscordInteractionData>,
     pub channel_id: Option<String>,
+    pub guild_id: Option<String>,
     pub member: Option<DiscordGuildMember>,
     pub user: Option<DiscordUser>,
     pub token: String,
@@ -101,7 +102,106 @@ pub enum DiscordInteractionData {
 pub struct DiscordApplicationCommandInteractionData {
     pub id: String,
     pub name: String,
+    pub r#type: Option<DiscordApplicationCommandType>,
     pub options: Option<Vec<DiscordInteractionOptions>>,
+    // the message or user a context menu command was invoked on
+    pub target_id: Option<String>,
+    pub resolved: Option<DiscordResolvedData>,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+#[repr(i32)]
+pub enum DiscordApplicationCommandType {
+    ChatInput = 1,
+    User = 2,
+    Message = 3,
+}
+
+/**
+ * Command definition sent to Discord when registering the bot's commands.
+ *
+ * See also: https://discord.com/developers/docs/interactions/application-commands#application-command-object
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DiscordApplicationCommand {
+    pub name: String,
+    pub name_localizations: Option<HashMap<String, String>>,
+    pub description: String,
+    pub description_localizations: Option<HashMap<String, String>>,
+    pub r#type: DiscordApplicationCommandType,
+    pub integration_types: Vec<DiscordApplicationIntegrationType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<Vec<DiscordInteractionContextType>>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub options: Vec<DiscordApplicationCommandOption>,
+    // permission bitfield serialized as a string, like `DiscordGuildMember::permissions`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_member_permissions: Option<String>,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+#[repr(i32)]
+pub enum DiscordApplicationIntegrationType {
+    GuildInstall = 0,
+    UserInstall = 1,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+#[repr(i32)]
+pub enum DiscordInteractionContextType {
+    Guild = 0,
+    #[allow(dead_code)]
+    BotDm = 1,
+    #[allow(dead_code)]
+    PrivateChannel = 2,
+}
+
+// Discord rejects nulls for some of these, so unset fields are left out entirely
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DiscordApplicationCommandOption {
+    pub r#type: DiscordApplicationCommandOptionType,
+    pub name: String,
+    pub name_localizations: Option<HashMap<String, String>>,
+    pub description: String,
+    pub description_localizations: Option<HashMap<String, String>>,
+    pub required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autocomplete: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_value: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub choices: Option<Vec<DiscordApplicationCommandOptionChoice>>,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Clone, Copy, Debug)]
+#[repr(i32)]
+pub enum DiscordApplicationCommandOptionType {
+    String = 3,
+    Integer = 4,
+    Boolean = 5,
+    User = 6,
+    Channel = 7,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DiscordApplicationCommandOptionChoice {
+    pub name: String,
+    pub value: String,
+}
+
+/**
+ * Full objects for the ids referenced by an interaction, keyed by id.
+ *
+ * See also: https://discord.com/developers/docs/interactions/receiving-and-responding#interaction-object-resolved-data-structure
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DiscordResolvedData {
+    pub users: Option<HashMap<String, DiscordUser>>,
+    pub messages: Option<HashMap<String, DiscordMessage>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -229,6 +329,8 @@ pub struct DiscordUser {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DiscordGuildMember {
     pub user: Option<DiscordUser>,
+    // bitfield of the member's permissions in the channel, serialized as a string
+    pub permissions: Option<String>,
 }
 
 #[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug)]
@@ -253,8 +355,19 @@ pub enum DiscordMessageFlag {
     SuppressNotifications = 4096,
 }
 
+/**
+ * See also: https://discord.com/developers/docs/topics/permissions#permissions-bitwise-permission-flags
+ */
+#[repr(u64)]
+pub enum DiscordPermission {
+    ManageChannels = 1 << 4,
+    ManageGuild = 1 << 5,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DiscordMessage {
+    pub content: Option<String>,
+    pub author: Option<DiscordUser>,
     pub interaction_metadata: Option<DiscordInteractionMetadata>,
 }
 
@@ -0,0 +1,175 @@
+use std::str::FromStr;
+
+use ruina::ruina_common::game_objects::common::Chapter;
+use unic_langid::LanguageIdentifier;
+
+use crate::error::BinahBotError;
+use crate::guild_config::get_user_progress;
+use crate::macros::cast_enum_variant;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::config::ConfigScope;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionOptionValue;
+use crate::models::discord::MessageResponse;
+use crate::spoilers_command::build_chapter_limit_response;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_data;
+use crate::utils::get_interaction_user;
+use crate::utils::get_option_value;
+
+// Shows or changes the user's progress through the game. Pages and decks from later chapters
+// are hidden from them everywhere, including DMs and private responses
+pub async fn progress_command(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    // every option is optional, and Discord leaves them out entirely when none are given
+    let command_args = get_command_data(interaction)?
+        .options
+        .as_deref()
+        .unwrap_or_default();
+
+    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+
+    let Some(chapter_option) = get_option_value("chapter", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
+    else {
+        let progress = get_user_progress(interaction, env).await;
+        return Ok(build_chapter_limit_response(
+            "progress_show",
+            progress.as_ref(),
+            &lang_id,
+            env,
+        ));
+    };
+
+    // the only other choice is a reset, which clears the progress
+    let progress = Chapter::from_str(chapter_option).ok();
+
+    let user_id = &get_interaction_user(interaction)?.id;
+    let scope = ConfigScope::User(user_id.clone());
+    let result = match env.config_repository.get_config(&scope).await {
+        Ok(mut config) => {
+            config.max_spoiler_chapter = progress.clone();
+            env.config_repository.put_config(&scope, &config).await
+        }
+        Err(e) => Err(e),
+    };
+    env.progress_cache.invalidate(user_id);
+    if result.is_err() {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "generic_error_message",
+            env,
+        ));
+    }
+
+    Ok(build_chapter_limit_response(
+        "progress_updated",
+        progress.as_ref(),
+        &lang_id,
+        env,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::RESET_CHOICE;
+    use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordInteractionData;
+    use crate::models::discord::DiscordInteractionOptions;
+    use crate::models::discord::DiscordInteractionType;
+    use crate::models::discord::DiscordUser;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn sanity_progress_command() {
+        let env = build_mocked_binahbot_env();
+        let lang_id = LanguageIdentifier::from(&get_binahbot_locale(&build_interaction(None)));
+
+        let response = progress_command(&build_interaction(Some("UrbanPlague")), &env)
+            .await
+            .unwrap();
+        assert_eq!(
+            format!(
+                "{:?}",
+                build_chapter_limit_response(
+                    "progress_updated",
+                    Some(&Chapter::UrbanPlague),
+                    &lang_id,
+                    &env
+                )
+            ),
+            format!("{:?}", response)
+        );
+        assert_eq!(
+            Some(Chapter::UrbanPlague),
+            get_user_progress(&build_interaction(None), &env).await
+        );
+
+        let response = progress_command(&build_interaction(None), &env)
+            .await
+            .unwrap();
+        assert_eq!(
+            format!(
+                "{:?}",
+                build_chapter_limit_response(
+                    "progress_show",
+                    Some(&Chapter::UrbanPlague),
+                    &lang_id,
+                    &env
+                )
+            ),
+            format!("{:?}", response)
+        );
+
+        progress_command(&build_interaction(Some(RESET_CHOICE)), &env)
+            .await
+            .unwrap();
+        assert_eq!(
+            None,
+            get_user_progress(&build_interaction(None), &env).await
+        );
+    }
+
+    fn build_interaction(chapter: Option<&str>) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommand,
+            data: Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: "progress".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
+                    options: chapter.map(|x| {
+                        vec![DiscordInteractionOptions {
+                            name: "chapter".to_string(),
+                            name_localizations: None,
+                            value: DiscordInteractionOptionValue::String(x.to_string()),
+                            focused: None,
+                        }]
+                    }),
+                    target_id: None,
+                    resolved: None,
+                },
+            )),
+            channel_id: None,
+            guild_id: None,
+            token: "token".to_string(),
+            locale: Some("en-US".to_string()),
+            guild_locale: None,
+            user: Some(DiscordUser {
+                id: "user".to_string(),
+                username: "user".to_string(),
+                avatar: None,
+            }),
+            member: None,
+            message: None,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+pub mod dynamodb;
+pub mod memory;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::models::binahbot::InteractionTtl;
+use crate::models::config::ConfigScope;
+use crate::models::config::GuildConfig;
+use crate::models::deck::Deck;
+use crate::models::deck::DeckIndexEntry;
+use crate::models::deck::DeckMetadata;
+use crate::models::deck::DeckVersion;
+use crate::models::deck::DeckVote;
+use crate::models::deck::DeckVoteTotals;
+use crate::models::deck::PendingDeck;
+
+// Decks and everything stored alongside them: history, the search index, votes
+// and guild collections. Decks are keyed by author id and deck name
+#[async_trait]
+pub trait DeckRepository: Send + Sync {
+    async fn get_deck(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<Deck, Box<dyn Error + Send + Sync>>;
+    // without a previous version, fails if the deck already exists. With one, fails unless the
+    // stored deck is still at that version, so concurrent edits can't claim the same version
+    async fn put_deck(
+        &self,
+        deck: &Deck,
+        previous_version: Option<u32>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn list_decks(
+        &self,
+        author: Option<&str>,
+        keypage_id: Option<&str>,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>>;
+    async fn delete_deck(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    // fails if the version already exists, unless overwriting
+    async fn put_deck_version(
+        &self,
+        deck_version: &DeckVersion,
+        overwrite: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn list_deck_versions(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<Vec<DeckVersion>, Box<dyn Error + Send + Sync>>;
+    async fn delete_deck_versions(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    // replaces every index key of the deck
+    async fn put_deck_index(
+        &self,
+        deck: &Deck,
+        index_keys: &[String],
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    // adds a single index key, leaving the deck's other keys alone
+    async fn put_deck_index_entry(
+        &self,
+        entry: &DeckIndexEntry,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn delete_deck_index(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn search_deck_index(
+        &self,
+        index_key: &str,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>>;
+
+    // users who never voted have a default, empty vote
+    async fn get_deck_vote(
+        &self,
+        name: &str,
+        author: &str,
+        user_id: &str,
+    ) -> Result<DeckVote, Box<dyn Error + Send + Sync>>;
+    // fails unless the stored vote is still the previous vote, so a toggle is only counted once
+    async fn put_deck_vote(
+        &self,
+        name: &str,
+        author: &str,
+        user_id: &str,
+        vote: &DeckVote,
+        previous_vote: &DeckVote,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    // adds the deltas to the deck's totals and returns the new totals
+    async fn update_deck_vote_totals(
+        &self,
+        metadata: &DeckMetadata,
+        upvote_delta: i64,
+        favorite_delta: i64,
+    ) -> Result<DeckVoteTotals, Box<dyn Error + Send + Sync>>;
+    async fn list_deck_vote_totals(
+        &self,
+    ) -> Result<Vec<DeckVoteTotals>, Box<dyn Error + Send + Sync>>;
+    // removes both the individual votes and the totals
+    async fn delete_deck_votes(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    async fn put_guild_collection_deck(
+        &self,
+        guild_id: &str,
+        metadata: &DeckMetadata,
+        added_by: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn delete_guild_collection_deck(
+        &self,
+        guild_id: &str,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn list_guild_collection(
+        &self,
+        guild_id: &str,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>>;
+    async fn delete_deck_from_guild_collections(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+// Short-lived state tied to an interaction, i.e. tokens for deleting responses
+// and decks waiting on the user to confirm an import
+#[async_trait]
+pub trait InteractionStore: Send + Sync {
+    async fn put_interaction_token(
+        &self,
+        interaction_ttl: &InteractionTtl,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn get_interaction_token(
+        &self,
+        interaction_id: &str,
+    ) -> Result<InteractionTtl, Box<dyn Error + Send + Sync>>;
+    async fn put_pending_deck(
+        &self,
+        pending_deck: &PendingDeck,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn get_pending_deck(
+        &self,
+        interaction_id: &str,
+    ) -> Result<PendingDeck, Box<dyn Error + Send + Sync>>;
+}
+
+// Settings servers change at runtime, see `GuildConfig`
+#[async_trait]
+pub trait ConfigRepository: Send + Sync {
+    // scopes that were never configured have a default, empty config
+    async fn get_config(
+        &self,
+        scope: &ConfigScope,
+    ) -> Result<GuildConfig, Box<dyn Error + Send + Sync>>;
+    async fn put_config(
+        &self,
+        scope: &ConfigScope,
+        config: &GuildConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
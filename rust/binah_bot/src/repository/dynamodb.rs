@@ -0,0 +1,300 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::ddb;
+use crate::models::binahbot::InteractionTtl;
+use crate::models::config::ConfigScope;
+use crate::models::config::GuildConfig;
+use crate::models::deck::Deck;
+use crate::models::deck::DeckIndexEntry;
+use crate::models::deck::DeckMetadata;
+use crate::models::deck::DeckVersion;
+use crate::models::deck::DeckVote;
+use crate::models::deck::DeckVoteTotals;
+use crate::models::deck::PendingDeck;
+use crate::repository::ConfigRepository;
+use crate::repository::DeckRepository;
+use crate::repository::InteractionStore;
+
+pub struct DynamoDbDeckRepository {
+    pub client: aws_sdk_dynamodb::Client,
+    pub deck_table_name: String,
+    pub deck_history_table_name: String,
+    pub deck_index_table_name: String,
+    pub deck_votes_table_name: String,
+    pub guild_collection_table_name: String,
+}
+
+#[async_trait]
+impl DeckRepository for DynamoDbDeckRepository {
+    async fn get_deck(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<Deck, Box<dyn Error + Send + Sync>> {
+        ddb::get_deck(&self.client, &self.deck_table_name, name, author).await
+    }
+
+    async fn put_deck(
+        &self,
+        deck: &Deck,
+        previous_version: Option<u32>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::put_deck(&self.client, &self.deck_table_name, deck, previous_version).await
+    }
+
+    async fn list_decks(
+        &self,
+        author: Option<&str>,
+        keypage_id: Option<&str>,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+        ddb::list_decks(&self.client, &self.deck_table_name, author, keypage_id).await
+    }
+
+    async fn delete_deck(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::delete_deck(&self.client, &self.deck_table_name, name, author).await
+    }
+
+    async fn put_deck_version(
+        &self,
+        deck_version: &DeckVersion,
+        overwrite: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::put_deck_version(
+            &self.client,
+            &self.deck_history_table_name,
+            deck_version,
+            overwrite,
+        )
+        .await
+    }
+
+    async fn list_deck_versions(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<Vec<DeckVersion>, Box<dyn Error + Send + Sync>> {
+        ddb::list_deck_versions(&self.client, &self.deck_history_table_name, name, author).await
+    }
+
+    async fn delete_deck_versions(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::delete_deck_versions(&self.client, &self.deck_history_table_name, name, author).await
+    }
+
+    async fn put_deck_index(
+        &self,
+        deck: &Deck,
+        index_keys: &[String],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::put_deck_index(&self.client, &self.deck_index_table_name, deck, index_keys).await
+    }
+
+    async fn put_deck_index_entry(
+        &self,
+        entry: &DeckIndexEntry,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::put_deck_index_entry(&self.client, &self.deck_index_table_name, entry).await
+    }
+
+    async fn delete_deck_index(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::delete_deck_index(&self.client, &self.deck_index_table_name, name, author).await
+    }
+
+    async fn search_deck_index(
+        &self,
+        index_key: &str,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+        ddb::search_deck_index(&self.client, &self.deck_index_table_name, index_key).await
+    }
+
+    async fn get_deck_vote(
+        &self,
+        name: &str,
+        author: &str,
+        user_id: &str,
+    ) -> Result<DeckVote, Box<dyn Error + Send + Sync>> {
+        ddb::get_deck_vote(
+            &self.client,
+            &self.deck_votes_table_name,
+            name,
+            author,
+            user_id,
+        )
+        .await
+    }
+
+    async fn put_deck_vote(
+        &self,
+        name: &str,
+        author: &str,
+        user_id: &str,
+        vote: &DeckVote,
+        previous_vote: &DeckVote,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::put_deck_vote(
+            &self.client,
+            &self.deck_votes_table_name,
+            name,
+            author,
+            user_id,
+            vote,
+            previous_vote,
+        )
+        .await
+    }
+
+    async fn update_deck_vote_totals(
+        &self,
+        metadata: &DeckMetadata,
+        upvote_delta: i64,
+        favorite_delta: i64,
+    ) -> Result<DeckVoteTotals, Box<dyn Error + Send + Sync>> {
+        ddb::update_deck_vote_totals(
+            &self.client,
+            &self.deck_votes_table_name,
+            metadata,
+            upvote_delta,
+            favorite_delta,
+        )
+        .await
+    }
+
+    async fn list_deck_vote_totals(
+        &self,
+    ) -> Result<Vec<DeckVoteTotals>, Box<dyn Error + Send + Sync>> {
+        ddb::list_deck_vote_totals(&self.client, &self.deck_votes_table_name).await
+    }
+
+    async fn delete_deck_votes(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::delete_deck_votes(&self.client, &self.deck_votes_table_name, name, author).await
+    }
+
+    async fn put_guild_collection_deck(
+        &self,
+        guild_id: &str,
+        metadata: &DeckMetadata,
+        added_by: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::put_guild_collection_deck(
+            &self.client,
+            &self.guild_collection_table_name,
+            guild_id,
+            metadata,
+            added_by,
+        )
+        .await
+    }
+
+    async fn delete_guild_collection_deck(
+        &self,
+        guild_id: &str,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::delete_guild_collection_deck(
+            &self.client,
+            &self.guild_collection_table_name,
+            guild_id,
+            name,
+            author,
+        )
+        .await
+    }
+
+    async fn list_guild_collection(
+        &self,
+        guild_id: &str,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+        ddb::list_guild_collection(&self.client, &self.guild_collection_table_name, guild_id).await
+    }
+
+    async fn delete_deck_from_guild_collections(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::delete_deck_from_guild_collections(
+            &self.client,
+            &self.guild_collection_table_name,
+            name,
+            author,
+        )
+        .await
+    }
+}
+
+pub struct DynamoDbInteractionStore {
+    pub client: aws_sdk_dynamodb::Client,
+    pub table_name: String,
+}
+
+#[async_trait]
+impl InteractionStore for DynamoDbInteractionStore {
+    async fn put_interaction_token(
+        &self,
+        interaction_ttl: &InteractionTtl,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::put_interaction_token(&self.client, &self.table_name, interaction_ttl).await
+    }
+
+    async fn get_interaction_token(
+        &self,
+        interaction_id: &str,
+    ) -> Result<InteractionTtl, Box<dyn Error + Send + Sync>> {
+        ddb::get_interaction_token(&self.client, &self.table_name, interaction_id).await
+    }
+
+    async fn put_pending_deck(
+        &self,
+        pending_deck: &PendingDeck,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::put_pending_deck(&self.client, &self.table_name, pending_deck).await
+    }
+
+    async fn get_pending_deck(
+        &self,
+        interaction_id: &str,
+    ) -> Result<PendingDeck, Box<dyn Error + Send + Sync>> {
+        ddb::get_pending_deck(&self.client, &self.table_name, interaction_id).await
+    }
+}
+
+pub struct DynamoDbConfigRepository {
+    pub client: aws_sdk_dynamodb::Client,
+    pub table_name: String,
+}
+
+#[async_trait]
+impl ConfigRepository for DynamoDbConfigRepository {
+    async fn get_config(
+        &self,
+        scope: &ConfigScope,
+    ) -> Result<GuildConfig, Box<dyn Error + Send + Sync>> {
+        ddb::get_guild_config(&self.client, &self.table_name, scope).await
+    }
+
+    async fn put_config(
+        &self,
+        scope: &ConfigScope,
+        config: &GuildConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ddb::put_guild_config(&self.client, &self.table_name, scope, config).await
+    }
+}
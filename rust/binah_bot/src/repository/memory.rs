@@ -0,0 +1,602 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::models::binahbot::InteractionTtl;
+use crate::models::config::ConfigScope;
+use crate::models::config::GuildConfig;
+use crate::models::deck::Deck;
+use crate::models::deck::DeckIndexEntry;
+use crate::models::deck::DeckMetadata;
+use crate::models::deck::DeckVersion;
+use crate::models::deck::DeckVote;
+use crate::models::deck::DeckVoteTotals;
+use crate::models::deck::PendingDeck;
+use crate::repository::ConfigRepository;
+use crate::repository::DeckRepository;
+use crate::repository::InteractionStore;
+
+// (author, deck name)
+type DeckKey = (String, String);
+
+// Keeps everything in process memory, for tests and throwaway self-hosted instances.
+// Mirrors the DynamoDB conditions, e.g. missing items are errors and puts without
+// overwrite fail on existing items
+#[derive(Default)]
+pub struct InMemoryDeckRepository {
+    tables: Mutex<DeckTables>,
+}
+
+#[derive(Default)]
+struct DeckTables {
+    decks: BTreeMap<DeckKey, Deck>,
+    versions: BTreeMap<DeckKey, BTreeMap<u32, DeckVersion>>,
+    index: BTreeMap<DeckKey, BTreeMap<String, DeckMetadata>>,
+    votes: BTreeMap<DeckKey, BTreeMap<String, DeckVote>>,
+    vote_totals: BTreeMap<DeckKey, DeckVoteTotals>,
+    guild_collections: BTreeMap<String, BTreeMap<DeckKey, DeckMetadata>>,
+}
+
+fn get_deck_key(author: &str, name: &str) -> DeckKey {
+    (author.to_string(), name.to_string())
+}
+
+#[async_trait]
+impl DeckRepository for InMemoryDeckRepository {
+    async fn get_deck(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<Deck, Box<dyn Error + Send + Sync>> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables
+            .decks
+            .get(&get_deck_key(author, name))
+            .ok_or("could not get deck")?
+            .clone())
+    }
+
+    async fn put_deck(
+        &self,
+        deck: &Deck,
+        previous_version: Option<u32>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        let key = get_deck_key(&deck.author_id, &deck.name);
+        let stored_version = tables.decks.get(&key).map(|x| x.version);
+        if stored_version != previous_version {
+            return Err("deck was changed or already exists".into());
+        }
+        tables.decks.insert(key, deck.clone());
+        Ok(())
+    }
+
+    async fn list_decks(
+        &self,
+        author: Option<&str>,
+        keypage_id: Option<&str>,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables
+            .decks
+            .values()
+            .filter(|x| author.is_none_or(|y| x.author_id == y))
+            .filter(|x| keypage_id.is_none_or(|y| x.deck_data.keypage_id.as_deref() == Some(y)))
+            .map(DeckMetadata::from)
+            .collect())
+    }
+
+    async fn delete_deck(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        tables.decks.remove(&get_deck_key(author, name));
+        Ok(())
+    }
+
+    async fn put_deck_version(
+        &self,
+        deck_version: &DeckVersion,
+        overwrite: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        let versions = tables
+            .versions
+            .entry(get_deck_key(
+                &deck_version.deck.author_id,
+                &deck_version.deck.name,
+            ))
+            .or_default();
+        if !overwrite && versions.contains_key(&deck_version.deck.version) {
+            return Err("deck version already exists".into());
+        }
+        versions.insert(deck_version.deck.version, deck_version.clone());
+        Ok(())
+    }
+
+    async fn list_deck_versions(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<Vec<DeckVersion>, Box<dyn Error + Send + Sync>> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables
+            .versions
+            .get(&get_deck_key(author, name))
+            .map(|x| x.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn delete_deck_versions(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        tables.versions.remove(&get_deck_key(author, name));
+        Ok(())
+    }
+
+    async fn put_deck_index(
+        &self,
+        deck: &Deck,
+        index_keys: &[String],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        tables.index.insert(
+            get_deck_key(&deck.author_id, &deck.name),
+            index_keys
+                .iter()
+                .map(|x| (x.clone(), DeckMetadata::from(deck)))
+                .collect(),
+        );
+        Ok(())
+    }
+
+    async fn put_deck_index_entry(
+        &self,
+        entry: &DeckIndexEntry,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        tables
+            .index
+            .entry(get_deck_key(
+                &entry.metadata.author_id,
+                &entry.metadata.name,
+            ))
+            .or_default()
+            .insert(entry.index_key.clone(), entry.metadata.clone());
+        Ok(())
+    }
+
+    async fn delete_deck_index(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        tables.index.remove(&get_deck_key(author, name));
+        Ok(())
+    }
+
+    async fn search_deck_index(
+        &self,
+        index_key: &str,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables
+            .index
+            .values()
+            .filter_map(|x| x.get(index_key).cloned())
+            .collect())
+    }
+
+    async fn get_deck_vote(
+        &self,
+        name: &str,
+        author: &str,
+        user_id: &str,
+    ) -> Result<DeckVote, Box<dyn Error + Send + Sync>> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables
+            .votes
+            .get(&get_deck_key(author, name))
+            .and_then(|x| x.get(user_id).cloned())
+            .unwrap_or_default())
+    }
+
+    async fn put_deck_vote(
+        &self,
+        name: &str,
+        author: &str,
+        user_id: &str,
+        vote: &DeckVote,
+        previous_vote: &DeckVote,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        let votes = tables.votes.entry(get_deck_key(author, name)).or_default();
+        if votes.get(user_id).cloned().unwrap_or_default() != *previous_vote {
+            return Err("vote was changed".into());
+        }
+        votes.insert(user_id.to_string(), vote.clone());
+        Ok(())
+    }
+
+    async fn update_deck_vote_totals(
+        &self,
+        metadata: &DeckMetadata,
+        upvote_delta: i64,
+        favorite_delta: i64,
+    ) -> Result<DeckVoteTotals, Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        let totals = tables
+            .vote_totals
+            .entry(get_deck_key(&metadata.author_id, &metadata.name))
+            .or_insert_with(|| DeckVoteTotals {
+                metadata: metadata.clone(),
+                upvotes: 0,
+                favorites: 0,
+            });
+        totals.metadata = metadata.clone();
+        totals.upvotes += upvote_delta;
+        totals.favorites += favorite_delta;
+        Ok(totals.clone())
+    }
+
+    async fn list_deck_vote_totals(
+        &self,
+    ) -> Result<Vec<DeckVoteTotals>, Box<dyn Error + Send + Sync>> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables.vote_totals.values().cloned().collect())
+    }
+
+    async fn delete_deck_votes(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        let key = get_deck_key(author, name);
+        tables.votes.remove(&key);
+        tables.vote_totals.remove(&key);
+        Ok(())
+    }
+
+    async fn put_guild_collection_deck(
+        &self,
+        guild_id: &str,
+        metadata: &DeckMetadata,
+        _added_by: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        tables
+            .guild_collections
+            .entry(guild_id.to_string())
+            .or_default()
+            .insert(
+                get_deck_key(&metadata.author_id, &metadata.name),
+                metadata.clone(),
+            );
+        Ok(())
+    }
+
+    async fn delete_guild_collection_deck(
+        &self,
+        guild_id: &str,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        if let Some(collection) = tables.guild_collections.get_mut(guild_id) {
+            collection.remove(&get_deck_key(author, name));
+        }
+        Ok(())
+    }
+
+    async fn list_guild_collection(
+        &self,
+        guild_id: &str,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables
+            .guild_collections
+            .get(guild_id)
+            .map(|x| x.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn delete_deck_from_guild_collections(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut tables = self.tables.lock().unwrap();
+        let key = get_deck_key(author, name);
+        for collection in tables.guild_collections.values_mut() {
+            collection.remove(&key);
+        }
+        Ok(())
+    }
+}
+
+// Expired entries are never purged; nothing reads them once the ttl has passed
+#[derive(Default)]
+pub struct InMemoryInteractionStore {
+    interaction_tokens: Mutex<HashMap<String, InteractionTtl>>,
+    pending_decks: Mutex<HashMap<String, PendingDeck>>,
+}
+
+#[async_trait]
+impl InteractionStore for InMemoryInteractionStore {
+    async fn put_interaction_token(
+        &self,
+        interaction_ttl: &InteractionTtl,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.interaction_tokens.lock().unwrap().insert(
+            interaction_ttl.interaction_id.clone(),
+            interaction_ttl.clone(),
+        );
+        Ok(())
+    }
+
+    async fn get_interaction_token(
+        &self,
+        interaction_id: &str,
+    ) -> Result<InteractionTtl, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .interaction_tokens
+            .lock()
+            .unwrap()
+            .get(interaction_id)
+            .ok_or("could not get token")?
+            .clone())
+    }
+
+    async fn put_pending_deck(
+        &self,
+        pending_deck: &PendingDeck,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.pending_decks
+            .lock()
+            .unwrap()
+            .insert(pending_deck.interaction_id.clone(), pending_deck.clone());
+        Ok(())
+    }
+
+    async fn get_pending_deck(
+        &self,
+        interaction_id: &str,
+    ) -> Result<PendingDeck, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .pending_decks
+            .lock()
+            .unwrap()
+            .get(interaction_id)
+            .ok_or("could not get pending deck")?
+            .clone())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryConfigRepository {
+    configs: Mutex<HashMap<String, GuildConfig>>,
+}
+
+#[async_trait]
+impl ConfigRepository for InMemoryConfigRepository {
+    async fn get_config(
+        &self,
+        scope: &ConfigScope,
+    ) -> Result<GuildConfig, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .configs
+            .lock()
+            .unwrap()
+            .get(&scope.key())
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn put_config(
+        &self,
+        scope: &ConfigScope,
+        config: &GuildConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.configs
+            .lock()
+            .unwrap()
+            .insert(scope.key(), config.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::deck::DeckData;
+    use crate::test_utils::build_deck;
+
+    fn build_deck_data(keypage_id: &str) -> DeckData {
+        DeckData {
+            keypage_id: Some(keypage_id.to_string()),
+            passive_ids: Vec::new(),
+            combat_page_ids: std::array::from_fn(|_| None),
+        }
+    }
+
+    #[tokio::test]
+    async fn sanity_put_deck() {
+        let repository = InMemoryDeckRepository::default();
+        let deck = build_deck("1", "Turbo Nikolai", build_deck_data("250023"));
+
+        assert!(repository.get_deck("Turbo Nikolai", "1").await.is_err());
+        assert!(repository.put_deck(&deck, None).await.is_ok());
+        assert!(repository.put_deck(&deck, None).await.is_err());
+
+        let updated_deck = Deck {
+            version: 2,
+            ..deck.clone()
+        };
+        assert!(repository.put_deck(&updated_deck, Some(1)).await.is_ok());
+        // a concurrent edit that also read version 1
+        assert!(repository.put_deck(&updated_deck, Some(1)).await.is_err());
+        assert_eq!(
+            "250023",
+            repository
+                .get_deck("Turbo Nikolai", "1")
+                .await
+                .unwrap()
+                .deck_data
+                .keypage_id
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_list_decks() {
+        let repository = InMemoryDeckRepository::default();
+        for deck in [
+            build_deck("1", "Turbo Nikolai", build_deck_data("250023")),
+            build_deck("1", "Xiao", build_deck_data("250036")),
+            build_deck("2", "Roland", build_deck_data("250023")),
+        ] {
+            repository.put_deck(&deck, None).await.unwrap();
+        }
+
+        let names = |x: Vec<DeckMetadata>| x.into_iter().map(|y| y.name).collect::<Vec<_>>();
+        assert_eq!(3, repository.list_decks(None, None).await.unwrap().len());
+        assert_eq!(
+            vec!["Turbo Nikolai", "Xiao"],
+            names(repository.list_decks(Some("1"), None).await.unwrap())
+        );
+        assert_eq!(
+            vec!["Turbo Nikolai", "Roland"],
+            names(repository.list_decks(None, Some("250023")).await.unwrap())
+        );
+        assert_eq!(
+            vec!["Roland"],
+            names(
+                repository
+                    .list_decks(Some("2"), Some("250023"))
+                    .await
+                    .unwrap()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_put_deck_index() {
+        let repository = InMemoryDeckRepository::default();
+        let deck = build_deck("1", "Turbo Nikolai", build_deck_data("250023"));
+        repository
+            .put_deck_index(&deck, &["tag#burn".to_string(), "tag#fast".to_string()])
+            .await
+            .unwrap();
+        repository
+            .put_deck_index(&deck, &["tag#burn".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            1,
+            repository
+                .search_deck_index("tag#burn")
+                .await
+                .unwrap()
+                .len()
+        );
+        assert!(repository
+            .search_deck_index("tag#fast")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_update_deck_saved_before_versioning() {
+        let repository = InMemoryDeckRepository::default();
+        let mut deck_json =
+            serde_json::to_value(build_deck("1", "Turbo Nikolai", build_deck_data("250023")))
+                .unwrap();
+        deck_json.as_object_mut().unwrap().remove("version");
+        let deck: Deck = serde_json::from_value(deck_json).unwrap();
+        assert_eq!(1, deck.version);
+        repository.put_deck(&deck, None).await.unwrap();
+
+        let updated_deck = Deck { version: 2, ..deck };
+        assert!(repository.put_deck(&updated_deck, Some(1)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn sanity_update_deck_vote_totals() {
+        let repository = InMemoryDeckRepository::default();
+        let metadata =
+            DeckMetadata::from(&build_deck("1", "Turbo Nikolai", build_deck_data("250023")));
+        repository
+            .update_deck_vote_totals(&metadata, 1, 1)
+            .await
+            .unwrap();
+        let totals = repository
+            .update_deck_vote_totals(&metadata, 1, -1)
+            .await
+            .unwrap();
+
+        assert_eq!(2, totals.upvotes);
+        assert_eq!(0, totals.favorites);
+        repository
+            .delete_deck_votes("Turbo Nikolai", "1")
+            .await
+            .unwrap();
+        assert!(repository.list_deck_vote_totals().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_reject_stale_deck_vote() {
+        let repository = InMemoryDeckRepository::default();
+        let upvoted = DeckVote {
+            upvote: true,
+            favorite: false,
+        };
+        let unvoted = DeckVote::default();
+        let put_vote = |vote, previous_vote| {
+            repository.put_deck_vote("Turbo Nikolai", "1", "voter", vote, previous_vote)
+        };
+        assert!(put_vote(&upvoted, &unvoted).await.is_ok());
+        // a second click that also read the vote before the first was written
+        assert!(put_vote(&upvoted, &unvoted).await.is_err());
+        assert!(put_vote(&unvoted, &upvoted).await.is_ok());
+        assert!(put_vote(&upvoted, &unvoted).await.is_ok());
+        assert_eq!(
+            upvoted,
+            repository
+                .get_deck_vote("Turbo Nikolai", "1", "voter")
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_interaction_store() {
+        let store = InMemoryInteractionStore::default();
+        store
+            .put_interaction_token(&InteractionTtl {
+                interaction_id: "id".to_string(),
+                ttl: 0,
+                token: "token".to_string(),
+                original_user_id: "1".to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            "token",
+            store.get_interaction_token("id").await.unwrap().token
+        );
+        assert!(store.get_pending_deck("id").await.is_err());
+    }
+}
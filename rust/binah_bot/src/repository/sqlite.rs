@@ -0,0 +1,873 @@
+use std::error::Error;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use rusqlite::params;
+use rusqlite::Connection;
+use rusqlite::OptionalExtension;
+use rusqlite::Row;
+
+use crate::models::binahbot::InteractionTtl;
+use crate::models::config::ConfigScope;
+use crate::models::config::GuildConfig;
+use crate::models::deck::Deck;
+use crate::models::deck::DeckIndexEntry;
+use crate::models::deck::DeckMetadata;
+use crate::models::deck::DeckVersion;
+use crate::models::deck::DeckVote;
+use crate::models::deck::DeckVoteTotals;
+use crate::models::deck::PendingDeck;
+use crate::repository::ConfigRepository;
+use crate::repository::DeckRepository;
+use crate::repository::InteractionStore;
+use crate::utils::get_epoch_time;
+
+// One connection to the database file, shared by every SQLite backed store
+pub type SharedConnection = Arc<Mutex<Connection>>;
+
+pub fn open_connection(path: &str) -> Result<SharedConnection, Box<dyn Error + Send + Sync>> {
+    Ok(Arc::new(Mutex::new(Connection::open(path)?)))
+}
+
+// Single file storage for self-hosted instances. Decks, versions and pending decks are
+// stored as JSON so that new deck fields don't need a migration
+pub struct SqliteDeckRepository {
+    connection: SharedConnection,
+}
+
+impl SqliteDeckRepository {
+    pub fn new(connection: SharedConnection) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        connection.lock().unwrap().execute_batch(
+            "CREATE TABLE IF NOT EXISTS decks (
+                author TEXT NOT NULL,
+                deck_name TEXT NOT NULL,
+                keypage TEXT,
+                deck TEXT NOT NULL,
+                PRIMARY KEY (author, deck_name)
+            );
+            CREATE TABLE IF NOT EXISTS deck_versions (
+                author TEXT NOT NULL,
+                deck_name TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                deck_version TEXT NOT NULL,
+                PRIMARY KEY (author, deck_name, version)
+            );
+            CREATE TABLE IF NOT EXISTS deck_index (
+                author TEXT NOT NULL,
+                deck_name TEXT NOT NULL,
+                index_key TEXT NOT NULL,
+                author_name TEXT NOT NULL,
+                PRIMARY KEY (author, deck_name, index_key)
+            );
+            CREATE INDEX IF NOT EXISTS deck_index_by_key ON deck_index (index_key);
+            CREATE TABLE IF NOT EXISTS deck_votes (
+                author TEXT NOT NULL,
+                deck_name TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                upvote INTEGER NOT NULL,
+                favorite INTEGER NOT NULL,
+                PRIMARY KEY (author, deck_name, user_id)
+            );
+            CREATE TABLE IF NOT EXISTS deck_vote_totals (
+                author TEXT NOT NULL,
+                deck_name TEXT NOT NULL,
+                author_name TEXT NOT NULL,
+                upvotes INTEGER NOT NULL,
+                favorites INTEGER NOT NULL,
+                PRIMARY KEY (author, deck_name)
+            );
+            CREATE TABLE IF NOT EXISTS guild_collections (
+                guild_id TEXT NOT NULL,
+                author TEXT NOT NULL,
+                deck_name TEXT NOT NULL,
+                author_name TEXT NOT NULL,
+                added_by TEXT NOT NULL,
+                PRIMARY KEY (guild_id, author, deck_name)
+            );",
+        )?;
+        Ok(SqliteDeckRepository { connection })
+    }
+}
+
+fn row_to_metadata(row: &Row) -> rusqlite::Result<DeckMetadata> {
+    Ok(DeckMetadata {
+        author_id: row.get(0)?,
+        name: row.get(1)?,
+        author_name: row.get(2)?,
+    })
+}
+
+#[async_trait]
+impl DeckRepository for SqliteDeckRepository {
+    async fn get_deck(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<Deck, Box<dyn Error + Send + Sync>> {
+        let deck: String = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT deck FROM decks WHERE author = ?1 AND deck_name = ?2",
+                params![author, name],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or("could not get deck")?;
+        Ok(serde_json::from_str(&deck)?)
+    }
+
+    async fn put_deck(
+        &self,
+        deck: &Deck,
+        previous_version: Option<u32>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let connection = self.connection.lock().unwrap();
+        let deck_json = serde_json::to_string(deck)?;
+        let Some(previous_version) = previous_version else {
+            connection.execute(
+                "INSERT INTO decks (author, deck_name, keypage, deck) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    deck.author_id,
+                    deck.name,
+                    deck.deck_data.keypage_id,
+                    deck_json
+                ],
+            )?;
+            return Ok(());
+        };
+
+        let updated = connection.execute(
+            "UPDATE decks SET keypage = ?3, deck = ?4
+            WHERE author = ?1 AND deck_name = ?2
+            AND COALESCE(json_extract(deck, '$.version'), 1) = ?5",
+            params![
+                deck.author_id,
+                deck.name,
+                deck.deck_data.keypage_id,
+                deck_json,
+                previous_version
+            ],
+        )?;
+        if updated == 0 {
+            return Err("deck was changed or doesn't exist".into());
+        }
+        Ok(())
+    }
+
+    async fn list_decks(
+        &self,
+        author: Option<&str>,
+        keypage_id: Option<&str>,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT deck FROM decks
+            WHERE (?1 IS NULL OR author = ?1) AND (?2 IS NULL OR keypage = ?2)
+            ORDER BY author, deck_name",
+        )?;
+        let decks = statement
+            .query_map(params![author, keypage_id], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        decks
+            .iter()
+            .map(|x| -> Result<DeckMetadata, Box<dyn Error + Send + Sync>> {
+                Ok(DeckMetadata::from(&serde_json::from_str::<Deck>(x)?))
+            })
+            .collect()
+    }
+
+    async fn delete_deck(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.connection.lock().unwrap().execute(
+            "DELETE FROM decks WHERE author = ?1 AND deck_name = ?2",
+            params![author, name],
+        )?;
+        Ok(())
+    }
+
+    async fn put_deck_version(
+        &self,
+        deck_version: &DeckVersion,
+        overwrite: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let statement = if overwrite {
+            "INSERT OR REPLACE INTO deck_versions (author, deck_name, version, deck_version)
+            VALUES (?1, ?2, ?3, ?4)"
+        } else {
+            "INSERT INTO deck_versions (author, deck_name, version, deck_version)
+            VALUES (?1, ?2, ?3, ?4)"
+        };
+        self.connection.lock().unwrap().execute(
+            statement,
+            params![
+                deck_version.deck.author_id,
+                deck_version.deck.name,
+                deck_version.deck.version,
+                serde_json::to_string(deck_version)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    async fn list_deck_versions(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<Vec<DeckVersion>, Box<dyn Error + Send + Sync>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT deck_version FROM deck_versions
+            WHERE author = ?1 AND deck_name = ?2 ORDER BY version",
+        )?;
+        let versions = statement
+            .query_map(params![author, name], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        versions
+            .iter()
+            .map(|x| -> Result<DeckVersion, Box<dyn Error + Send + Sync>> {
+                Ok(serde_json::from_str(x)?)
+            })
+            .collect()
+    }
+
+    async fn delete_deck_versions(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.connection.lock().unwrap().execute(
+            "DELETE FROM deck_versions WHERE author = ?1 AND deck_name = ?2",
+            params![author, name],
+        )?;
+        Ok(())
+    }
+
+    async fn put_deck_index(
+        &self,
+        deck: &Deck,
+        index_keys: &[String],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "DELETE FROM deck_index WHERE author = ?1 AND deck_name = ?2",
+            params![deck.author_id, deck.name],
+        )?;
+        for index_key in index_keys {
+            transaction.execute(
+                "INSERT OR REPLACE INTO deck_index (author, deck_name, index_key, author_name)
+                VALUES (?1, ?2, ?3, ?4)",
+                params![deck.author_id, deck.name, index_key, deck.author_name],
+            )?;
+        }
+        Ok(transaction.commit()?)
+    }
+
+    async fn put_deck_index_entry(
+        &self,
+        entry: &DeckIndexEntry,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.connection.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO deck_index (author, deck_name, index_key, author_name)
+            VALUES (?1, ?2, ?3, ?4)",
+            params![
+                entry.metadata.author_id,
+                entry.metadata.name,
+                entry.index_key,
+                entry.metadata.author_name
+            ],
+        )?;
+        Ok(())
+    }
+
+    async fn delete_deck_index(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.connection.lock().unwrap().execute(
+            "DELETE FROM deck_index WHERE author = ?1 AND deck_name = ?2",
+            params![author, name],
+        )?;
+        Ok(())
+    }
+
+    async fn search_deck_index(
+        &self,
+        index_key: &str,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT author, deck_name, author_name FROM deck_index
+            WHERE index_key = ?1 ORDER BY author, deck_name",
+        )?;
+        let decks = statement
+            .query_map(params![index_key], row_to_metadata)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(decks)
+    }
+
+    async fn get_deck_vote(
+        &self,
+        name: &str,
+        author: &str,
+        user_id: &str,
+    ) -> Result<DeckVote, Box<dyn Error + Send + Sync>> {
+        let vote = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT upvote, favorite FROM deck_votes
+                WHERE author = ?1 AND deck_name = ?2 AND user_id = ?3",
+                params![author, name, user_id],
+                |row| {
+                    Ok(DeckVote {
+                        upvote: row.get(0)?,
+                        favorite: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(vote.unwrap_or_default())
+    }
+
+    async fn put_deck_vote(
+        &self,
+        name: &str,
+        author: &str,
+        user_id: &str,
+        vote: &DeckVote,
+        previous_vote: &DeckVote,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let connection = self.connection.lock().unwrap();
+        let params = params![
+            author,
+            name,
+            user_id,
+            vote.upvote,
+            vote.favorite,
+            previous_vote.upvote,
+            previous_vote.favorite
+        ];
+        // a missing vote counts as the default one, so only then may the vote be inserted
+        let updated = if *previous_vote == DeckVote::default() {
+            connection.execute(
+                "INSERT INTO deck_votes (author, deck_name, user_id, upvote, favorite)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                ON CONFLICT (author, deck_name, user_id) DO UPDATE
+                SET upvote = excluded.upvote, favorite = excluded.favorite
+                WHERE upvote = ?6 AND favorite = ?7",
+                params,
+            )?
+        } else {
+            connection.execute(
+                "UPDATE deck_votes SET upvote = ?4, favorite = ?5
+                WHERE author = ?1 AND deck_name = ?2 AND user_id = ?3
+                AND upvote = ?6 AND favorite = ?7",
+                params,
+            )?
+        };
+        if updated == 0 {
+            return Err("vote was changed".into());
+        }
+        Ok(())
+    }
+
+    async fn update_deck_vote_totals(
+        &self,
+        metadata: &DeckMetadata,
+        upvote_delta: i64,
+        favorite_delta: i64,
+    ) -> Result<DeckVoteTotals, Box<dyn Error + Send + Sync>> {
+        let (upvotes, favorites) = self.connection.lock().unwrap().query_row(
+            "INSERT INTO deck_vote_totals (author, deck_name, author_name, upvotes, favorites)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT (author, deck_name) DO UPDATE SET
+                author_name = excluded.author_name,
+                upvotes = upvotes + excluded.upvotes,
+                favorites = favorites + excluded.favorites
+            RETURNING upvotes, favorites",
+            params![
+                metadata.author_id,
+                metadata.name,
+                metadata.author_name,
+                upvote_delta,
+                favorite_delta
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(DeckVoteTotals {
+            metadata: metadata.clone(),
+            upvotes,
+            favorites,
+        })
+    }
+
+    async fn list_deck_vote_totals(
+        &self,
+    ) -> Result<Vec<DeckVoteTotals>, Box<dyn Error + Send + Sync>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT author, deck_name, author_name, upvotes, favorites FROM deck_vote_totals",
+        )?;
+        let totals = statement
+            .query_map([], |row| {
+                Ok(DeckVoteTotals {
+                    metadata: row_to_metadata(row)?,
+                    upvotes: row.get(3)?,
+                    favorites: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(totals)
+    }
+
+    async fn delete_deck_votes(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "DELETE FROM deck_votes WHERE author = ?1 AND deck_name = ?2",
+            params![author, name],
+        )?;
+        transaction.execute(
+            "DELETE FROM deck_vote_totals WHERE author = ?1 AND deck_name = ?2",
+            params![author, name],
+        )?;
+        Ok(transaction.commit()?)
+    }
+
+    async fn put_guild_collection_deck(
+        &self,
+        guild_id: &str,
+        metadata: &DeckMetadata,
+        added_by: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.connection.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO guild_collections
+            (guild_id, author, deck_name, author_name, added_by) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                guild_id,
+                metadata.author_id,
+                metadata.name,
+                metadata.author_name,
+                added_by
+            ],
+        )?;
+        Ok(())
+    }
+
+    async fn delete_guild_collection_deck(
+        &self,
+        guild_id: &str,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.connection.lock().unwrap().execute(
+            "DELETE FROM guild_collections WHERE guild_id = ?1 AND author = ?2 AND deck_name = ?3",
+            params![guild_id, author, name],
+        )?;
+        Ok(())
+    }
+
+    async fn list_guild_collection(
+        &self,
+        guild_id: &str,
+    ) -> Result<Vec<DeckMetadata>, Box<dyn Error + Send + Sync>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT author, deck_name, author_name FROM guild_collections
+            WHERE guild_id = ?1 ORDER BY author, deck_name",
+        )?;
+        let decks = statement
+            .query_map(params![guild_id], row_to_metadata)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(decks)
+    }
+
+    async fn delete_deck_from_guild_collections(
+        &self,
+        name: &str,
+        author: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.connection.lock().unwrap().execute(
+            "DELETE FROM guild_collections WHERE author = ?1 AND deck_name = ?2",
+            params![author, name],
+        )?;
+        Ok(())
+    }
+}
+
+pub struct SqliteInteractionStore {
+    connection: SharedConnection,
+}
+
+impl SqliteInteractionStore {
+    pub fn new(connection: SharedConnection) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        connection.lock().unwrap().execute_batch(
+            "CREATE TABLE IF NOT EXISTS interaction_tokens (
+                interaction_id TEXT PRIMARY KEY,
+                ttl INTEGER NOT NULL,
+                interaction_ttl TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS pending_decks (
+                interaction_id TEXT PRIMARY KEY,
+                ttl INTEGER NOT NULL,
+                pending_deck TEXT NOT NULL
+            );",
+        )?;
+        Ok(SqliteInteractionStore { connection })
+    }
+
+    // DynamoDB drops expired items on its own; here they are cleared out on every write
+    fn purge_expired(connection: &Connection) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let now = get_epoch_time();
+        connection.execute(
+            "DELETE FROM interaction_tokens WHERE ttl < ?1",
+            params![now],
+        )?;
+        connection.execute("DELETE FROM pending_decks WHERE ttl < ?1", params![now])?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl InteractionStore for SqliteInteractionStore {
+    async fn put_interaction_token(
+        &self,
+        interaction_ttl: &InteractionTtl,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let connection = self.connection.lock().unwrap();
+        Self::purge_expired(&connection)?;
+        connection.execute(
+            "INSERT OR REPLACE INTO interaction_tokens (interaction_id, ttl, interaction_ttl)
+            VALUES (?1, ?2, ?3)",
+            params![
+                interaction_ttl.interaction_id,
+                interaction_ttl.ttl,
+                serde_json::to_string(interaction_ttl)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    async fn get_interaction_token(
+        &self,
+        interaction_id: &str,
+    ) -> Result<InteractionTtl, Box<dyn Error + Send + Sync>> {
+        let interaction_ttl: String = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT interaction_ttl FROM interaction_tokens WHERE interaction_id = ?1",
+                params![interaction_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or("could not get token")?;
+        Ok(serde_json::from_str(&interaction_ttl)?)
+    }
+
+    async fn put_pending_deck(
+        &self,
+        pending_deck: &PendingDeck,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let connection = self.connection.lock().unwrap();
+        Self::purge_expired(&connection)?;
+        connection.execute(
+            "INSERT OR REPLACE INTO pending_decks (interaction_id, ttl, pending_deck)
+            VALUES (?1, ?2, ?3)",
+            params![
+                pending_deck.interaction_id,
+                pending_deck.ttl,
+                serde_json::to_string(pending_deck)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    async fn get_pending_deck(
+        &self,
+        interaction_id: &str,
+    ) -> Result<PendingDeck, Box<dyn Error + Send + Sync>> {
+        let pending_deck: String = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT pending_deck FROM pending_decks WHERE interaction_id = ?1",
+                params![interaction_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or("could not get pending deck")?;
+        Ok(serde_json::from_str(&pending_deck)?)
+    }
+}
+
+// Disabled commands are stored comma separated; command names never contain commas
+pub struct SqliteConfigRepository {
+    connection: SharedConnection,
+}
+
+impl SqliteConfigRepository {
+    pub fn new(connection: SharedConnection) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        connection.lock().unwrap().execute_batch(
+            "CREATE TABLE IF NOT EXISTS guild_configs (
+                scope TEXT PRIMARY KEY,
+                max_spoiler_chapter TEXT,
+                default_locale TEXT,
+                default_private INTEGER,
+                disabled_commands TEXT NOT NULL
+            );",
+        )?;
+        Ok(SqliteConfigRepository { connection })
+    }
+}
+
+#[async_trait]
+impl ConfigRepository for SqliteConfigRepository {
+    async fn get_config(
+        &self,
+        scope: &ConfigScope,
+    ) -> Result<GuildConfig, Box<dyn Error + Send + Sync>> {
+        let config = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT max_spoiler_chapter, default_locale, default_private, disabled_commands
+                FROM guild_configs WHERE scope = ?1",
+                params![scope.key()],
+                |row| {
+                    Ok(GuildConfig {
+                        max_spoiler_chapter: row
+                            .get::<_, Option<String>>(0)?
+                            .and_then(|x| x.parse().ok()),
+                        default_locale: row
+                            .get::<_, Option<String>>(1)?
+                            .and_then(|x| x.parse().ok()),
+                        default_private: row.get(2)?,
+                        disabled_commands: row
+                            .get::<_, String>(3)?
+                            .split(',')
+                            .filter(|x| !x.is_empty())
+                            .map(|x| x.to_string())
+                            .collect(),
+                    })
+                },
+            )
+            .optional()?;
+        Ok(config.unwrap_or_default())
+    }
+
+    async fn put_config(
+        &self,
+        scope: &ConfigScope,
+        config: &GuildConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.connection.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO guild_configs
+            (scope, max_spoiler_chapter, default_locale, default_private, disabled_commands)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                scope.key(),
+                config.max_spoiler_chapter.as_ref().map(|x| x.to_string()),
+                config.default_locale.as_ref().map(|x| x.to_string()),
+                config.default_private,
+                config.disabled_commands.join(",")
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruina::ruina_common::game_objects::common::Chapter;
+
+    use super::*;
+    use crate::models::deck::DeckData;
+    use crate::test_utils::build_deck;
+
+    #[tokio::test]
+    async fn sanity_sqlite_deck_repository() {
+        let repository = SqliteDeckRepository::new(open_connection(":memory:").unwrap()).unwrap();
+        let deck = build_deck(
+            "1",
+            "Turbo Nikolai",
+            DeckData {
+                keypage_id: Some("250023".to_string()),
+                passive_ids: Vec::new(),
+                combat_page_ids: std::array::from_fn(|_| None),
+            },
+        );
+
+        assert!(repository.put_deck(&deck, None).await.is_ok());
+        assert!(repository.put_deck(&deck, None).await.is_err());
+
+        let updated_deck = Deck {
+            version: 2,
+            ..deck.clone()
+        };
+        assert!(repository.put_deck(&updated_deck, Some(1)).await.is_ok());
+        // a concurrent edit that also read version 1
+        assert!(repository.put_deck(&updated_deck, Some(1)).await.is_err());
+        assert_eq!(
+            "Turbo Nikolai",
+            repository
+                .get_deck("Turbo Nikolai", "1")
+                .await
+                .unwrap()
+                .name
+        );
+        assert_eq!(
+            1,
+            repository
+                .list_decks(None, Some("250023"))
+                .await
+                .unwrap()
+                .len()
+        );
+
+        let metadata = DeckMetadata::from(&deck);
+        repository
+            .update_deck_vote_totals(&metadata, 1, 1)
+            .await
+            .unwrap();
+        let totals = repository
+            .update_deck_vote_totals(&metadata, 1, -1)
+            .await
+            .unwrap();
+        assert_eq!((2, 0), (totals.upvotes, totals.favorites));
+
+        let upvoted = DeckVote {
+            upvote: true,
+            favorite: false,
+        };
+        let unvoted = DeckVote::default();
+        let put_vote = |vote, previous_vote| {
+            repository.put_deck_vote("Turbo Nikolai", "1", "voter", vote, previous_vote)
+        };
+        assert!(put_vote(&upvoted, &unvoted).await.is_ok());
+        // a second click that also read the vote before the first was written
+        assert!(put_vote(&upvoted, &unvoted).await.is_err());
+        assert!(put_vote(&unvoted, &upvoted).await.is_ok());
+        assert!(put_vote(&upvoted, &unvoted).await.is_ok());
+        assert_eq!(
+            upvoted,
+            repository
+                .get_deck_vote("Turbo Nikolai", "1", "voter")
+                .await
+                .unwrap()
+        );
+
+        repository
+            .put_deck_index(&deck, &["tag#burn".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(
+            1,
+            repository
+                .search_deck_index("tag#burn")
+                .await
+                .unwrap()
+                .len()
+        );
+        repository
+            .delete_deck_index("Turbo Nikolai", "1")
+            .await
+            .unwrap();
+        assert!(repository
+            .search_deck_index("tag#burn")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_update_deck_saved_before_versioning() {
+        let repository = SqliteDeckRepository::new(open_connection(":memory:").unwrap()).unwrap();
+        let mut deck_json = serde_json::to_value(build_deck(
+            "1",
+            "Turbo Nikolai",
+            DeckData {
+                keypage_id: Some("250023".to_string()),
+                passive_ids: Vec::new(),
+                combat_page_ids: std::array::from_fn(|_| None),
+            },
+        ))
+        .unwrap();
+        deck_json.as_object_mut().unwrap().remove("version");
+        repository
+            .connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO decks (author, deck_name, keypage, deck) VALUES (?1, ?2, ?3, ?4)",
+                params!["1", "Turbo Nikolai", "250023", deck_json.to_string()],
+            )
+            .unwrap();
+
+        let deck = repository.get_deck("Turbo Nikolai", "1").await.unwrap();
+        assert_eq!(1, deck.version);
+        let updated_deck = Deck { version: 2, ..deck };
+        assert!(repository.put_deck(&updated_deck, Some(1)).await.is_ok());
+        assert_eq!(
+            2,
+            repository
+                .get_deck("Turbo Nikolai", "1")
+                .await
+                .unwrap()
+                .version
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_sqlite_config_repository() {
+        let repository = SqliteConfigRepository::new(open_connection(":memory:").unwrap()).unwrap();
+        let scope = ConfigScope::Channel("1".to_string());
+        let config = GuildConfig {
+            max_spoiler_chapter: Some(Chapter::UrbanPlague),
+            default_locale: None,
+            default_private: Some(true),
+            disabled_commands: vec!["lc".to_string(), "rollcalc".to_string()],
+        };
+
+        assert_eq!(
+            GuildConfig::default(),
+            repository.get_config(&scope).await.unwrap()
+        );
+        repository.put_config(&scope, &config).await.unwrap();
+        assert_eq!(config, repository.get_config(&scope).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn sanity_sqlite_interaction_store() {
+        let store = SqliteInteractionStore::new(open_connection(":memory:").unwrap()).unwrap();
+        let interaction_ttl = InteractionTtl {
+            interaction_id: "id".to_string(),
+            ttl: get_epoch_time() + 60,
+            token: "token".to_string(),
+            original_user_id: "1".to_string(),
+        };
+        store.put_interaction_token(&interaction_ttl).await.unwrap();
+
+        assert_eq!(
+            "token",
+            store.get_interaction_token("id").await.unwrap().token
+        );
+        assert!(store.get_interaction_token("other").await.is_err());
+    }
+}
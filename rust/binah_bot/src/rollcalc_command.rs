@@ -6,6 +6,7 @@ use fluent_templates::fluent_bundle::FluentValue;
 use fluent_templates::Loader;
 use unic_langid::LanguageIdentifier;
 
+use crate::error::BinahBotError;
 use crate::macros::cast_enum_variant;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::BinahBotLocale;
@@ -17,13 +18,13 @@ use crate::models::discord::DiscordComponentType;
 use crate::models::discord::DiscordEmbed;
 use crate::models::discord::DiscordEmbedFields;
 use crate::models::discord::DiscordInteraction;
-use crate::models::discord::DiscordInteractionData;
 use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionResponseMessage;
 use crate::models::discord::DiscordInteractionResponseType;
 use crate::models::discord::DiscordMessageFlag;
 use crate::models::discord::MessageResponse;
 use crate::utils::build_delete_button_component;
+use crate::utils::get_command_options;
 use crate::utils::get_option_value;
 
 #[derive(Debug, PartialEq)]
@@ -32,13 +33,8 @@ struct DiceDistribution(u128, u128, u128, u128); // Total, d1 win, d2 win, draw
 pub fn rollcalc_command(
     interaction: &DiscordInteraction,
     env: &BinahBotEnvironment,
-) -> MessageResponse {
-    let command_args = interaction
-        .data
-        .as_ref()
-        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-        .and_then(|x| x.options.as_ref())
-        .unwrap();
+) -> Result<MessageResponse, BinahBotError> {
+    let command_args = get_command_options(interaction)?;
 
     tracing::info!("Rollcalc command: command args: {:#?}", command_args);
 
@@ -65,16 +61,16 @@ pub fn rollcalc_command(
 
     let min1 = get_option_value("min1", command_args)
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Integer))
-        .expect("no min1");
+        .ok_or(BinahBotError::MissingOption("min1"))?;
     let min2 = get_option_value("min2", command_args)
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Integer))
-        .expect("no min2");
+        .ok_or(BinahBotError::MissingOption("min2"))?;
     let max1 = get_option_value("max1", command_args)
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Integer))
-        .expect("no max1");
+        .ok_or(BinahBotError::MissingOption("max1"))?;
     let max2 = get_option_value("max2", command_args)
         .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Integer))
-        .expect("no max2");
+        .ok_or(BinahBotError::MissingOption("max2"))?;
 
     let title = env.locales.lookup_with_args(
         &lang_id,
@@ -171,7 +167,7 @@ pub fn rollcalc_command(
             ))],
         })]);
 
-    MessageResponse {
+    Ok(MessageResponse {
         r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
         data: Some(DiscordInteractionResponseMessage {
             allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
@@ -180,7 +176,7 @@ pub fn rollcalc_command(
             flags: flags,
             components: components,
         }),
-    }
+    })
 }
 
 fn format_percent<'a>(n: f64) -> FluentValue<'a> {
@@ -222,6 +218,8 @@ fn calculate(min1: i32, max1: i32, min2: i32, max2: i32) -> DiceDistribution {
 #[cfg(test)]
 mod tests {
     use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordInteractionData;
     use crate::models::discord::DiscordInteractionOptions;
     use crate::models::discord::DiscordInteractionType;
     use crate::models::discord::DiscordUser;
@@ -266,6 +264,7 @@ mod tests {
                 DiscordApplicationCommandInteractionData {
                     id: "id".to_string(),
                     name: "lor".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
                     options: Some(vec![
                         DiscordInteractionOptions {
                             name: "min1".to_string(),
@@ -292,9 +291,12 @@ mod tests {
                             focused: None,
                         },
                     ]),
+                    target_id: None,
+                    resolved: None,
                 },
             )),
             channel_id: None,
+            guild_id: None,
             token: "token".to_string(),
             locale: None,
             guild_locale: None,
@@ -306,6 +308,6 @@ mod tests {
             member: None,
             message: None,
         };
-        let _does_not_crash = rollcalc_command(&interaction, &build_mocked_binahbot_env());
+        assert!(rollcalc_command(&interaction, &build_mocked_binahbot_env()).is_ok());
     }
 }
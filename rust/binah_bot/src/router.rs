@@ -1,19 +1,59 @@
 use std::error::Error;
+use std::future::Future;
+use std::time::Duration;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
-use unic_langid::langid;
+use tokio::time::Instant;
+use unic_langid::LanguageIdentifier;
 
 use crate::about_command::about_command;
-use crate::ddb::get_interaction_token;
-use crate::ddb::put_interaction_token;
+use crate::commands::ABOUT_COMMAND_NAME;
+use crate::commands::CONFIG_COMMAND_NAME;
+use crate::commands::CREATE_DECK_COMMAND_NAME;
+use crate::commands::DECK_COLLECTION_COMMAND_NAME;
+use crate::commands::DECK_DIFF_COMMAND_NAME;
+use crate::commands::DECK_EDITORS_COMMAND_NAME;
+use crate::commands::DECK_HISTORY_COMMAND_NAME;
+use crate::commands::DECK_LEADERBOARD_COMMAND_NAME;
+use crate::commands::DECK_SEARCH_COMMAND_NAME;
+use crate::commands::DELETE_DECK_COMMAND_NAME;
+use crate::commands::FORK_DECK_COMMAND_NAME;
+use crate::commands::LC_COMMAND_NAME;
+use crate::commands::LOOKUP_MESSAGE_COMMAND_NAME;
+use crate::commands::LOR_COMMAND_NAME;
+use crate::commands::PROGRESS_COMMAND_NAME;
+use crate::commands::READ_DECK_COMMAND_NAME;
+use crate::commands::ROLLCALC_COMMAND_NAME;
+use crate::commands::SPOILERS_COMMAND_NAME;
+use crate::commands::UPDATE_DECK_COMMAND_NAME;
+use crate::commands::USER_DECKS_COMMAND_NAME;
+use crate::config_command::config_command;
+use crate::context_menu_command::lookup_message_command;
+use crate::context_menu_command::user_decks_command;
 use crate::deck::create_deck::create_deck;
+use crate::deck::create_deck::create_deck_button;
+use crate::deck::create_deck::CREATE_DECK_BUTTON_PREFIX;
+use crate::deck::deck_collection::deck_collection;
+use crate::deck::deck_diff::deck_diff;
+use crate::deck::deck_editors::deck_editors;
+use crate::deck::deck_history::deck_history;
+use crate::deck::deck_leaderboard::deck_leaderboard;
+use crate::deck::deck_vote::deck_vote_button;
+use crate::deck::deck_vote::DECK_VOTE_BUTTON_PREFIX;
 use crate::deck::delete_deck::delete_deck;
+use crate::deck::fork_deck::fork_deck;
 use crate::deck::list_deck::list_deck;
 use crate::deck::list_deck::list_my_decks;
 use crate::deck::read_deck::read_deck;
+use crate::deck::search_deck::search_deck;
 use crate::deck::update_deck::update_deck;
+use crate::discord::create_interaction_response;
 use crate::discord::delete_interaction;
+use crate::discord::edit_original_response;
+use crate::error::BinahBotError;
+use crate::guild_config::apply_guild_config;
+use crate::guild_config::get_guild_config;
 use crate::lc::autocomplete::lc_autocomplete;
 use crate::lc::button::lc_button;
 use crate::lc::button::LC_BUTTON_PREFIX;
@@ -23,144 +63,360 @@ use crate::lor::command::lor_command;
 use crate::macros::cast_enum_variant;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::InteractionTtl;
+use crate::models::config::GuildConfig;
 use crate::models::discord::AutocompleteResponse;
 use crate::models::discord::DeferredUpdateResponse;
 use crate::models::discord::DiscordInteraction;
 use crate::models::discord::DiscordInteractionData;
+use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionResponse;
 use crate::models::discord::DiscordInteractionResponseAutocomplete;
+use crate::models::discord::DiscordInteractionResponseMessage;
 use crate::models::discord::DiscordInteractionResponseType;
 use crate::models::discord::DiscordInteractionType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::MessageResponse;
 use crate::models::discord::PingResponse;
+use crate::progress_command::progress_command;
 use crate::rollcalc_command::rollcalc_command;
-use crate::utils::build_error_message_response;
+use crate::spoilers_command::spoilers_command;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_command_data;
+use crate::utils::get_component_data;
+use crate::utils::get_interaction_user;
+use crate::utils::get_option_value;
+use crate::utils::get_reqwest_client;
 use crate::utils::DELETE_BUTTON_CUSTOM_ID;
 
-const ABOUT_COMMAND_NAME: &str = "about";
-const LC_COMMAND_NAME: &str = "lc";
-const LOR_COMMAND_NAME: &str = "lor";
-const CREATE_DECK_COMMAND_NAME: &str = "createdeck";
-const READ_DECK_COMMAND_NAME: &str = "deck";
-const UPDATE_DECK_COMMAND_NAME: &str = "updatedeck";
-const DELETE_DECK_COMMAND_NAME: &str = "deletedeck";
-const ROLLCALC_COMMAND_NAME: &str = "rollcalc";
+// Discord drops interactions that aren't acknowledged within 3 seconds of being sent, so
+// commands that wait on tiphereth or storage are deferred shortly before then, counting from
+// when the interaction was received, and finished with an edit
+const DEFER_AFTER: Duration = Duration::from_secs(2);
 
 pub async fn get_response(
-    discord_interaction: &DiscordInteraction,
+    mut discord_interaction: DiscordInteraction,
     binahbot_env: &BinahBotEnvironment,
 ) -> Result<DiscordInteractionResponse, Box<dyn Error + Send + Sync>> {
+    let received_at = Instant::now();
     tracing::info!(
         "Calling router with interaction type={:?}",
         &discord_interaction.r#type
     );
 
+    let guild_config = get_guild_config(&discord_interaction, binahbot_env).await;
+    apply_guild_config(&mut discord_interaction, &guild_config);
+    let discord_interaction = &discord_interaction;
+
     let (response, _) = tokio::join!(
-        route(discord_interaction, binahbot_env),
+        route(
+            discord_interaction,
+            &guild_config,
+            received_at,
+            binahbot_env
+        ),
         put_interaction_ttl(discord_interaction, binahbot_env)
     );
 
-    response
+    Ok(response.unwrap_or_else(|e| build_error_response(&e, discord_interaction, binahbot_env)))
 }
 
 async fn route(
     discord_interaction: &DiscordInteraction,
+    guild_config: &GuildConfig,
+    received_at: Instant,
     binahbot_env: &BinahBotEnvironment,
-) -> Result<DiscordInteractionResponse, Box<dyn Error + Send + Sync>> {
+) -> Result<DiscordInteractionResponse, BinahBotError> {
     match &discord_interaction.r#type {
         DiscordInteractionType::Ping => Ok(DiscordInteractionResponse::Ping(PingResponse {
             r#type: DiscordInteractionResponseType::Pong,
         })),
         DiscordInteractionType::ApplicationCommand => {
-            let data = discord_interaction
-                .data
-                .as_ref()
-                .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-                .unwrap();
+            let data = get_command_data(discord_interaction)?;
+            check_command_enabled(&data.name, guild_config)?;
             Ok(DiscordInteractionResponse::Message(
                 match data.name.as_str() {
-                    LC_COMMAND_NAME => lc_command(discord_interaction, binahbot_env),
-                    LOR_COMMAND_NAME => lor_command(discord_interaction, binahbot_env),
+                    LC_COMMAND_NAME => lc_command(discord_interaction, binahbot_env)?,
+                    // checking whether the page card was already rendered is a request to S3
+                    LOR_COMMAND_NAME => {
+                        defer_if_slow(
+                            lor_command(discord_interaction, binahbot_env),
+                            has_private_option(discord_interaction),
+                            received_at,
+                            discord_interaction,
+                            binahbot_env,
+                        )
+                        .await?
+                    }
                     CREATE_DECK_COMMAND_NAME => {
-                        create_deck(discord_interaction, binahbot_env).await
+                        defer_if_slow(
+                            create_deck(discord_interaction, binahbot_env),
+                            true,
+                            received_at,
+                            discord_interaction,
+                            binahbot_env,
+                        )
+                        .await?
+                    }
+                    READ_DECK_COMMAND_NAME => {
+                        defer_if_slow(
+                            read_deck(discord_interaction, binahbot_env),
+                            has_private_option(discord_interaction),
+                            received_at,
+                            discord_interaction,
+                            binahbot_env,
+                        )
+                        .await?
                     }
-                    READ_DECK_COMMAND_NAME => read_deck(discord_interaction, binahbot_env).await,
                     UPDATE_DECK_COMMAND_NAME => {
-                        update_deck(discord_interaction, binahbot_env).await
+                        defer_if_slow(
+                            update_deck(discord_interaction, binahbot_env),
+                            true,
+                            received_at,
+                            discord_interaction,
+                            binahbot_env,
+                        )
+                        .await?
                     }
                     DELETE_DECK_COMMAND_NAME => {
-                        delete_deck(discord_interaction, binahbot_env).await
+                        delete_deck(discord_interaction, binahbot_env).await?
+                    }
+                    // viewing and rolling back a version render its thumbnail
+                    DECK_HISTORY_COMMAND_NAME => {
+                        defer_if_slow(
+                            deck_history(discord_interaction, binahbot_env),
+                            true,
+                            received_at,
+                            discord_interaction,
+                            binahbot_env,
+                        )
+                        .await?
+                    }
+                    DECK_DIFF_COMMAND_NAME => deck_diff(discord_interaction, binahbot_env).await?,
+                    DECK_SEARCH_COMMAND_NAME => {
+                        search_deck(discord_interaction, binahbot_env).await?
+                    }
+                    DECK_LEADERBOARD_COMMAND_NAME => {
+                        deck_leaderboard(discord_interaction, binahbot_env).await?
+                    }
+                    DECK_COLLECTION_COMMAND_NAME => {
+                        deck_collection(discord_interaction, binahbot_env).await?
+                    }
+                    FORK_DECK_COMMAND_NAME => {
+                        defer_if_slow(
+                            fork_deck(discord_interaction, binahbot_env),
+                            true,
+                            received_at,
+                            discord_interaction,
+                            binahbot_env,
+                        )
+                        .await?
+                    }
+                    DECK_EDITORS_COMMAND_NAME => {
+                        deck_editors(discord_interaction, binahbot_env).await?
+                    }
+                    SPOILERS_COMMAND_NAME => {
+                        spoilers_command(discord_interaction, binahbot_env).await?
+                    }
+                    PROGRESS_COMMAND_NAME => {
+                        progress_command(discord_interaction, binahbot_env).await?
+                    }
+                    CONFIG_COMMAND_NAME => {
+                        config_command(discord_interaction, binahbot_env).await?
                     }
                     ABOUT_COMMAND_NAME => about_command(discord_interaction, binahbot_env),
-                    ROLLCALC_COMMAND_NAME => rollcalc_command(discord_interaction, binahbot_env),
-                    _ => build_error_message_response(
-                        &langid!("en-US"),
-                        "generic_error_message",
-                        binahbot_env,
-                    ),
+                    ROLLCALC_COMMAND_NAME => rollcalc_command(discord_interaction, binahbot_env)?,
+                    // looks up page cards like /lor, or decodes and renders a tiphereth deck
+                    LOOKUP_MESSAGE_COMMAND_NAME => {
+                        defer_if_slow(
+                            lookup_message_command(discord_interaction, binahbot_env),
+                            true,
+                            received_at,
+                            discord_interaction,
+                            binahbot_env,
+                        )
+                        .await?
+                    }
+                    USER_DECKS_COMMAND_NAME => {
+                        user_decks_command(discord_interaction, binahbot_env).await?
+                    }
+                    x => return Err(BinahBotError::UnknownCommand(x.to_string())),
                 },
             ))
         }
         DiscordInteractionType::ApplicationCommandAutocomplete => {
-            let data = discord_interaction
-                .data
-                .as_ref()
-                .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
-                .unwrap();
+            let data = get_command_data(discord_interaction)?;
+            check_command_enabled(&data.name, guild_config)?;
             Ok(DiscordInteractionResponse::Autocomplete(
                 match data.name.as_str() {
-                    LOR_COMMAND_NAME => lor_autocomplete(discord_interaction, binahbot_env),
-                    READ_DECK_COMMAND_NAME => list_deck(discord_interaction, binahbot_env).await,
-                    UPDATE_DECK_COMMAND_NAME | DELETE_DECK_COMMAND_NAME => {
-                        list_my_decks(discord_interaction, binahbot_env).await
+                    LOR_COMMAND_NAME => lor_autocomplete(discord_interaction, binahbot_env).await?,
+                    READ_DECK_COMMAND_NAME
+                    | DECK_HISTORY_COMMAND_NAME
+                    | DECK_DIFF_COMMAND_NAME
+                    | DECK_SEARCH_COMMAND_NAME
+                    | DECK_LEADERBOARD_COMMAND_NAME
+                    | DECK_COLLECTION_COMMAND_NAME
+                    | FORK_DECK_COMMAND_NAME => {
+                        list_deck(discord_interaction, binahbot_env).await?
                     }
-                    LC_COMMAND_NAME => lc_autocomplete(discord_interaction, binahbot_env),
-                    _ => AutocompleteResponse {
-                        r#type:
-                            DiscordInteractionResponseType::ApplicationCommandAutocompleteResult,
-                        data: Some(DiscordInteractionResponseAutocomplete {
-                            choices: Some(Vec::new()),
-                        }),
-                    },
+                    UPDATE_DECK_COMMAND_NAME
+                    | DELETE_DECK_COMMAND_NAME
+                    | DECK_EDITORS_COMMAND_NAME => {
+                        list_my_decks(discord_interaction, binahbot_env).await?
+                    }
+                    LC_COMMAND_NAME => lc_autocomplete(discord_interaction, binahbot_env)?,
+                    x => return Err(BinahBotError::UnknownCommand(x.to_string())),
                 },
             ))
         }
         DiscordInteractionType::MessageComponent => {
-            let data = discord_interaction
-                .data
-                .as_ref()
-                .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::MessageComponent))
-                .unwrap();
-
-            let custom_id = &data.custom_id;
+            let custom_id = &get_component_data(discord_interaction)?.custom_id;
 
-            let result = if custom_id == DELETE_BUTTON_CUSTOM_ID {
-                let _ = process_delete_button(discord_interaction, binahbot_env).await;
+            if custom_id == DELETE_BUTTON_CUSTOM_ID {
+                process_delete_button(discord_interaction, binahbot_env).await?;
 
-                return Ok(DiscordInteractionResponse::DeferredUpdateMessage(
+                Ok(DiscordInteractionResponse::DeferredUpdateMessage(
                     DeferredUpdateResponse {
                         r#type: DiscordInteractionResponseType::DeferredUpdateMessage,
                     },
-                ));
+                ))
+            } else if custom_id.starts_with(DECK_VOTE_BUTTON_PREFIX) {
+                // votes are acknowledged privately rather than editing the shared deck message
+                Ok(DiscordInteractionResponse::Message(
+                    deck_vote_button(discord_interaction, binahbot_env).await?,
+                ))
             } else if custom_id.starts_with(LC_BUTTON_PREFIX) {
-                lc_button(&discord_interaction, binahbot_env)
+                Ok(DiscordInteractionResponse::UpdateMessage(lc_button(
+                    discord_interaction,
+                    binahbot_env,
+                )?))
+            } else if custom_id.starts_with(CREATE_DECK_BUTTON_PREFIX) {
+                Ok(DiscordInteractionResponse::UpdateMessage(
+                    create_deck_button(discord_interaction, binahbot_env).await?,
+                ))
             } else {
-                panic!("unknown button with custom_id={} detected", custom_id)
-            };
-
-            match result {
-                Ok(x) => Ok(DiscordInteractionResponse::UpdateMessage(x)),
-                Err(_) => Ok(DiscordInteractionResponse::DeferredUpdateMessage(
-                    DeferredUpdateResponse {
-                        r#type: DiscordInteractionResponseType::DeferredUpdateMessage,
-                    },
-                )),
+                Err(BinahBotError::UnknownComponent(custom_id.to_string()))
             }
         }
-        _ => panic!(
-            "unknown interaction type={:?} detected",
-            &discord_interaction.r#type
+        x => Err(BinahBotError::UnsupportedInteractionType(format!(
+            "{:?}",
+            x
+        ))),
+    }
+}
+
+// Malformed or stale interactions still get an answer so the user isn't left with
+// "The application did not respond"
+fn build_error_response(
+    error: &BinahBotError,
+    discord_interaction: &DiscordInteraction,
+    binahbot_env: &BinahBotEnvironment,
+) -> DiscordInteractionResponse {
+    error.log(discord_interaction);
+
+    match discord_interaction.r#type {
+        DiscordInteractionType::ApplicationCommandAutocomplete => {
+            DiscordInteractionResponse::Autocomplete(AutocompleteResponse {
+                r#type: DiscordInteractionResponseType::ApplicationCommandAutocompleteResult,
+                data: Some(DiscordInteractionResponseAutocomplete {
+                    choices: Some(Vec::new()),
+                }),
+            })
+        }
+        _ => DiscordInteractionResponse::Message(error.to_message_response(
+            &LanguageIdentifier::from(&get_binahbot_locale(discord_interaction)),
+            binahbot_env,
+        )),
+    }
+}
+
+// Answers directly if the command finishes in time. Otherwise the interaction is
+// acknowledged through the callback endpoint while the command keeps running, and the
+// result is sent by editing the original response. The deferred response is returned so
+// the caller still has something to reply with, though Discord will have stopped waiting
+async fn defer_if_slow(
+    response: impl Future<Output = Result<MessageResponse, BinahBotError>>,
+    is_private: bool,
+    received_at: Instant,
+    discord_interaction: &DiscordInteraction,
+    binahbot_env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    tokio::pin!(response);
+    tokio::select! {
+        x = &mut response => return x,
+        _ = tokio::time::sleep_until(received_at + DEFER_AFTER) => {}
+    }
+
+    tracing::info!(
+        "Deferring response to interaction with id={}",
+        discord_interaction.id
+    );
+    let client = get_reqwest_client(binahbot_env)?;
+    // ephemerality can't be changed by the follow-up edit, so it's decided up front
+    let deferred_response = MessageResponse {
+        r#type: DiscordInteractionResponseType::DeferredChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: None,
+            content: None,
+            embeds: None,
+            flags: is_private.then_some(DiscordMessageFlag::EphemeralMessage as i32),
+            components: None,
+        }),
+    };
+
+    let (acknowledgement, response) = tokio::join!(
+        create_interaction_response(
+            client,
+            &discord_interaction.id,
+            &discord_interaction.token,
+            &deferred_response
         ),
+        response
+    );
+    if let Err(e) = acknowledgement {
+        tracing::error!("Failed to defer interaction: {}", e);
+        return response;
+    }
+
+    // the deferred response has already been sent, so errors are delivered by the edit too
+    let response = response.unwrap_or_else(|e| {
+        e.log(discord_interaction);
+        e.to_message_response(
+            &LanguageIdentifier::from(&get_binahbot_locale(discord_interaction)),
+            binahbot_env,
+        )
+    });
+    if let Some(message) = response.data.as_ref() {
+        if let Err(e) = edit_original_response(
+            client,
+            &binahbot_env.discord_secrets,
+            &discord_interaction.token,
+            message,
+        )
+        .await
+        {
+            tracing::error!("Failed to send deferred response: {}", e);
+        }
+    }
+
+    Ok(deferred_response)
+}
+
+fn check_command_enabled(name: &str, guild_config: &GuildConfig) -> Result<(), BinahBotError> {
+    if guild_config.disabled_commands.iter().any(|x| x == name) {
+        return Err(BinahBotError::CommandDisabled(name.to_string()));
     }
+    Ok(())
+}
+
+fn has_private_option(discord_interaction: &DiscordInteraction) -> bool {
+    discord_interaction
+        .data
+        .as_ref()
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
+        .and_then(|x| x.options.as_ref())
+        .and_then(|x| get_option_value("private", x))
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::Bool))
+        .is_some_and(|x| *x)
 }
 
 async fn put_interaction_ttl(
@@ -182,82 +438,231 @@ async fn put_interaction_ttl(
         interaction_id: discord_interaction.id.clone(),
         token: discord_interaction.token.clone(),
         ttl: epoch_time,
-        original_user_id: discord_interaction
-            .user
-            .as_ref()
-            .unwrap_or(
-                discord_interaction
-                    .member
-                    .as_ref()
-                    .unwrap()
-                    .user
-                    .as_ref()
-                    .unwrap(),
-            )
-            .id
-            .clone(),
+        original_user_id: get_interaction_user(discord_interaction)?.id.clone(),
     };
 
-    put_interaction_token(
-        binahbot_env.ddb_client.as_ref().expect("no ddb client"),
-        &binahbot_env.ddb_interaction_ttl_table_name,
-        &interaction_ttl,
-    )
-    .await
+    binahbot_env
+        .interaction_store
+        .put_interaction_token(&interaction_ttl)
+        .await
 }
 
 async fn process_delete_button(
     discord_interaction: &DiscordInteraction,
     binahbot_env: &BinahBotEnvironment,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
+) -> Result<(), BinahBotError> {
     let previous_interaction_id = &discord_interaction
         .message
         .as_ref()
-        .expect("no message on this delete button interaction")
-        .interaction_metadata
-        .as_ref()
-        .expect("no interaction_metadata field")
+        .and_then(|x| x.interaction_metadata.as_ref())
+        .ok_or(BinahBotError::MissingMessage)?
         .id;
-    let user_id = discord_interaction
-        .user
-        .as_ref()
-        .unwrap_or(
-            discord_interaction
-                .member
-                .as_ref()
-                .unwrap()
-                .user
-                .as_ref()
-                .unwrap(),
-        )
-        .id
-        .clone();
-
-    let interaction_ttl = get_interaction_token(
-        binahbot_env
-            .ddb_client
-            .as_ref()
-            .expect("no ddb client provided"),
-        &binahbot_env.ddb_interaction_ttl_table_name,
-        &previous_interaction_id,
-    )
-    .await;
-
-    if interaction_ttl
-        .as_ref()
-        .is_ok_and(|x| user_id == x.original_user_id)
+    let user_id = &get_interaction_user(discord_interaction)?.id;
+
+    let interaction_ttl = binahbot_env
+        .interaction_store
+        .get_interaction_token(previous_interaction_id)
+        .await;
+
+    // messages whose token has expired can't be deleted anymore, so those clicks are ignored
+    if let Some(interaction_ttl) = interaction_ttl
+        .ok()
+        .filter(|x| user_id == &x.original_user_id)
     {
         tracing::info!("Deleting interaction with id={}", previous_interaction_id);
         let _ = delete_interaction(
-            binahbot_env
-                .reqwest_client
-                .as_ref()
-                .expect("no http client provided"),
+            get_reqwest_client(binahbot_env)?,
             &binahbot_env.discord_secrets,
-            &interaction_ttl.as_ref().unwrap().token,
+            &interaction_ttl.token,
         )
         .await;
     };
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::build_application_commands;
+    use crate::models::discord::DiscordApplicationCommandInteractionData;
+    use crate::models::discord::DiscordApplicationCommandType;
+    use crate::models::discord::DiscordGuildMember;
+    use crate::models::discord::DiscordInteractionOptions;
+    use crate::models::discord::DiscordMessageComponentInteractionData;
+    use crate::models::discord::DiscordUser;
+    use crate::test_utils::build_mocked_binahbot_env;
+    use crate::LOCALES;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn sanity_fast_command_not_deferred() {
+        // the mocked environment has no http client, so deferring would panic
+        let response = get_response(
+            build_discord_interaction("missing#snowflake"),
+            &build_mocked_binahbot_env(),
+        )
+        .await
+        .unwrap();
+
+        let message = cast_enum_variant!(response, DiscordInteractionResponse::Message).unwrap();
+        assert_eq!(
+            DiscordInteractionResponseType::ChannelMessageWithSource,
+            message.r#type
+        );
+    }
+
+    #[tokio::test]
+    async fn should_defer_from_when_interaction_was_received() {
+        // storage reads before routing count against the deadline too, so this defers at once
+        let response = defer_if_slow(
+            std::future::pending(),
+            true,
+            Instant::now() - DEFER_AFTER,
+            &build_discord_interaction("deck#user"),
+            &build_mocked_binahbot_env(),
+        )
+        .await;
+
+        assert_eq!(Some(BinahBotError::MissingHttpClient), response.err());
+    }
+
+    #[tokio::test]
+    async fn sanity_unknown_button_not_panicking() {
+        let mut interaction = build_discord_interaction("deck#user");
+        interaction.r#type = DiscordInteractionType::MessageComponent;
+        interaction.data = Some(DiscordInteractionData::MessageComponent(
+            DiscordMessageComponentInteractionData {
+                custom_id: "stale#button".to_string(),
+            },
+        ));
+
+        let response = get_response(interaction, &build_mocked_binahbot_env())
+            .await
+            .unwrap();
+
+        let message = cast_enum_variant!(response, DiscordInteractionResponse::Message).unwrap();
+        assert_eq!(
+            Some(DiscordMessageFlag::EphemeralMessage as i32),
+            message.data.unwrap().flags
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_missing_options_not_panicking() {
+        let mut interaction = build_discord_interaction("deck#user");
+        if let Some(DiscordInteractionData::ApplicationCommand(data)) = interaction.data.as_mut() {
+            data.options = None;
+        }
+
+        let response = route(
+            &interaction,
+            &GuildConfig::default(),
+            Instant::now(),
+            &build_mocked_binahbot_env(),
+        )
+        .await;
+
+        assert_eq!(Some(BinahBotError::MissingInteractionData), response.err());
+    }
+
+    #[tokio::test]
+    async fn sanity_registered_commands_are_routed() {
+        let env = build_mocked_binahbot_env();
+
+        for command in build_application_commands(&LOCALES) {
+            let mut interaction = build_discord_interaction("deck#user");
+            interaction.data = Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: command.name.clone(),
+                    r#type: Some(command.r#type),
+                    options: None,
+                    target_id: None,
+                    resolved: None,
+                },
+            ));
+
+            let response = route(&interaction, &GuildConfig::default(), Instant::now(), &env).await;
+
+            assert_ne!(
+                Some(BinahBotError::UnknownCommand(command.name)),
+                response.err()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn sanity_disabled_command() {
+        let guild_config = GuildConfig {
+            disabled_commands: vec![READ_DECK_COMMAND_NAME.to_string()],
+            ..GuildConfig::default()
+        };
+
+        let response = route(
+            &build_discord_interaction("deck#user"),
+            &guild_config,
+            Instant::now(),
+            &build_mocked_binahbot_env(),
+        )
+        .await;
+
+        assert_eq!(
+            Some(BinahBotError::CommandDisabled(
+                READ_DECK_COMMAND_NAME.to_string()
+            )),
+            response.err()
+        );
+    }
+
+    #[test]
+    fn sanity_has_private_option() {
+        assert!(has_private_option(&build_discord_interaction("deck#user")));
+    }
+
+    fn build_discord_interaction(deck_name: &str) -> DiscordInteraction {
+        DiscordInteraction {
+            id: "id".to_string(),
+            application_id: "app_id".to_string(),
+            r#type: DiscordInteractionType::ApplicationCommand,
+            data: Some(DiscordInteractionData::ApplicationCommand(
+                DiscordApplicationCommandInteractionData {
+                    id: "id".to_string(),
+                    name: "deck".to_string(),
+                    r#type: Some(DiscordApplicationCommandType::ChatInput),
+                    options: Some(vec![
+                        DiscordInteractionOptions {
+                            name: "name".to_string(),
+                            name_localizations: None,
+                            value: DiscordInteractionOptionValue::String(deck_name.to_string()),
+                            focused: None,
+                        },
+                        DiscordInteractionOptions {
+                            name: "private".to_string(),
+                            name_localizations: None,
+                            value: DiscordInteractionOptionValue::Bool(true),
+                            focused: None,
+                        },
+                    ]),
+                    target_id: None,
+                    resolved: None,
+                },
+            )),
+            channel_id: None,
+            guild_id: Some("guild".to_string()),
+            token: "token".to_string(),
+            locale: None,
+            guild_locale: None,
+            user: None,
+            member: Some(DiscordGuildMember {
+                user: Some(DiscordUser {
+                    id: "snowflake".to_string(),
+                    username: "username".to_string(),
+                    avatar: Some("hash".to_string()),
+                }),
+                permissions: None,
+            }),
+            message: None,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::env;
+
 use crate::models::binahbot::DiscordSecrets;
 
 pub async fn get_discord_secrets(
@@ -16,3 +18,24 @@ pub async fn get_discord_secrets(
     )
     .unwrap()
 }
+
+// Self-hosted deployments can pass the secrets directly instead of through Secrets Manager
+pub fn get_discord_secrets_from_env() -> Option<DiscordSecrets> {
+    Some(DiscordSecrets {
+        application_id: env::var("DISCORD_APPLICATION_ID").ok()?,
+        auth_token: env::var("DISCORD_AUTH_TOKEN").unwrap_or_default(),
+        public_key: env::var("DISCORD_PUBLIC_KEY").ok()?,
+        bot_token: env::var("DISCORD_BOT_TOKEN").ok()?,
+    })
+}
+
+// Prefers secrets passed through the environment, falling back to Secrets Manager
+pub async fn load_discord_secrets(config: &aws_config::SdkConfig) -> DiscordSecrets {
+    match get_discord_secrets_from_env() {
+        Some(x) => x,
+        None => {
+            let asm = aws_sdk_secretsmanager::Client::new(config);
+            get_discord_secrets(&asm, &env::var("SECRETS_ID").expect("no SECRETS_ID")).await
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::env;
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::Response;
+use axum::routing::post;
+use axum::Router;
+use lambda_http::tracing;
+use tokio::net::TcpListener;
+
+use crate::handle_interaction;
+use crate::models::binahbot::BinahBotEnvironment;
+
+static DEFAULT_BIND_ADDRESS: &str = "0.0.0.0:8080";
+// same path as the API Gateway endpoint, so only the host differs between deployments
+static EVENT_PATH: &str = "/event";
+
+// Serves interactions over plain HTTP, e.g. behind a reverse proxy on a VPS or
+// through a tunnel for local end-to-end testing
+pub async fn serve(binahbot_env: BinahBotEnvironment) -> Result<(), lambda_http::Error> {
+    let address = env::var("BIND_ADDRESS").unwrap_or(DEFAULT_BIND_ADDRESS.to_string());
+    let listener = TcpListener::bind(&address).await?;
+    tracing::info!("Listening on address={}", address);
+
+    axum::serve(listener, build_router(binahbot_env)).await?;
+    Ok(())
+}
+
+fn build_router(binahbot_env: BinahBotEnvironment) -> Router {
+    Router::new()
+        .route(EVENT_PATH, post(handle_event))
+        .with_state(Arc::new(binahbot_env))
+}
+
+async fn handle_event(
+    State(binahbot_env): State<Arc<BinahBotEnvironment>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response<String> {
+    let request_body = String::from_utf8_lossy(&body).into_owned();
+
+    match handle_interaction(&headers, request_body, &binahbot_env).await {
+        Ok(x) => x,
+        Err(e) => {
+            tracing::error!("Failed to handle interaction: {}", e);
+            Response::builder().status(500).body(String::new()).unwrap()
+        }
+    }
+}
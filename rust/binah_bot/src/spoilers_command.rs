@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::Loader;
+use ruina::ruina_common::game_objects::common::Chapter;
+use unic_langid::LanguageIdentifier;
+
+use crate::error::BinahBotError;
+use crate::guild_config::get_max_spoiler_chapter;
+use crate::macros::cast_enum_variant;
+use crate::models::binahbot::BinahBotEnvironment;
+use crate::models::binahbot::DiscordEmbedColors;
+use crate::models::config::ConfigScope;
+use crate::models::discord::AllowedMentions;
+use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionOptionValue;
+use crate::models::discord::DiscordInteractionResponseMessage;
+use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::DiscordPermission;
+use crate::models::discord::MessageResponse;
+use crate::utils::build_error_message_response;
+use crate::utils::get_binahbot_locale;
+use crate::utils::get_choice_display_name;
+use crate::utils::get_command_data;
+use crate::utils::get_option_value;
+use crate::utils::has_permission;
+
+const SHOW_ACTION: &str = "show";
+const SET_ACTION: &str = "set";
+
+// Shows or changes the latest chapter that can be shown in the current channel. Changes are
+// stored as the channel's config, the same setting `/config channel:` changes
+pub async fn spoilers_command(
+    interaction: &DiscordInteraction,
+    env: &BinahBotEnvironment,
+) -> Result<MessageResponse, BinahBotError> {
+    // every option is optional, and Discord leaves them out entirely when none are given
+    let command_args = get_command_data(interaction)?
+        .options
+        .as_deref()
+        .unwrap_or_default();
+
+    let lang_id = LanguageIdentifier::from(&get_binahbot_locale(interaction));
+
+    let action = get_option_value("action", command_args)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
+        .map(|x| x.as_str())
+        .unwrap_or(SHOW_ACTION);
+
+    if action == SHOW_ACTION {
+        let max_spoiler_chapter = get_max_spoiler_chapter(interaction, env).await;
+        return Ok(build_chapter_limit_response(
+            "spoilers_show",
+            max_spoiler_chapter.as_ref(),
+            &lang_id,
+            env,
+        ));
+    }
+
+    let (Some(_), Some(channel_id)) = (
+        interaction.guild_id.as_ref(),
+        interaction.channel_id.as_ref(),
+    ) else {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "spoilers_guild_only_error_message",
+            env,
+        ));
+    };
+
+    // permissions sent with the interaction are already resolved for this channel
+    if !has_permission(
+        interaction.member.as_ref(),
+        DiscordPermission::ManageChannels,
+    ) {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "spoilers_no_permission_error_message",
+            env,
+        ));
+    }
+
+    let chapter = match action {
+        SET_ACTION => {
+            let Some(chapter) = get_option_value("chapter", command_args)
+                .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
+                .and_then(|x| Chapter::from_str(x).ok())
+            else {
+                return Ok(build_error_message_response(
+                    &lang_id,
+                    "spoilers_missing_chapter_error_message",
+                    env,
+                ));
+            };
+            Some(chapter)
+        }
+        // choices are validated by Discord, so the only other action is a reset
+        _ => None,
+    };
+
+    let scope = ConfigScope::Channel(channel_id.clone());
+    let result = match env.config_repository.get_config(&scope).await {
+        Ok(mut config) => {
+            config.max_spoiler_chapter = chapter;
+            env.config_repository.put_config(&scope, &config).await
+        }
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        return Ok(build_error_message_response(
+            &lang_id,
+            "generic_error_message",
+            env,
+        ));
+    }
+
+    // a reset channel falls back to the server's setting, so the effective value is shown
+    let max_spoiler_chapter = get_max_spoiler_chapter(interaction, env).await;
+    Ok(build_chapter_limit_response(
+        "spoilers_updated",
+        max_spoiler_chapter.as_ref(),
+        &lang_id,
+        env,
+    ))
+}
+
+// Describes a chapter limit with `{key_prefix}_message`, or `{key_prefix}_unrestricted_message`
+// when there's none
+pub fn build_chapter_limit_response(
+    key_prefix: &str,
+    max_spoiler_chapter: Option<&Chapter>,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> MessageResponse {
+    let description = match max_spoiler_chapter {
+        Some(x) => env.locales.lookup_with_args(
+            lang_id,
+            &format!("{}_message", key_prefix),
+            &HashMap::from([(
+                "chapter",
+                FluentValue::from(get_choice_display_name(&x.to_string(), lang_id, env)),
+            )]),
+        ),
+        None => env
+            .locales
+            .lookup(lang_id, &format!("{}_unrestricted_message", key_prefix)),
+    };
+
+    MessageResponse {
+        r#type: DiscordInteractionResponseType::ChannelMessageWithSource,
+        data: Some(DiscordInteractionResponseMessage {
+            allowed_mentions: Some(AllowedMentions { parse: Vec::new() }),
+            content: None,
+            embeds: Some(vec![DiscordEmbed {
+                title: None,
+                description: Some(description),
+                color: Some(DiscordEmbedColors::Default as i32),
+                image: None,
+                thumbnail: None,
+                footer: None,
+                author: None,
+                url: None,
+                fields: None,
+            }]),
+            flags: Some(DiscordMessageFlag::EphemeralMessage as i32),
+            components: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::RESET_CHOICE;
+    use crate::models::config::GuildConfig;
+    use crate::models::discord::DiscordInteractionData;
+    use crate::test_utils::build_guild_command_interaction;
+    use crate::test_utils::build_mocked_binahbot_env;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn sanity_spoilers_command() {
+        let env = build_mocked_binahbot_env();
+        let lang_id = LanguageIdentifier::from(&get_binahbot_locale(
+            &build_guild_command_interaction("spoilers", "16", vec![]),
+        ));
+
+        let response = spoilers_command(
+            &build_guild_command_interaction(
+                "spoilers",
+                "16",
+                vec![("action", SET_ACTION), ("chapter", "UrbanLegend")],
+            ),
+            &env,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            format!(
+                "{:?}",
+                build_chapter_limit_response(
+                    "spoilers_updated",
+                    Some(&Chapter::UrbanLegend),
+                    &lang_id,
+                    &env
+                )
+            ),
+            format!("{:?}", response)
+        );
+
+        // anyone can check the current value
+        let mut interaction = build_guild_command_interaction("spoilers", "0", vec![]);
+        if let Some(DiscordInteractionData::ApplicationCommand(data)) = interaction.data.as_mut() {
+            data.options = None;
+        }
+        let response = spoilers_command(&interaction, &env).await.unwrap();
+        assert_eq!(
+            format!(
+                "{:?}",
+                build_chapter_limit_response(
+                    "spoilers_show",
+                    Some(&Chapter::UrbanLegend),
+                    &lang_id,
+                    &env
+                )
+            ),
+            format!("{:?}", response)
+        );
+
+        spoilers_command(
+            &build_guild_command_interaction("spoilers", "16", vec![("action", RESET_CHOICE)]),
+            &env,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            GuildConfig::default(),
+            env.config_repository
+                .get_config(&ConfigScope::Channel("channel".to_string()))
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_spoilers_command_no_permission() {
+        let env = build_mocked_binahbot_env();
+        let interaction = build_guild_command_interaction(
+            "spoilers",
+            "32",
+            vec![("action", SET_ACTION), ("chapter", "UrbanLegend")],
+        );
+        let lang_id = LanguageIdentifier::from(&get_binahbot_locale(&interaction));
+
+        let response = spoilers_command(&interaction, &env).await.unwrap();
+
+        assert_eq!(
+            format!(
+                "{:?}",
+                build_error_message_response(
+                    &lang_id,
+                    "spoilers_no_permission_error_message",
+                    &env
+                )
+            ),
+            format!("{:?}", response)
+        );
+    }
+}
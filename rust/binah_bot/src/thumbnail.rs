@@ -2,27 +2,105 @@ use std::error::Error;
 
 use aws_sdk_lambda::primitives::Blob;
 use aws_sdk_lambda::types::InvocationType;
+use ruina::ruina_common::localizations::common::Locale;
+use ruina::ruina_reparser::get_passive_locales_by_id;
 use serde::Serialize;
 use xxhash_rust::xxh3::xxh3_64;
 
+use crate::models::deck::Deck;
+
+// renders the combat page grid alongside the key page, passives and deck name
+const DECK_CARD_TEMPLATE: &str = "deck_card";
+
 // todo: figure out how to distribute this across packages without copy paste
 #[derive(Debug, Serialize)]
 struct ThumbnailLambdaInput {
     pub combat_pages: [String; 9],
     pub thumb_name: String,
+    pub template: &'static str,
+    pub deck_name: Option<String>,
+    pub keypage: Option<String>,
+    // localized here like card images, so the renderer doesn't need to know the reader's locale
+    pub passive_names: Vec<String>,
+}
+
+// todo: figure out how to distribute this across packages without copy paste
+#[derive(Debug, Serialize)]
+struct CardLambdaInput<'a> {
+    pub thumb_name: String,
+    pub card: &'a CardImage,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CardImage {
+    CombatPage(CombatPageCard),
+    KeyPage(Box<KeyPageCard>),
+}
+
+// all text is localized before it's sent for rendering
+#[derive(Debug, Serialize)]
+pub struct CombatPageCard {
+    pub artwork: Option<String>,
+    pub name: String,
+    pub cost: u8,
+    pub frame_color: u32,
+    pub description: String,
+    pub dice: Vec<CardDie>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CardDie {
+    pub die_type: String,
+    pub min: u16,
+    pub max: u16,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyPageCard {
+    pub id: String,
+    pub name: String,
+    pub frame_color: u32,
+    pub hp: u16,
+    pub stagger: u16,
+    pub min_speed: u8,
+    pub max_speed: u8,
+    pub hp_label: String,
+    pub stagger_label: String,
+    pub speed_label: String,
+    pub hp_resists: [CardResist; 3],
+    pub stagger_resists: [CardResist; 3],
+    pub passives_label: String,
+    pub passives: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CardResist {
+    pub resistance: String,
+    pub label: String,
+}
+
+// Skipped when no thumbnail generator is configured, e.g. when self-hosting without one
 pub async fn generate_thumbnail(
-    client: &aws_sdk_lambda::Client,
+    client: Option<&aws_sdk_lambda::Client>,
     function_name: &str,
-    combat_pages: &[Option<String>; 9],
+    deck: &Deck,
+    card_locale: &Locale,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
-    tracing::info!("Generating thumbnail with combat_pages={:?}", combat_pages);
-    let name = generate_thumb_name(combat_pages);
+    let Some(client) = client else {
+        return Ok(());
+    };
+    tracing::info!("Generating thumbnail with deck_data={:?}", deck.deck_data);
+    let name = generate_thumb_name(deck, card_locale);
 
     let blob = serde_json::to_string(&ThumbnailLambdaInput {
-        combat_pages: resolve_optional(combat_pages),
+        combat_pages: resolve_optional(&deck.deck_data.combat_page_ids),
         thumb_name: name,
+        template: DECK_CARD_TEMPLATE,
+        deck_name: Some(deck.name.clone()),
+        keypage: deck.deck_data.keypage_id.clone(),
+        passive_names: get_passive_names(&deck.deck_data.passive_ids, card_locale),
     })?;
 
     tracing::info!("Invoking generate thumbnail lambda with blob={}", blob);
@@ -38,11 +116,65 @@ pub async fn generate_thumbnail(
     Ok(())
 }
 
-pub fn generate_thumb_name(combat_pages: &[Option<String>; 9]) -> String {
-    format!(
+// The deck card shows localized passive names, so each locale gets its own thumbnail
+pub fn generate_thumb_name(deck: &Deck, card_locale: &Locale) -> String {
+    let components = [
+        DECK_CARD_TEMPLATE.to_string(),
+        deck.name.clone(),
+        deck.deck_data.keypage_id.clone().unwrap_or("0".to_string()),
+        deck.deck_data.passive_ids.join(","),
+        card_locale.to_string(),
+    ]
+    .into_iter()
+    .chain(resolve_optional(&deck.deck_data.combat_page_ids))
+    .collect::<Vec<_>>();
+
+    format!("{:X}", xxh3_64(components.join("#").as_bytes()))
+}
+
+// Rendering happens in the background, so the card is only in the bucket for later requests
+pub async fn generate_card_image(
+    client: &aws_sdk_lambda::Client,
+    function_name: &str,
+    thumb_name: &str,
+    card: &CardImage,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let blob = serde_json::to_string(&CardLambdaInput {
+        thumb_name: thumb_name.to_string(),
+        card,
+    })?;
+
+    tracing::info!("Invoking generate card image lambda with blob={}", blob);
+    let response = client
+        .invoke()
+        .function_name(function_name)
+        .invocation_type(InvocationType::Event)
+        .payload(Blob::new(blob.as_bytes()))
+        .send()
+        .await?;
+    tracing::info!("Received response status code={}", response.status_code());
+
+    Ok(())
+}
+
+// cards are named by their contents, so changed game data or translations render anew
+pub fn generate_card_name(card: &CardImage) -> Result<String, serde_json::Error> {
+    Ok(format!(
         "{:X}",
-        xxh3_64(resolve_optional(combat_pages).join("#").as_bytes())
-    )
+        xxh3_64(serde_json::to_string(card)?.as_bytes())
+    ))
+}
+
+fn get_passive_names(passive_ids: &[String], card_locale: &Locale) -> Vec<String> {
+    passive_ids
+        .iter()
+        .map(|x| {
+            get_passive_locales_by_id(x)
+                .get(card_locale)
+                .map(|y| y.name.to_string())
+                .unwrap_or(x.to_string())
+        })
+        .collect()
 }
 
 fn resolve_optional(combat_pages: &[Option<String>; 9]) -> [String; 9] {
@@ -57,22 +189,69 @@ fn resolve_optional(combat_pages: &[Option<String>; 9]) -> [String; 9] {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::deck::DeckData;
+    use crate::test_utils::build_deck;
+
+    fn build_deck_data(last_page: &str) -> DeckData {
+        DeckData {
+            keypage_id: Some("250023".to_string()),
+            passive_ids: vec!["230018".to_string()],
+            combat_page_ids: vec!["a", "b", "c", "d", "e", "f", "g", "h", last_page]
+                .into_iter()
+                .map(|x| Some(x.to_string()))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        }
+    }
 
     #[test]
     fn sanity_generate_thumb_name() {
-        let input = vec!["a", "b", "c", "d", "e", "f", "g", "h", "i"]
-            .into_iter()
-            .map(|x| Some(x.to_string()))
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap();
-        let input2 = vec!["a", "b", "c", "d", "e", "f", "g", "h", "j"]
-            .into_iter()
-            .map(|x| Some(x.to_string()))
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap();
-
-        assert_ne!(generate_thumb_name(&input), generate_thumb_name(&input2));
+        let input = build_deck("1", "Turbo Nikolai", build_deck_data("i"));
+        let input2 = build_deck("1", "Turbo Nikolai", build_deck_data("j"));
+        assert_ne!(
+            generate_thumb_name(&input, &Locale::English),
+            generate_thumb_name(&input2, &Locale::English)
+        );
+
+        let mut renamed = build_deck("1", "Turbo Nikolai", build_deck_data("i"));
+        renamed.name = "Turbo Nikolai 2".to_string();
+        assert_ne!(
+            generate_thumb_name(&input, &Locale::English),
+            generate_thumb_name(&renamed, &Locale::English)
+        );
+
+        let mut repassived = build_deck("1", "Turbo Nikolai", build_deck_data("i"));
+        repassived.deck_data.passive_ids.push("230019".to_string());
+        assert_ne!(
+            generate_thumb_name(&input, &Locale::English),
+            generate_thumb_name(&repassived, &Locale::English)
+        );
+        assert_ne!(
+            generate_thumb_name(&input, &Locale::English),
+            generate_thumb_name(&input, &Locale::Korean)
+        );
+    }
+
+    #[test]
+    fn generate_thumb_name_matches_prerender() {
+        // pinned to the name the thumbnail prerender CLI generates for the same deck, in English
+        let mut deck = build_deck("1", "Turbo Nikolai", build_deck_data("i"));
+        deck.deck_data.combat_page_ids = [
+            Some("608014"),
+            Some("608014"),
+            Some("608014"),
+            Some("608015"),
+            Some("608015"),
+            Some("608015"),
+            Some("608009"),
+            Some("608009"),
+            None,
+        ]
+        .map(|x| x.map(|y| y.to_string()));
+        assert_eq!(
+            "CE2779042F9A4C9",
+            generate_thumb_name(&deck, &Locale::English)
+        );
     }
 }
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 use fluent_templates::fluent_bundle::FluentValue;
 use fluent_templates::Loader;
@@ -15,20 +17,28 @@ use ruina::ruina_reparser::get_key_page_locales_by_text_id;
 use ruina::ruina_reparser::get_passive_locales_by_id;
 use unic_langid::LanguageIdentifier;
 
+use crate::error::BinahBotError;
+use crate::macros::cast_enum_variant;
 use crate::models::binahbot::BinahBotEnvironment;
 use crate::models::binahbot::BinahBotLocale;
 use crate::models::binahbot::DiscordEmbedColors;
 use crate::models::discord::AllowedMentions;
 use crate::models::discord::ButtonComponent;
 use crate::models::discord::ButtonStyle;
+use crate::models::discord::DiscordApplicationCommandInteractionData;
 use crate::models::discord::DiscordComponentType;
 use crate::models::discord::DiscordEmbed;
+use crate::models::discord::DiscordGuildMember;
 use crate::models::discord::DiscordInteraction;
+use crate::models::discord::DiscordInteractionData;
 use crate::models::discord::DiscordInteractionOptionValue;
 use crate::models::discord::DiscordInteractionOptions;
 use crate::models::discord::DiscordInteractionResponseMessage;
 use crate::models::discord::DiscordInteractionResponseType;
+use crate::models::discord::DiscordMessageComponentInteractionData;
 use crate::models::discord::DiscordMessageFlag;
+use crate::models::discord::DiscordPermission;
+use crate::models::discord::DiscordUser;
 use crate::models::discord::MessageResponse;
 
 // todo: where to put this?
@@ -44,6 +54,61 @@ pub fn get_option_value<'a>(
         .map(|x| &x.value)
 }
 
+pub fn get_required_string_option<'a>(
+    option_name: &'static str,
+    options: &'a [DiscordInteractionOptions],
+) -> Result<&'a String, BinahBotError> {
+    get_option_value(option_name, options)
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionOptionValue::String))
+        .ok_or(BinahBotError::MissingOption(option_name))
+}
+
+pub fn get_command_data(
+    interaction: &DiscordInteraction,
+) -> Result<&DiscordApplicationCommandInteractionData, BinahBotError> {
+    interaction
+        .data
+        .as_ref()
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::ApplicationCommand))
+        .ok_or(BinahBotError::MissingInteractionData)
+}
+
+pub fn get_command_options(
+    interaction: &DiscordInteraction,
+) -> Result<&[DiscordInteractionOptions], BinahBotError> {
+    get_command_data(interaction)?
+        .options
+        .as_deref()
+        .ok_or(BinahBotError::MissingInteractionData)
+}
+
+pub fn get_component_data(
+    interaction: &DiscordInteraction,
+) -> Result<&DiscordMessageComponentInteractionData, BinahBotError> {
+    interaction
+        .data
+        .as_ref()
+        .and_then(|x| cast_enum_variant!(x, DiscordInteractionData::MessageComponent))
+        .ok_or(BinahBotError::MissingInteractionData)
+}
+
+// guild interactions carry the user on `member`, DMs carry it on `user`
+pub fn get_interaction_user(
+    interaction: &DiscordInteraction,
+) -> Result<&DiscordUser, BinahBotError> {
+    interaction
+        .user
+        .as_ref()
+        .or_else(|| interaction.member.as_ref().and_then(|x| x.user.as_ref()))
+        .ok_or(BinahBotError::MissingUser)
+}
+
+pub fn get_reqwest_client(env: &BinahBotEnvironment) -> Result<&reqwest::Client, BinahBotError> {
+    env.reqwest_client
+        .as_ref()
+        .ok_or(BinahBotError::MissingHttpClient)
+}
+
 pub fn get_focused_option(
     options: &[DiscordInteractionOptions],
 ) -> Option<&DiscordInteractionOptions> {
@@ -124,11 +189,26 @@ pub fn get_display_name_locale(typed_id: &ParsedTypedId, locale: &Locale) -> Opt
     }
 }
 
+pub fn get_epoch_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("couldn't calculate epoch time")
+        .as_secs()
+}
+
+pub fn has_permission(member: Option<&DiscordGuildMember>, permission: DiscordPermission) -> bool {
+    member
+        .and_then(|x| x.permissions.as_ref())
+        .and_then(|x| x.parse::<u64>().ok())
+        .is_some_and(|x| x & permission as u64 != 0)
+}
+
+pub const TIPH_DECK_URL: &str = "https://tiphereth.zasz.su/u/decks";
+
 pub fn parse_tiph_deck_id(raw_input: &str) -> String {
-    let url = "https://tiphereth.zasz.su/u/decks";
     let mut ret_val: String = raw_input.to_string();
-    if ret_val.starts_with(url) {
-        ret_val = ret_val[url.len()..ret_val.len()].to_string();
+    if ret_val.starts_with(TIPH_DECK_URL) {
+        ret_val = ret_val[TIPH_DECK_URL.len()..ret_val.len()].to_string();
     }
     if ret_val.starts_with('/') {
         ret_val = ret_val[1..ret_val.len()].to_string();
@@ -166,6 +246,42 @@ pub fn build_error_message_response(
     }
 }
 
+pub fn build_error_message_response_with_args(
+    lang_id: &LanguageIdentifier,
+    err_code: &str,
+    args: &HashMap<&str, FluentValue>,
+    env: &BinahBotEnvironment,
+) -> MessageResponse {
+    let mut response = build_error_message_response(lang_id, err_code, env);
+    if let Some(embed) = response
+        .data
+        .as_mut()
+        .and_then(|x| x.embeds.as_mut())
+        .and_then(|x| x.first_mut())
+    {
+        embed.description = Some(env.locales.lookup_with_args(lang_id, err_code, args));
+    }
+    response
+}
+
+// Display name of a registered choice value, such as a chapter or locale. Values without a
+// display name are shown as is
+pub fn get_choice_display_name(
+    value: &str,
+    lang_id: &LanguageIdentifier,
+    env: &BinahBotEnvironment,
+) -> String {
+    let key = format!("command_choice_{}", value);
+    [lang_id, env.locales.fallback()]
+        .into_iter()
+        .find_map(|x| {
+            env.locales
+                .lookup_single_language::<&str>(x, &key, None)
+                .ok()
+        })
+        .unwrap_or(value.to_string())
+}
+
 pub fn build_delete_button_component(
     lang_id: &LanguageIdentifier,
     env: &BinahBotEnvironment,
@@ -236,6 +352,25 @@ mod tests {
         assert_eq!(options[1].name, get_focused_option(&options).unwrap().name);
     }
 
+    #[test]
+    fn sanity_get_required_string_option() {
+        let options = vec![DiscordInteractionOptions {
+            name: "private".to_string(),
+            name_localizations: None,
+            value: DiscordInteractionOptionValue::Bool(true),
+            focused: None,
+        }];
+
+        assert_eq!(
+            Err(BinahBotError::MissingOption("query")),
+            get_required_string_option("query", &options)
+        );
+        assert_eq!(
+            Err(BinahBotError::MissingOption("private")),
+            get_required_string_option("private", &options)
+        );
+    }
+
     #[test]
     fn sanity_parse_tiph_deck_id() {
         let inputs = vec![
@@ -253,6 +388,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn sanity_has_permission() {
+        let build_member = |permissions: Option<&str>| DiscordGuildMember {
+            user: None,
+            permissions: permissions.map(|x| x.to_string()),
+        };
+
+        assert!(has_permission(
+            Some(&build_member(Some("32"))),
+            DiscordPermission::ManageGuild
+        ));
+        assert!(has_permission(
+            Some(&build_member(Some("8589934624"))),
+            DiscordPermission::ManageGuild
+        ));
+        assert!(!has_permission(
+            Some(&build_member(Some("16"))),
+            DiscordPermission::ManageGuild
+        ));
+        assert!(!has_permission(
+            Some(&build_member(None)),
+            DiscordPermission::ManageGuild
+        ));
+        assert!(!has_permission(None, DiscordPermission::ManageGuild));
+    }
+
     #[test]
     #[ignore]
     fn check_no_lookup_display_collisions() {
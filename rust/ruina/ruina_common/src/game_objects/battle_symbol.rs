@@ -1,3 +1,5 @@
+use crate::game_objects::common::Chapter;
+
 #[derive(Debug, PartialEq, strum_macros::Display)]
 pub enum BattleSymbolSlot {
     Eye,
@@ -20,4 +22,5 @@ pub struct BattleSymbol<'a> {
     pub slot: BattleSymbolSlot,
     pub hidden: bool,
     pub count: Option<u8>,
+    pub chapter: Option<Chapter>,
 }
@@ -30,7 +30,9 @@ pub enum Floor {
     None,
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, strum_macros::Display)]
+#[derive(
+    Debug, Clone, PartialEq, PartialOrd, Eq, Ord, strum_macros::Display, strum_macros::EnumString,
+)]
 pub enum Chapter {
     Canard,
     UrbanMyth,
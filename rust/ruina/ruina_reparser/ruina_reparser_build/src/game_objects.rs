@@ -1,5 +1,6 @@
 pub mod abno_page;
 pub mod battle_symbol;
+pub mod chapter;
 pub mod combat_page;
 pub mod common;
 pub mod key_page;
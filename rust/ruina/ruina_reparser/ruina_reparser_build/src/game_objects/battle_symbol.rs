@@ -3,8 +3,11 @@ use std::collections::HashMap;
 use roxmltree::{Document, Node};
 use ruina_common::game_objects::battle_symbol::BattleSymbolSlot;
 
+use crate::game_objects::common::DerivedChapterMap;
 use crate::game_objects::common::ParserProps;
-use crate::serde::{display_serializer, serialize_option_2, string_literal_serializer};
+use crate::serde::{
+    chapter_enum_serializer, display_serializer, serialize_option_2, string_literal_serializer,
+};
 use crate::xml::{get_nodes, get_unique_node, get_unique_node_text};
 
 type BattleSymbolKey = String;
@@ -14,7 +17,9 @@ pub fn reserialize_battle_symbols(parser_props: &ParserProps) -> String {
     let battle_symbols: HashMap<_, _> = parser_props
         .document_strings
         .iter()
-        .flat_map(|document_string| process_battle_symbol_file(document_string))
+        .flat_map(|document_string| {
+            process_battle_symbol_file(document_string, parser_props.derived_chapter_map)
+        })
         .collect();
 
     let mut builder = phf_codegen::Map::new();
@@ -29,6 +34,7 @@ pub fn reserialize_battle_symbols(parser_props: &ParserProps) -> String {
 
 fn process_battle_symbol_file(
     document_string: &str,
+    derived_chapter_map: &DerivedChapterMap,
 ) -> HashMap<BattleSymbolKey, BattleSymbolValue> {
     let doc: Box<Document> = Box::new(Document::parse(document_string).unwrap());
     let xml_root_node = get_unique_node(doc.root(), "GiftXmlRoot").unwrap();
@@ -36,11 +42,14 @@ fn process_battle_symbol_file(
 
     battle_symbol_node_list
         .into_iter()
-        .map(|x| parse_battle_symbol(x))
+        .map(|x| parse_battle_symbol(x, derived_chapter_map))
         .collect()
 }
 
-fn parse_battle_symbol(battle_symbol_node: Node) -> (BattleSymbolKey, BattleSymbolValue) {
+fn parse_battle_symbol(
+    battle_symbol_node: Node,
+    derived_chapter_map: &DerivedChapterMap,
+) -> (BattleSymbolKey, BattleSymbolValue) {
     let id = battle_symbol_node.attribute("ID").unwrap();
     // Unsure why empty string results in exclusion from the generated XML tree,
     // possibly a limitation with XML library
@@ -60,6 +69,13 @@ fn parse_battle_symbol(battle_symbol_node: Node) -> (BattleSymbolKey, BattleSymb
         get_unique_node_text(battle_symbol_node, "Count"),
         display_serializer,
     );
+    let chapter = serialize_option_2(
+        derived_chapter_map
+            .battle_symbols
+            .get(internal_name)
+            .cloned(),
+        chapter_enum_serializer,
+    );
 
     (
         internal_name.to_string(),
@@ -70,7 +86,8 @@ fn parse_battle_symbol(battle_symbol_node: Node) -> (BattleSymbolKey, BattleSymb
         resource: {resource},
         slot: BattleSymbolSlot::{slot:?},
         hidden: {hidden},
-        count: {count}
+        count: {count},
+        chapter: {chapter},
     }}"
         ),
     )
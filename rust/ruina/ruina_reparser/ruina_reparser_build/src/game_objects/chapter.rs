@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use roxmltree::Document;
+use ruina_common::game_objects::common::Chapter;
+use ruina_common::game_objects::common::PageType;
+
+use crate::serde::get_chapter_from_str;
+use crate::xml::{get_nodes, get_nodes_text, get_unique_node, get_unique_node_text};
+
+use super::common::from_chapter_map;
+use super::common::BattleSymbolReceptionMap;
+use super::common::ChapterMap;
+use super::common::ChapterOverrideMap;
+use super::common::CollectabilityMap;
+use super::common::DerivedChapterMap;
+
+pub struct ChapterDerivationProps<'a> {
+    pub key_page_document_strings: Vec<String>,
+    pub enemy_unit_document_strings: Vec<String>,
+    pub stage_document_strings: Vec<String>,
+    pub collectability_map: &'a CollectabilityMap,
+    pub chapter_map: &'a ChapterMap,
+    pub reception_map: &'a BattleSymbolReceptionMap,
+    pub override_map: &'a ChapterOverrideMap,
+}
+
+// Passives take the earliest chapter among the key pages that grant them, and battle symbols the
+// earliest chapter among the receptions that grant them. Overrides take precedence over both
+pub fn derive_chapter_map(props: &ChapterDerivationProps) -> DerivedChapterMap {
+    let stage_chapters = parse_stage_chapters(&props.stage_document_strings);
+
+    let mut passives = derive_passive_chapters(props, &stage_chapters);
+    passives.extend(parse_chapter_overrides(&props.override_map.passives));
+
+    let mut battle_symbols = derive_battle_symbol_chapters(props.reception_map, &stage_chapters);
+    battle_symbols.extend(parse_chapter_overrides(&props.override_map.battle_symbols));
+
+    DerivedChapterMap {
+        passives,
+        battle_symbols,
+    }
+}
+
+// Stage id to its chapter and the ids of the enemies in its waves. Abnormality stages have no
+// chapter and are left out
+fn parse_stage_chapters(
+    stage_document_strings: &[String],
+) -> HashMap<String, (Chapter, Vec<String>)> {
+    let mut stage_chapters = HashMap::new();
+
+    for document_string in stage_document_strings {
+        let doc: Box<Document> = Box::new(Document::parse(document_string).unwrap());
+        let xml_root_node = get_unique_node(doc.root(), "StageXmlRoot").unwrap();
+
+        for stage_node in get_nodes(xml_root_node, "Stage") {
+            let Some(chapter) =
+                get_unique_node_text(stage_node, "Chapter").and_then(get_chapter_from_str)
+            else {
+                continue;
+            };
+            let enemy_ids = get_nodes_text(stage_node, "Unit")
+                .into_iter()
+                .map(|x| x.to_string())
+                .collect();
+            stage_chapters.insert(
+                stage_node.attribute("id").unwrap().to_string(),
+                (chapter, enemy_ids),
+            );
+        }
+    }
+
+    stage_chapters
+}
+
+fn derive_passive_chapters(
+    props: &ChapterDerivationProps,
+    stage_chapters: &HashMap<String, (Chapter, Vec<String>)>,
+) -> HashMap<String, Chapter> {
+    let enemy_key_page_ids = parse_enemy_key_page_ids(&props.enemy_unit_document_strings);
+
+    // Enemy key pages are dated by the receptions they show up in, since the chapter map
+    // doesn't place them reliably
+    let mut key_page_chapters: HashMap<&str, Chapter> = HashMap::new();
+    for (chapter, enemy_ids) in stage_chapters.values() {
+        for key_page_id in enemy_ids.iter().filter_map(|x| enemy_key_page_ids.get(x)) {
+            insert_earliest(&mut key_page_chapters, key_page_id.as_str(), chapter);
+        }
+    }
+    for key_page_id in props.collectability_map.collectable.key_pages.iter() {
+        if let Some(chapter) = from_chapter_map(key_page_id, &PageType::KeyPage, props.chapter_map)
+        {
+            insert_earliest(&mut key_page_chapters, key_page_id.as_str(), &chapter);
+        }
+    }
+
+    let mut passives = HashMap::new();
+    for document_string in props.key_page_document_strings.iter() {
+        let doc: Box<Document> = Box::new(Document::parse(document_string).unwrap());
+        let xml_root_node = get_unique_node(doc.root(), "BookXmlRoot").unwrap();
+
+        for key_node in get_nodes(xml_root_node, "Book") {
+            let id = key_node.attribute("ID").unwrap();
+            let Some(chapter) = key_page_chapters.get(id) else {
+                continue;
+            };
+            let equip_effect_node = get_unique_node(key_node, "EquipEffect").unwrap();
+
+            for passive_id in get_nodes_text(equip_effect_node, "Passive") {
+                insert_earliest(&mut passives, passive_id.to_string(), chapter);
+            }
+        }
+    }
+
+    passives
+}
+
+// Enemy id to the id of the key page it uses
+fn parse_enemy_key_page_ids(enemy_unit_document_strings: &[String]) -> HashMap<String, String> {
+    let mut enemy_key_page_ids = HashMap::new();
+
+    for document_string in enemy_unit_document_strings {
+        let doc: Box<Document> = Box::new(Document::parse(document_string).unwrap());
+        let xml_root_node = get_unique_node(doc.root(), "EnemyUnitClassRoot").unwrap();
+
+        for enemy_node in get_nodes(xml_root_node, "Enemy") {
+            if let Some(key_page_id) = get_unique_node_text(enemy_node, "BookId") {
+                enemy_key_page_ids.insert(
+                    enemy_node.attribute("ID").unwrap().to_string(),
+                    key_page_id.to_string(),
+                );
+            }
+        }
+    }
+
+    enemy_key_page_ids
+}
+
+fn derive_battle_symbol_chapters(
+    reception_map: &BattleSymbolReceptionMap,
+    stage_chapters: &HashMap<String, (Chapter, Vec<String>)>,
+) -> HashMap<String, Chapter> {
+    reception_map
+        .iter()
+        .filter_map(|(internal_name, stage_ids)| {
+            let chapter = stage_ids
+                .iter()
+                .map(|x| match stage_chapters.get(x) {
+                    Some((chapter, _)) => chapter,
+                    None => panic!(
+                        "[reparser] unknown stage {} for battle symbol {}",
+                        x, internal_name
+                    ),
+                })
+                .min()?;
+            Some((internal_name.clone(), chapter.clone()))
+        })
+        .collect()
+}
+
+fn parse_chapter_overrides(overrides: &HashMap<String, String>) -> HashMap<String, Chapter> {
+    overrides
+        .iter()
+        .map(|(id, chapter)| {
+            let chapter = Chapter::from_str(chapter).unwrap_or_else(|_| {
+                panic!(
+                    "[reparser] unexpected chapter override for {}: {}",
+                    id, chapter
+                )
+            });
+            (id.clone(), chapter)
+        })
+        .collect()
+}
+
+fn insert_earliest<K>(map: &mut HashMap<K, Chapter>, key: K, chapter: &Chapter)
+where
+    K: std::hash::Hash + Eq,
+{
+    map.entry(key)
+        .and_modify(|x| {
+            if chapter < x {
+                *x = chapter.clone()
+            }
+        })
+        .or_insert_with(|| chapter.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::CollectableMap;
+    use super::super::common::EnemyOnlyMap;
+    use super::super::common::ObtainableMap;
+    use super::super::common::SingleChapterMap;
+    use super::*;
+
+    // key page 3 is only used by enemy 10, who shows up in stage 20
+    const KEY_PAGES: &str = r#"<BookXmlRoot>
+        <Book ID="1"><EquipEffect><Passive>100</Passive></EquipEffect></Book>
+        <Book ID="2"><EquipEffect><Passive>100</Passive><Passive>200</Passive></EquipEffect></Book>
+        <Book ID="3"><EquipEffect><Passive>300</Passive></EquipEffect></Book>
+    </BookXmlRoot>"#;
+    const ENEMY_UNITS: &str = r#"<EnemyUnitClassRoot>
+        <Enemy ID="10"><BookId>3</BookId></Enemy>
+    </EnemyUnitClassRoot>"#;
+    const STAGES: &str = r#"<StageXmlRoot>
+        <Stage id="20"><Wave><Unit>10</Unit></Wave><Chapter>5</Chapter></Stage>
+        <Stage id="21"><Chapter>2</Chapter></Stage>
+    </StageXmlRoot>"#;
+
+    #[test]
+    fn should_take_earliest_key_page_chapter_for_passive() {
+        let derived = derive(HashMap::new());
+
+        // granted by key page 1 in urban legend and key page 2 in canard
+        assert_eq!(Some(&Chapter::Canard), derived.passives.get("100"));
+        assert_eq!(Some(&Chapter::Canard), derived.passives.get("200"));
+    }
+
+    #[test]
+    fn should_date_enemy_key_page_by_reception() {
+        let derived = derive(HashMap::new());
+
+        // chapter.toml places key page 3 in canard, but its reception is in urban nightmare
+        assert_eq!(Some(&Chapter::UrbanNightmare), derived.passives.get("300"));
+    }
+
+    #[test]
+    fn should_prefer_chapter_override() {
+        let derived = derive(HashMap::from([(
+            "100".to_string(),
+            "ImpuritasCivitatis".to_string(),
+        )]));
+
+        assert_eq!(
+            Some(&Chapter::ImpuritasCivitatis),
+            derived.passives.get("100")
+        );
+        assert_eq!(Some(&Chapter::Canard), derived.passives.get("200"));
+        assert_eq!(
+            Some(&Chapter::StarOfTheCity),
+            derived.battle_symbols.get("Overridden")
+        );
+    }
+
+    #[test]
+    fn should_only_derive_battle_symbols_with_receptions() {
+        let derived = derive(HashMap::new());
+
+        assert_eq!(
+            Some(&Chapter::UrbanMyth),
+            derived.battle_symbols.get("Symbol")
+        );
+        assert_eq!(None, derived.battle_symbols.get("Unlisted"));
+    }
+
+    fn derive(passive_overrides: HashMap<String, String>) -> DerivedChapterMap {
+        let collectability_map = CollectabilityMap {
+            collectable: CollectableMap {
+                combat_pages: Vec::new(),
+                key_pages: vec!["1".to_string(), "2".to_string()],
+                passives: Vec::new(),
+            },
+            obtainable: ObtainableMap {
+                combat_pages: Vec::new(),
+            },
+            enemy_only: EnemyOnlyMap {
+                combat_pages: Vec::new(),
+                key_pages: vec!["3".to_string()],
+            },
+        };
+        let chapter_map = ChapterMap {
+            unranked: build_single_chapter_map(&[]),
+            canard: build_single_chapter_map(&["2", "3"]),
+            urban_myth: build_single_chapter_map(&[]),
+            urban_legend: build_single_chapter_map(&["1"]),
+            urban_plague: build_single_chapter_map(&[]),
+            urban_nightmare: build_single_chapter_map(&[]),
+            star_of_the_city: build_single_chapter_map(&[]),
+            impuritas_civitatis: build_single_chapter_map(&[]),
+        };
+        let reception_map = HashMap::from([(
+            "Symbol".to_string(),
+            vec!["20".to_string(), "21".to_string()],
+        )]);
+        let override_map = ChapterOverrideMap {
+            passives: passive_overrides,
+            battle_symbols: HashMap::from([(
+                "Overridden".to_string(),
+                "StarOfTheCity".to_string(),
+            )]),
+        };
+
+        derive_chapter_map(&ChapterDerivationProps {
+            key_page_document_strings: vec![KEY_PAGES.to_string()],
+            enemy_unit_document_strings: vec![ENEMY_UNITS.to_string()],
+            stage_document_strings: vec![STAGES.to_string()],
+            collectability_map: &collectability_map,
+            chapter_map: &chapter_map,
+            reception_map: &reception_map,
+            override_map: &override_map,
+        })
+    }
+
+    fn build_single_chapter_map(key_pages: &[&str]) -> SingleChapterMap {
+        SingleChapterMap {
+            combat_pages: Vec::new(),
+            key_pages: key_pages.iter().map(|x| x.to_string()).collect(),
+            passives: Vec::new(),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use ruina_common::game_objects::common::Chapter;
 use ruina_common::game_objects::common::PageType;
 use serde::Deserialize;
@@ -46,11 +48,29 @@ pub struct SingleChapterMap {
     pub passives: Vec<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ChapterOverrideMap {
+    pub passives: HashMap<String, String>,
+    pub battle_symbols: HashMap<String, String>,
+}
+
+// Battle symbol internal name to the ids of the stages that grant it
+pub type BattleSymbolReceptionMap = HashMap<String, Vec<String>>;
+
+// Chapters that aren't read directly off a single game object, keyed by passive id and by
+// battle symbol internal name
+#[derive(Debug)]
+pub struct DerivedChapterMap {
+    pub passives: HashMap<String, Chapter>,
+    pub battle_symbols: HashMap<String, Chapter>,
+}
+
 #[derive(Debug)]
 pub struct ParserProps<'a> {
     pub document_strings: Vec<String>,
     pub collectability_map: &'a CollectabilityMap,
     pub chapter_map: &'a ChapterMap,
+    pub derived_chapter_map: &'a DerivedChapterMap,
 }
 
 pub fn from_chapter_map<'a>(id: &str, pagetype: &PageType, map: &'a ChapterMap) -> Option<Chapter> {
@@ -2,7 +2,6 @@ use std::collections::HashMap;
 
 use roxmltree::{Document, Node};
 use ruina_common::game_objects::common::Collectability;
-use ruina_common::game_objects::common::PageType;
 
 use crate::game_objects::common::CollectabilityMap;
 use crate::game_objects::common::ParserProps;
@@ -12,8 +11,7 @@ use crate::serde::{
 };
 use crate::xml::{get_nodes, get_unique_node, get_unique_node_text};
 
-use super::common::from_chapter_map;
-use super::common::ChapterMap;
+use super::common::DerivedChapterMap;
 
 type PassiveKey = String;
 type PassiveValue = String;
@@ -26,7 +24,7 @@ pub fn reserialize_passives(parser_props: &ParserProps) -> String {
             process_passive_file(
                 document_string.as_str(),
                 parser_props.collectability_map,
-                parser_props.chapter_map,
+                parser_props.derived_chapter_map,
             )
         })
         .collect();
@@ -44,7 +42,7 @@ pub fn reserialize_passives(parser_props: &ParserProps) -> String {
 fn process_passive_file(
     document_string: &str,
     collectability_map: &CollectabilityMap,
-    chapter_map: &ChapterMap,
+    derived_chapter_map: &DerivedChapterMap,
 ) -> HashMap<PassiveKey, PassiveValue> {
     let doc: Box<Document> = Box::new(Document::parse(document_string).unwrap());
     let xml_root_node = get_unique_node(doc.root(), "PassiveXmlRoot").unwrap();
@@ -78,11 +76,11 @@ fn process_passive_file(
 
     let passives_in_xml = passive_node_list
         .iter()
-        .map(|x| parse_passive(*x, collectability_map, chapter_map));
+        .map(|x| parse_passive(*x, collectability_map, derived_chapter_map));
 
     let missing_passives = missing_passives
         .iter()
-        .map(|x| default_passive_settings(x, collectability_map, chapter_map));
+        .map(|x| default_passive_settings(x, collectability_map, derived_chapter_map));
 
     passives_in_xml.chain(missing_passives).collect()
 }
@@ -90,7 +88,7 @@ fn process_passive_file(
 fn parse_passive(
     passive_node: Node,
     collectability_map: &CollectabilityMap,
-    chapter_map: &ChapterMap,
+    derived_chapter_map: &DerivedChapterMap,
 ) -> (PassiveKey, PassiveValue) {
     let id = passive_node.attribute("ID").unwrap();
     let cost = serialize_option_2(
@@ -115,7 +113,7 @@ fn parse_passive(
     );
 
     let chapter = serialize_option_2(
-        from_chapter_map(&id, &PageType::Passive, chapter_map),
+        derived_chapter_map.passives.get(id).cloned(),
         chapter_enum_serializer,
     );
 
@@ -151,10 +149,10 @@ fn parse_passive(
 fn default_passive_settings(
     id: &str,
     collectability_map: &CollectabilityMap,
-    chapter_map: &ChapterMap,
+    derived_chapter_map: &DerivedChapterMap,
 ) -> (PassiveKey, PassiveValue) {
     let chapter = serialize_option_2(
-        from_chapter_map(&id, &PageType::Passive, chapter_map),
+        derived_chapter_map.passives.get(id).cloned(),
         chapter_enum_serializer,
     );
 
@@ -1,7 +1,12 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use game_objects::chapter::derive_chapter_map;
+use game_objects::chapter::ChapterDerivationProps;
+use game_objects::common::BattleSymbolReceptionMap;
 use game_objects::common::ChapterMap;
+use game_objects::common::ChapterOverrideMap;
+use game_objects::common::DerivedChapterMap;
 use paths::BATTLE_SYMBOL_LOCALIZE_DIR;
 use paths::CARD_EFFECT_LOCALIZE_DIR;
 use paths::COMBAT_PAGE_LOCALIZE_DIR;
@@ -30,10 +35,12 @@ use paths::ABNO_LOCALIZE_DIR;
 use paths::ABNO_PAGE_PATH_STR;
 use paths::BATTLE_SYMBOL_PATH_STR;
 use paths::COMBAT_PAGE_PATH_STR;
+use paths::ENEMY_UNIT_PATH_STR;
 use paths::KEY_PAGE_PATH_STR;
 use paths::LOCALE_PAGE_PATHS;
 use paths::MOST_PATHS;
 use paths::PASSIVE_PATH_STR;
+use paths::STAGE_PATH_STR;
 
 mod game_objects;
 mod localization;
@@ -70,34 +77,56 @@ pub fn build_reparser() -> String {
     let chapter_toml_str = include_str!("../data/chapter.toml");
     let chapter_toml_map: ChapterMap = from_str(chapter_toml_str).unwrap();
 
+    let reception_toml_str = include_str!("../data/battle_symbol_receptions.toml");
+    let reception_toml_map: BattleSymbolReceptionMap = from_str(reception_toml_str).unwrap();
+
+    let chapter_override_toml_str = include_str!("../data/chapter_override.toml");
+    let chapter_override_toml_map: ChapterOverrideMap =
+        from_str(chapter_override_toml_str).unwrap();
+
+    let derived_chapter_map = derive_chapter_map(&ChapterDerivationProps {
+        key_page_document_strings: read_document_strings(KEY_PAGE_PATH_STR),
+        enemy_unit_document_strings: read_document_strings(ENEMY_UNIT_PATH_STR),
+        stage_document_strings: read_document_strings(STAGE_PATH_STR),
+        collectability_map: &collectability_toml_map,
+        chapter_map: &chapter_toml_map,
+        reception_map: &reception_toml_map,
+        override_map: &chapter_override_toml_map,
+    });
+
     let abno_pages = reparse(
         ABNO_PAGE_PATH_STR,
         &collectability_toml_map,
         &chapter_toml_map,
+        &derived_chapter_map,
         reserialize_abno_pages,
     );
     let battle_symbols = reparse(
         BATTLE_SYMBOL_PATH_STR,
         &collectability_toml_map,
         &chapter_toml_map,
+        &derived_chapter_map,
         reserialize_battle_symbols,
     );
     let combat_pages = reparse(
         COMBAT_PAGE_PATH_STR,
         &collectability_toml_map,
         &chapter_toml_map,
+        &derived_chapter_map,
         reserialize_combat_pages,
     );
     let key_pages = reparse(
         KEY_PAGE_PATH_STR,
         &collectability_toml_map,
         &chapter_toml_map,
+        &derived_chapter_map,
         reserialize_key_pages,
     );
     let passives = reparse(
         PASSIVE_PATH_STR,
         &collectability_toml_map,
         &chapter_toml_map,
+        &derived_chapter_map,
         reserialize_passives,
     );
 
@@ -133,20 +162,26 @@ fn reparse(
     path_str: &str,
     collectability_map: &CollectabilityMap,
     chapter_map: &ChapterMap,
+    derived_chapter_map: &DerivedChapterMap,
     reserializer: fn(&ParserProps) -> String,
 ) -> String {
     let parser_props = ParserProps {
-        document_strings: read_xml_files_in_dir(&PathBuf::from(path_str))
-            .into_iter()
-            .map(|x| x.1)
-            .collect::<Vec<_>>(),
+        document_strings: read_document_strings(path_str),
         collectability_map,
         chapter_map,
+        derived_chapter_map,
     };
 
     reserializer(&parser_props)
 }
 
+fn read_document_strings(path_str: &str) -> Vec<String> {
+    read_xml_files_in_dir(&PathBuf::from(path_str))
+        .into_iter()
+        .map(|x| x.1)
+        .collect::<Vec<_>>()
+}
+
 fn reparse_locale(
     dir_str: &str,
     reserializer: fn(&HashMap<Locale, Vec<String>>) -> String,
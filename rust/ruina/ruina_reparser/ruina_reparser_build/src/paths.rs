@@ -10,8 +10,10 @@ pub static BASEMOD_LOCALIZE: &str = "./BaseMod/Localize";
 pub static ABNO_PAGE_PATH_STR: &str = "./BaseMod/StaticInfo/EmotionCard";
 pub static BATTLE_SYMBOL_PATH_STR: &str = "./BaseMod/StaticInfo/GiftInfo";
 pub static COMBAT_PAGE_PATH_STR: &str = "./BaseMod/StaticInfo/Card";
+pub static ENEMY_UNIT_PATH_STR: &str = "./BaseMod/StaticInfo/EnemyUnitInfo";
 pub static KEY_PAGE_PATH_STR: &str = "./BaseMod/StaticInfo/EquipPage";
 pub static PASSIVE_PATH_STR: &str = "./BaseMod/StaticInfo/PassiveList";
+pub static STAGE_PATH_STR: &str = "./BaseMod/StaticInfo/StageInfo";
 
 pub static EN_LOCALE_PATH_STR: &str = "./BaseMod/Localize/en";
 pub static KR_LOCALE_PATH_STR: &str = "./BaseMod/Localize/kr";
@@ -32,8 +34,10 @@ pub static MOST_PATHS: &[&str] = &[
     ABNO_PAGE_PATH_STR,
     BATTLE_SYMBOL_PATH_STR,
     COMBAT_PAGE_PATH_STR,
+    ENEMY_UNIT_PATH_STR,
     KEY_PAGE_PATH_STR,
     PASSIVE_PATH_STR,
+    STAGE_PATH_STR,
     EN_LOCALE_PATH_STR,
     KR_LOCALE_PATH_STR,
     JP_LOCALE_PATH_STR,
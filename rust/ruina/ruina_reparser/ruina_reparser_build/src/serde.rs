@@ -12,7 +12,7 @@ pub fn get_rarity_from_str(str: &str) -> Rarity {
     }
 }
 
-pub fn _get_chapter_from_str(str: &str) -> Option<Chapter> {
+pub fn get_chapter_from_str(str: &str) -> Option<Chapter> {
     Some(match str {
         "1" => Chapter::Canard,
         "2" => Chapter::UrbanMyth,
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::sync::Mutex;
+
+use futures::stream;
+use futures::StreamExt;
+use image::DynamicImage;
+
+use crate::decode_png;
+use crate::encode_png;
+use crate::models::ThumbnailGeneratorEnvironment;
+use crate::storage::ThumbnailStorage;
+use crate::BASE_COMBAT_PAGE_HEIGHT;
+use crate::BASE_COMBAT_PAGE_WIDTH;
+
+// Grid tiles, i.e. artwork already resized for draw_grid, kept in memory for as
+// long as the environment lives, so a warm lambda reuses them across invocations.
+// The oldest tile is evicted once the cache is full
+pub struct ArtworkCache {
+    capacity: usize,
+    tiles: Mutex<CachedTiles>,
+}
+
+#[derive(Default)]
+struct CachedTiles {
+    images: HashMap<String, DynamicImage>,
+    insertion_order: VecDeque<String>,
+}
+
+impl ArtworkCache {
+    pub fn new(capacity: usize) -> Self {
+        ArtworkCache {
+            capacity,
+            tiles: Mutex::new(CachedTiles::default()),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<DynamicImage> {
+        self.tiles.lock().unwrap().images.get(name).cloned()
+    }
+
+    fn insert(&self, name: &str, image: DynamicImage) {
+        let mut tiles = self.tiles.lock().unwrap();
+        if tiles.images.insert(name.to_string(), image).is_some() {
+            return;
+        }
+        tiles.insertion_order.push_back(name.to_string());
+        while tiles.insertion_order.len() > self.capacity {
+            if let Some(oldest) = tiles.insertion_order.pop_front() {
+                tiles.images.remove(&oldest);
+            }
+        }
+    }
+}
+
+// Fetches each distinct artwork once and returns a tile per name, in order.
+// Tiles are looked up in memory, then in the pre-resized tier in storage, and
+// only then resized from the original artwork
+pub async fn get_grid_tiles<S: ThumbnailStorage>(
+    image_names: &[String],
+    env: &ThumbnailGeneratorEnvironment<S>,
+) -> Result<Vec<DynamicImage>, Box<dyn Error + Send + Sync>> {
+    let mut unique_names = image_names.iter().collect::<Vec<_>>();
+    unique_names.sort();
+    unique_names.dedup();
+
+    tracing::info!("Grabbing grid tiles: {:?}", unique_names);
+
+    let tiles = stream::iter(unique_names)
+        .map(|x| async move { get_grid_tile(x, env).await.map(|y| (x, y)) })
+        .buffered(9)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    Ok(image_names.iter().map(|x| tiles[x].clone()).collect())
+}
+
+async fn get_grid_tile<S: ThumbnailStorage>(
+    image_name: &str,
+    env: &ThumbnailGeneratorEnvironment<S>,
+) -> Result<DynamicImage, Box<dyn Error + Send + Sync>> {
+    if let Some(tile) = env.artwork_cache.get(image_name) {
+        return Ok(tile);
+    }
+
+    // sized directories keep old tiles from being served if the grid is ever resized
+    let tile_key = format!(
+        "{}/{}x{}/{}.png",
+        env.artwork_tile_directory, BASE_COMBAT_PAGE_WIDTH, BASE_COMBAT_PAGE_HEIGHT, image_name
+    );
+    let tile = match env.storage.get(&tile_key).await {
+        Ok(x) => decode_png(x)?,
+        Err(_) => {
+            let original = decode_png(env.storage.get(&format!("{}.png", image_name)).await?)?;
+            let tile = tokio::task::spawn_blocking(move || {
+                original.resize_exact(
+                    BASE_COMBAT_PAGE_WIDTH,
+                    BASE_COMBAT_PAGE_HEIGHT,
+                    image::imageops::FilterType::Lanczos3,
+                )
+            })
+            .await?;
+
+            // a failed write only costs another resize next time
+            if let Err(e) = env
+                .storage
+                .put(&tile_key, encode_png(&tile.to_rgba8())?)
+                .await
+            {
+                tracing::warn!("Failed to store grid tile {}: {}", tile_key, e);
+            }
+            tile
+        }
+    };
+
+    env.artwork_cache.insert(image_name, tile.clone());
+    Ok(tile)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::GenericImageView;
+    use image::Rgba;
+    use image::RgbaImage;
+
+    use super::*;
+    use crate::storage::LocalStorage;
+
+    fn build_image(color: Rgba<u8>) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 3, color))
+    }
+
+    #[test]
+    fn sanity_artwork_cache_eviction() {
+        let cache = ArtworkCache::new(2);
+        cache.insert("a", build_image(Rgba([1, 0, 0, 255])));
+        cache.insert("b", build_image(Rgba([2, 0, 0, 255])));
+        cache.insert("a", build_image(Rgba([3, 0, 0, 255])));
+        cache.insert("c", build_image(Rgba([4, 0, 0, 255])));
+
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b").is_some());
+        assert!(cache.get("c").is_some());
+    }
+
+    #[tokio::test]
+    async fn sanity_get_grid_tiles() {
+        let root = std::env::temp_dir().join(format!("thumbnail_tiles_{}", std::process::id()));
+        let build_env = || ThumbnailGeneratorEnvironment {
+            thumbnail_directory: "deck_thumbnails".to_string(),
+            card_directory: "card_images".to_string(),
+            artwork_tile_directory: "artwork_tiles".to_string(),
+            font_name: "fonts/thumbnail.ttf".to_string(),
+            artwork_cache: ArtworkCache::new(16),
+            storage: LocalStorage { root: root.clone() },
+        };
+        let env = build_env();
+        let blue = Rgba([0, 0, 255, 255]);
+        env.storage
+            .put("a.png", encode_png(&build_image(blue).to_rgba8()).unwrap())
+            .await
+            .unwrap();
+
+        let names = ["a", "a", "a"].map(|x| x.to_string());
+        let tiles = get_grid_tiles(&names, &env).await.unwrap();
+        assert_eq!(3, tiles.len());
+        assert_eq!(
+            (BASE_COMBAT_PAGE_WIDTH, BASE_COMBAT_PAGE_HEIGHT),
+            tiles[2].dimensions()
+        );
+
+        // without the original, tiles come from memory, then from the pre-resized tier
+        std::fs::remove_file(root.join("a.png")).unwrap();
+        assert!(get_grid_tiles(&names, &env).await.is_ok());
+        let tiles = get_grid_tiles(&names[..1], &build_env()).await;
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(blue, tiles.unwrap()[0].get_pixel(1, 1));
+    }
+}
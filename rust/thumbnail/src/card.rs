@@ -0,0 +1,572 @@
+use std::error::Error;
+
+use ab_glyph::FontVec;
+use image::DynamicImage;
+use image::GenericImage;
+use image::Rgba;
+use image::RgbaImage;
+use imageproc::drawing::draw_filled_circle_mut;
+use imageproc::drawing::draw_filled_rect_mut;
+use imageproc::drawing::draw_hollow_rect_mut;
+use imageproc::drawing::draw_text_mut;
+use imageproc::drawing::text_size;
+use imageproc::rect::Rect;
+
+use crate::encode_png;
+use crate::fit_text;
+use crate::get_images;
+use crate::models::CardImage;
+use crate::models::CardLambdaInput;
+use crate::models::CombatPageCard;
+use crate::models::KeyPageCard;
+use crate::models::ThumbnailGeneratorEnvironment;
+use crate::storage::ThumbnailStorage;
+use crate::BASE_COMBAT_PAGE_HEIGHT;
+use crate::BASE_COMBAT_PAGE_WIDTH;
+use crate::CARD_BACKGROUND_COLOR;
+use crate::CARD_TEXT_COLOR;
+use crate::KEY_PAGE_DIRECTORY;
+use crate::NOT_FOUND_IMAGE_NAME;
+
+static CARD_WIDTH: u32 = 560;
+static CARD_MARGIN: u32 = 24;
+static CARD_FRAME_WIDTH: u32 = 8;
+static CARD_HEADER_HEIGHT: u32 = 88;
+static CARD_NAME_SCALE: f32 = 36.0;
+static CARD_COST_RADIUS: u32 = 28;
+static CARD_COST_SCALE: f32 = 40.0;
+static CARD_BODY_SCALE: f32 = 24.0;
+static CARD_BODY_LINE_HEIGHT: u32 = 30;
+static CARD_DIE_BADGE_WIDTH: u32 = 96;
+static CARD_DIE_BADGE_HEIGHT: u32 = 30;
+static CARD_DIE_LABEL_SCALE: f32 = 20.0;
+static CARD_DIE_RANGE_SCALE: f32 = 28.0;
+// badge and range, after which the die description starts
+static CARD_DIE_COLUMN_WIDTH: u32 = 190;
+static CARD_DIE_GAP: u32 = 12;
+static CARD_COUNTER_OUTLINE_COLOR: Rgba<u8> = Rgba([235, 190, 0, 255]);
+static KEY_PAGE_PORTRAIT_HEIGHT: u32 = 320;
+static KEY_PAGE_STAT_HEIGHT: u32 = 72;
+// row labels of the resist grid, after which the slash, pierce and blunt cells start
+static KEY_PAGE_RESIST_LABEL_WIDTH: u32 = 128;
+static KEY_PAGE_RESIST_CELL_HEIGHT: u32 = 36;
+
+pub async fn card_handler<S: ThumbnailStorage>(
+    event: CardLambdaInput,
+    env: &ThumbnailGeneratorEnvironment<S>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let card_key = format!("{}/{}.png", env.card_directory, event.thumb_name);
+
+    // same TOCTOU caveat as deck thumbnails; this is only an optimization
+    if env.storage.exists(&card_key).await {
+        tracing::info!("Card image already exists; no need to generate card image");
+        return Ok(());
+    }
+
+    let artwork_name = match &event.card {
+        CardImage::CombatPage(x) => x
+            .artwork
+            .clone()
+            .unwrap_or(NOT_FOUND_IMAGE_NAME.to_string()),
+        CardImage::KeyPage(x) => format!("{}/{}", KEY_PAGE_DIRECTORY, x.id),
+    };
+    let artwork = get_images(&[artwork_name], env)
+        .await?
+        .pop()
+        .ok_or("missing artwork")?;
+    let font = FontVec::try_from_vec(env.storage.get(&env.font_name).await?)?;
+
+    tracing::info!("Drawing card");
+
+    let bytes = tokio::task::spawn_blocking(move || {
+        encode_png(&match &event.card {
+            CardImage::CombatPage(x) => draw_combat_page_card(x, &artwork, &font),
+            CardImage::KeyPage(x) => draw_key_page_card(x, &artwork, &font),
+        })
+    })
+    .await??;
+
+    env.storage.put(&card_key, bytes).await?;
+    Ok(())
+}
+
+// Lays out the cost and name, the artwork, the page effect, then one row per die
+fn draw_combat_page_card(
+    card: &CombatPageCard,
+    artwork: &DynamicImage,
+    font: &FontVec,
+) -> RgbaImage {
+    let text_width = CARD_WIDTH - CARD_MARGIN * 2;
+    let artwork_height = text_width * BASE_COMBAT_PAGE_HEIGHT / BASE_COMBAT_PAGE_WIDTH;
+
+    let description_lines = wrap_text(&card.description, CARD_BODY_SCALE, font, text_width);
+    let dice_lines = card
+        .dice
+        .iter()
+        .map(|x| {
+            wrap_text(
+                &x.description,
+                CARD_BODY_SCALE,
+                font,
+                text_width - CARD_DIE_COLUMN_WIDTH,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let description_height = if description_lines.is_empty() {
+        0
+    } else {
+        description_lines.len() as u32 * CARD_BODY_LINE_HEIGHT + CARD_MARGIN
+    };
+    let dice_height = dice_lines
+        .iter()
+        .map(|x| (x.len().max(1) as u32) * CARD_BODY_LINE_HEIGHT + CARD_DIE_GAP)
+        .sum::<u32>();
+    let height = CARD_HEADER_HEIGHT
+        + artwork_height
+        + CARD_MARGIN
+        + description_height
+        + dice_height
+        + CARD_MARGIN;
+
+    let frame_color = Rgba([
+        (card.frame_color >> 16) as u8,
+        (card.frame_color >> 8) as u8,
+        card.frame_color as u8,
+        255,
+    ]);
+    let mut canvas = RgbaImage::from_pixel(CARD_WIDTH, height, frame_color);
+    draw_filled_rect_mut(
+        &mut canvas,
+        Rect::at(CARD_FRAME_WIDTH as i32, CARD_FRAME_WIDTH as i32).of_size(
+            CARD_WIDTH - CARD_FRAME_WIDTH * 2,
+            height - CARD_FRAME_WIDTH * 2,
+        ),
+        CARD_BACKGROUND_COLOR,
+    );
+
+    let cost_center = (
+        (CARD_MARGIN + CARD_COST_RADIUS) as i32,
+        (CARD_HEADER_HEIGHT / 2) as i32,
+    );
+    draw_filled_circle_mut(
+        &mut canvas,
+        cost_center,
+        CARD_COST_RADIUS as i32,
+        frame_color,
+    );
+    let cost = card.cost.to_string();
+    let cost_size = text_size(CARD_COST_SCALE, font, &cost);
+    draw_text_mut(
+        &mut canvas,
+        CARD_BACKGROUND_COLOR,
+        cost_center.0 - cost_size.0 as i32 / 2,
+        cost_center.1 - CARD_COST_SCALE as i32 / 2,
+        CARD_COST_SCALE,
+        font,
+        &cost,
+    );
+
+    let name_x = CARD_MARGIN * 2 + CARD_COST_RADIUS * 2;
+    draw_text_mut(
+        &mut canvas,
+        CARD_TEXT_COLOR,
+        name_x as i32,
+        (CARD_HEADER_HEIGHT as f32 / 2.0 - CARD_NAME_SCALE / 2.0) as i32,
+        CARD_NAME_SCALE,
+        font,
+        &fit_text(
+            &card.name,
+            CARD_NAME_SCALE,
+            font,
+            CARD_WIDTH - name_x - CARD_MARGIN,
+        ),
+    );
+
+    let resized = artwork.resize_exact(
+        text_width,
+        artwork_height,
+        image::imageops::FilterType::Lanczos3,
+    );
+    let _ = canvas.copy_from(&resized, CARD_MARGIN, CARD_HEADER_HEIGHT);
+
+    let mut y = CARD_HEADER_HEIGHT + artwork_height + CARD_MARGIN;
+    for line in &description_lines {
+        draw_text_mut(
+            &mut canvas,
+            CARD_TEXT_COLOR,
+            CARD_MARGIN as i32,
+            y as i32,
+            CARD_BODY_SCALE,
+            font,
+            line,
+        );
+        y += CARD_BODY_LINE_HEIGHT;
+    }
+    if !description_lines.is_empty() {
+        y += CARD_MARGIN;
+    }
+
+    for (die, lines) in card.dice.iter().zip(dice_lines.iter()) {
+        let (badge_color, label, is_counter) = get_die_style(&die.die_type);
+        let badge = Rect::at(CARD_MARGIN as i32, y as i32)
+            .of_size(CARD_DIE_BADGE_WIDTH, CARD_DIE_BADGE_HEIGHT);
+        draw_filled_rect_mut(&mut canvas, badge, badge_color);
+        if is_counter {
+            draw_hollow_rect_mut(&mut canvas, badge, CARD_COUNTER_OUTLINE_COLOR);
+            draw_hollow_rect_mut(
+                &mut canvas,
+                Rect::at(badge.left() + 1, badge.top() + 1)
+                    .of_size(badge.width() - 2, badge.height() - 2),
+                CARD_COUNTER_OUTLINE_COLOR,
+            );
+        }
+        let label_size = text_size(CARD_DIE_LABEL_SCALE, font, label);
+        draw_text_mut(
+            &mut canvas,
+            CARD_TEXT_COLOR,
+            badge.left() + (CARD_DIE_BADGE_WIDTH as i32 - label_size.0 as i32) / 2,
+            badge.top() + (CARD_DIE_BADGE_HEIGHT as i32 - CARD_DIE_LABEL_SCALE as i32) / 2,
+            CARD_DIE_LABEL_SCALE,
+            font,
+            label,
+        );
+        draw_text_mut(
+            &mut canvas,
+            CARD_TEXT_COLOR,
+            (CARD_MARGIN + CARD_DIE_BADGE_WIDTH + CARD_DIE_GAP) as i32,
+            y as i32,
+            CARD_DIE_RANGE_SCALE,
+            font,
+            &format!("{}-{}", die.min, die.max),
+        );
+
+        let mut line_y = y;
+        for line in lines {
+            draw_text_mut(
+                &mut canvas,
+                CARD_TEXT_COLOR,
+                (CARD_MARGIN + CARD_DIE_COLUMN_WIDTH) as i32,
+                line_y as i32,
+                CARD_BODY_SCALE,
+                font,
+                line,
+            );
+            line_y += CARD_BODY_LINE_HEIGHT;
+        }
+        y += (lines.len().max(1) as u32) * CARD_BODY_LINE_HEIGHT + CARD_DIE_GAP;
+    }
+
+    canvas
+}
+
+// Lays out the name, the portrait, hp, stagger and speed, the resist grid, then the passives
+fn draw_key_page_card(card: &KeyPageCard, portrait: &DynamicImage, font: &FontVec) -> RgbaImage {
+    let text_width = CARD_WIDTH - CARD_MARGIN * 2;
+    let cell_width = (text_width - KEY_PAGE_RESIST_LABEL_WIDTH - CARD_DIE_GAP * 3) / 3;
+
+    let resist_height = (KEY_PAGE_RESIST_CELL_HEIGHT + CARD_DIE_GAP) * 3;
+    let passives_height = if card.passives.is_empty() {
+        0
+    } else {
+        (card.passives.len() as u32 + 1) * CARD_BODY_LINE_HEIGHT + CARD_MARGIN
+    };
+    let height = CARD_HEADER_HEIGHT
+        + KEY_PAGE_PORTRAIT_HEIGHT
+        + CARD_MARGIN
+        + KEY_PAGE_STAT_HEIGHT
+        + CARD_MARGIN
+        + resist_height
+        + passives_height
+        + CARD_MARGIN;
+
+    let frame_color = Rgba([
+        (card.frame_color >> 16) as u8,
+        (card.frame_color >> 8) as u8,
+        card.frame_color as u8,
+        255,
+    ]);
+    let mut canvas = RgbaImage::from_pixel(CARD_WIDTH, height, frame_color);
+    draw_filled_rect_mut(
+        &mut canvas,
+        Rect::at(CARD_FRAME_WIDTH as i32, CARD_FRAME_WIDTH as i32).of_size(
+            CARD_WIDTH - CARD_FRAME_WIDTH * 2,
+            height - CARD_FRAME_WIDTH * 2,
+        ),
+        CARD_BACKGROUND_COLOR,
+    );
+
+    draw_text_mut(
+        &mut canvas,
+        CARD_TEXT_COLOR,
+        CARD_MARGIN as i32,
+        (CARD_HEADER_HEIGHT as f32 / 2.0 - CARD_NAME_SCALE / 2.0) as i32,
+        CARD_NAME_SCALE,
+        font,
+        &fit_text(&card.name, CARD_NAME_SCALE, font, text_width),
+    );
+
+    // sprites come in all shapes, so keep the aspect ratio and center them
+    let resized = portrait.resize(
+        text_width,
+        KEY_PAGE_PORTRAIT_HEIGHT,
+        image::imageops::FilterType::Lanczos3,
+    );
+    image::imageops::overlay(
+        &mut canvas,
+        &resized.to_rgba8(),
+        (CARD_MARGIN + (text_width - resized.width()) / 2) as i64,
+        (CARD_HEADER_HEIGHT + (KEY_PAGE_PORTRAIT_HEIGHT - resized.height()) / 2) as i64,
+    );
+
+    let mut y = CARD_HEADER_HEIGHT + KEY_PAGE_PORTRAIT_HEIGHT + CARD_MARGIN;
+    let stats = [
+        (&card.hp_label, card.hp.to_string()),
+        (&card.stagger_label, card.stagger.to_string()),
+        (
+            &card.speed_label,
+            format!("{}-{}", card.min_speed, card.max_speed),
+        ),
+    ];
+    let stat_width = text_width / stats.len() as u32;
+    for (i, (label, value)) in stats.iter().enumerate() {
+        let x = (CARD_MARGIN + stat_width * i as u32) as i32;
+        draw_text_mut(
+            &mut canvas,
+            CARD_TEXT_COLOR,
+            x,
+            y as i32,
+            CARD_DIE_LABEL_SCALE,
+            font,
+            &fit_text(label, CARD_DIE_LABEL_SCALE, font, stat_width),
+        );
+        draw_text_mut(
+            &mut canvas,
+            frame_color,
+            x,
+            (y + KEY_PAGE_STAT_HEIGHT) as i32 - CARD_COST_SCALE as i32,
+            CARD_COST_SCALE,
+            font,
+            value,
+        );
+    }
+    y += KEY_PAGE_STAT_HEIGHT + CARD_MARGIN;
+
+    let cell_x =
+        |i: u32| CARD_MARGIN + KEY_PAGE_RESIST_LABEL_WIDTH + (cell_width + CARD_DIE_GAP) * i;
+    for (i, die_type) in ["Slash", "Pierce", "Blunt"].iter().enumerate() {
+        let (color, label, _) = get_die_style(die_type);
+        let label_size = text_size(CARD_DIE_LABEL_SCALE, font, label);
+        draw_text_mut(
+            &mut canvas,
+            color,
+            cell_x(i as u32) as i32 + (cell_width as i32 - label_size.0 as i32) / 2,
+            y as i32 + (KEY_PAGE_RESIST_CELL_HEIGHT as i32 - CARD_DIE_LABEL_SCALE as i32) / 2,
+            CARD_DIE_LABEL_SCALE,
+            font,
+            label,
+        );
+    }
+    y += KEY_PAGE_RESIST_CELL_HEIGHT + CARD_DIE_GAP;
+
+    for (row_label, resists) in [
+        (&card.hp_label, &card.hp_resists),
+        (&card.stagger_label, &card.stagger_resists),
+    ] {
+        draw_text_mut(
+            &mut canvas,
+            CARD_TEXT_COLOR,
+            CARD_MARGIN as i32,
+            y as i32 + (KEY_PAGE_RESIST_CELL_HEIGHT as i32 - CARD_BODY_SCALE as i32) / 2,
+            CARD_BODY_SCALE,
+            font,
+            &fit_text(
+                row_label,
+                CARD_BODY_SCALE,
+                font,
+                KEY_PAGE_RESIST_LABEL_WIDTH - CARD_DIE_GAP,
+            ),
+        );
+        for (i, resist) in resists.iter().enumerate() {
+            let cell = Rect::at(cell_x(i as u32) as i32, y as i32)
+                .of_size(cell_width, KEY_PAGE_RESIST_CELL_HEIGHT);
+            draw_filled_rect_mut(&mut canvas, cell, get_resist_color(&resist.resistance));
+            let label = fit_text(&resist.label, CARD_DIE_LABEL_SCALE, font, cell_width);
+            let label_size = text_size(CARD_DIE_LABEL_SCALE, font, &label);
+            draw_text_mut(
+                &mut canvas,
+                CARD_TEXT_COLOR,
+                cell.left() + (cell_width as i32 - label_size.0 as i32) / 2,
+                cell.top() + (KEY_PAGE_RESIST_CELL_HEIGHT as i32 - CARD_DIE_LABEL_SCALE as i32) / 2,
+                CARD_DIE_LABEL_SCALE,
+                font,
+                &label,
+            );
+        }
+        y += KEY_PAGE_RESIST_CELL_HEIGHT + CARD_DIE_GAP;
+    }
+
+    if !card.passives.is_empty() {
+        y += CARD_MARGIN;
+        draw_text_mut(
+            &mut canvas,
+            frame_color,
+            CARD_MARGIN as i32,
+            y as i32,
+            CARD_BODY_SCALE,
+            font,
+            &card.passives_label,
+        );
+        for passive in &card.passives {
+            y += CARD_BODY_LINE_HEIGHT;
+            draw_text_mut(
+                &mut canvas,
+                CARD_TEXT_COLOR,
+                CARD_MARGIN as i32,
+                y as i32,
+                CARD_BODY_SCALE,
+                font,
+                &fit_text(passive, CARD_BODY_SCALE, font, text_width),
+            );
+        }
+    }
+
+    canvas
+}
+
+// runs from red for damage taken in full to blue for damage shrugged off
+fn get_resist_color(resistance: &str) -> Rgba<u8> {
+    match resistance {
+        "Fatal" => Rgba([168, 32, 40, 255]),
+        "Weak" => Rgba([200, 112, 48, 255]),
+        "Normal" => Rgba([96, 96, 96, 255]),
+        "Endured" => Rgba([64, 128, 112, 255]),
+        "Ineffective" => Rgba([48, 96, 176, 255]),
+        "Immune" => Rgba([112, 80, 176, 255]),
+        _ => Rgba([48, 48, 48, 255]),
+    }
+}
+
+// badge color, label and whether the die is a counter die
+fn get_die_style(die_type: &str) -> (Rgba<u8>, &'static str, bool) {
+    let (base_type, is_counter) = match die_type.strip_prefix('C') {
+        Some(x) => (x, true),
+        None => (die_type, false),
+    };
+    let (color, label) = match base_type {
+        "Slash" => (Rgba([196, 64, 56, 255]), "SLASH"),
+        "Pierce" => (Rgba([196, 64, 56, 255]), "PIERCE"),
+        "Blunt" => (Rgba([196, 64, 56, 255]), "BLUNT"),
+        "Block" => (Rgba([56, 112, 196, 255]), "BLOCK"),
+        "Evade" => (Rgba([64, 160, 88, 255]), "EVADE"),
+        _ => (Rgba([96, 96, 96, 255]), "?"),
+    };
+    (color, label, is_counter)
+}
+
+// Greedily wraps text into lines no wider than max_width. Words wider than a
+// line, and scripts without spaces, are broken between characters
+fn wrap_text(text: &str, scale: f32, font: &FontVec, max_width: u32) -> Vec<String> {
+    let fits = |x: &str| text_size(scale, font, x.trim_end()).0 <= max_width;
+
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_inclusive(' ') {
+            let candidate = format!("{}{}", line, word);
+            if fits(&candidate) {
+                line = candidate;
+                continue;
+            }
+            if !line.trim().is_empty() {
+                lines.push(line.trim_end().to_string());
+            }
+            line = String::new();
+            for c in word.chars() {
+                let candidate = format!("{}{}", line, c);
+                if line.is_empty() || fits(&candidate) {
+                    line = candidate;
+                } else {
+                    lines.push(line.trim_end().to_string());
+                    line = c.to_string();
+                }
+            }
+        }
+        if !line.trim().is_empty() {
+            lines.push(line.trim_end().to_string());
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ThumbnailRequest;
+
+    #[test]
+    fn sanity_get_die_style() {
+        let (slash_color, slash_label, slash_counter) = get_die_style("Slash");
+        let (cslash_color, cslash_label, cslash_counter) = get_die_style("CSlash");
+        assert_eq!(slash_color, cslash_color);
+        assert_eq!(slash_label, cslash_label);
+        assert!(!slash_counter);
+        assert!(cslash_counter);
+
+        assert_ne!(get_die_style("Block").0, get_die_style("Evade").0);
+        assert_eq!("?", get_die_style("Unknown").1);
+    }
+
+    #[test]
+    fn sanity_parse_card_request() {
+        let request: ThumbnailRequest = serde_json::from_str(
+            r#"{"thumb_name":"a","card":{"type":"combat_page","artwork":null,"name":"Focus Spirit","cost":0,"frame_color":8115825,"description":"","dice":[{"die_type":"CBlock","min":3,"max":6,"description":""}]}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            request,
+            ThumbnailRequest::Card(CardLambdaInput {
+                card: CardImage::CombatPage(CombatPageCard { cost: 0, .. }),
+                ..
+            })
+        ));
+
+        let request: ThumbnailRequest = serde_json::from_str(
+            r#"{"combat_pages":["1","2","3","4","5","6","7","8","9"],"thumb_name":"a"}"#,
+        )
+        .unwrap();
+        assert!(matches!(request, ThumbnailRequest::Deck(_)));
+    }
+
+    #[test]
+    fn sanity_get_resist_color() {
+        let colors = [
+            "Fatal",
+            "Weak",
+            "Normal",
+            "Endured",
+            "Ineffective",
+            "Immune",
+        ]
+        .map(get_resist_color);
+        for (i, color) in colors.iter().enumerate() {
+            assert!(!colors[i + 1..].contains(color));
+        }
+        assert_ne!(get_resist_color("Normal"), get_resist_color("Unknown"));
+    }
+
+    #[test]
+    fn sanity_parse_key_page_request() {
+        let request: ThumbnailRequest = serde_json::from_str(
+            r#"{"thumb_name":"a","card":{"type":"key_page","id":"250023","name":"Roland","frame_color":16766720,"hp":72,"stagger":36,"min_speed":2,"max_speed":6,"hp_label":"HP","stagger_label":"Stagger","speed_label":"Speed","hp_resists":[{"resistance":"Endured","label":"Endured"},{"resistance":"Normal","label":"Normal"},{"resistance":"Weak","label":"Weak"}],"stagger_resists":[{"resistance":"Fatal","label":"Fatal"},{"resistance":"Immune","label":"Immune"},{"resistance":"Ineffective","label":"Ineffective"}],"passives_label":"Passives","passives":["Black Silence"]}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            request,
+            ThumbnailRequest::Card(CardLambdaInput {
+                card: CardImage::KeyPage(x),
+                ..
+            }) if x.hp == 72 && x.stagger_resists[1].resistance == "Immune"
+        ));
+    }
+}
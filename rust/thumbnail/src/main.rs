@@ -1,105 +1,223 @@
+mod artwork;
+mod card;
 mod models;
+mod prerender;
+mod storage;
 
+use std::collections::HashMap;
 use std::env;
 use std::error::Error;
 use std::io::Cursor;
+use std::path::PathBuf;
 
-use aws_sdk_s3::primitives::ByteStream;
+use ab_glyph::FontVec;
+use artwork::get_grid_tiles;
+use artwork::ArtworkCache;
 use futures::stream;
 use futures::StreamExt;
+use image::DynamicImage;
 use image::GenericImage;
+use image::GenericImageView;
 use image::ImageFormat;
 use image::ImageReader;
+use image::Rgba;
 use image::RgbaImage;
+use imageproc::drawing::draw_text_mut;
+use imageproc::drawing::text_size;
 use lambda_runtime::run;
 use lambda_runtime::service_fn;
 use lambda_runtime::LambdaEvent;
 use models::ThumbnailGeneratorEnvironment;
 use models::ThumbnailLambdaInput;
+use models::ThumbnailRequest;
+use models::ThumbnailTemplate;
 use ruina::ruina_reparser::get_combat_page_by_id;
+use ruina::ruina_reparser::get_key_page_by_id;
+use storage::LocalStorage;
+use storage::S3Storage;
+use storage::ThumbnailStorage;
 
 static NOT_FOUND_IMAGE_NAME: &str = "404_Not_Found";
+static KEY_PAGE_DIRECTORY: &str = "Sprite";
+// combat page or key page id of an unfilled slot
+static EMPTY_SLOT_ID: &str = "0";
+static PRERENDER_COMMAND: &str = "prerender";
+// grid tiles are ~500KB each, so this stays well within the lambda's memory
+static ARTWORK_CACHE_CAPACITY: usize = 128;
 static BASE_COMBAT_PAGE_WIDTH: u32 = 410;
 static BASE_COMBAT_PAGE_HEIGHT: u32 = 310;
+static CARD_PADDING: u32 = 40;
+static CARD_HEADER_HEIGHT: u32 = 160;
+static CARD_SIDEBAR_WIDTH: u32 = 410;
+static CARD_TITLE_SCALE: f32 = 80.0;
+static CARD_PASSIVE_SCALE: f32 = 36.0;
+static CARD_PASSIVE_LINE_HEIGHT: u32 = 48;
+static CARD_BACKGROUND_COLOR: Rgba<u8> = Rgba([24, 22, 28, 255]);
+static CARD_TEXT_COLOR: Rgba<u8> = Rgba([236, 224, 200, 255]);
 
-async fn function_handler(
+async fn function_handler<S: ThumbnailStorage>(
     event: ThumbnailLambdaInput,
-    env: &ThumbnailGeneratorEnvironment,
+    env: &ThumbnailGeneratorEnvironment<S>,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     tracing::debug!("Rust function invoked");
 
-    let thumbnail_key = format!("{}/{}.png", env.s3_directory, event.thumb_name);
-    let head_object_result = env
-        .s3_client
-        .as_ref()
-        .unwrap()
-        .head_object()
-        .bucket(&env.s3_bucket_name)
-        .key(&thumbnail_key)
-        .send()
-        .await;
+    let thumbnail_key = format!("{}/{}.png", env.thumbnail_directory, event.thumb_name);
 
     // There is a TOCTOU gap here, but since put doesn't affect the
     // correctness of this logic, this is only here as an optimization
-    if head_object_result.is_ok() {
-        tracing::info!("Head Object resulted in success; no need to generate thumbnail");
+    if env.storage.exists(&thumbnail_key).await {
+        tracing::info!("Thumbnail already exists; no need to generate thumbnail");
         return Ok(());
     }
 
-    let image_dirs = event
-        .combat_pages
+    let mut image_names = resolve_artwork(&event)
         .into_iter()
-        .map(|x| get_combat_page_by_id(&x))
+        .map(|(_, artwork)| artwork.unwrap_or(NOT_FOUND_IMAGE_NAME.to_string()))
+        .collect::<Vec<_>>();
+    // anything past the grid, i.e. the key page portrait, is drawn at its own size
+    let portrait_names = image_names.split_off(event.combat_pages.len());
+    let mut image_data = get_grid_tiles(&image_names, env).await?;
+    image_data.extend(get_images(&portrait_names, env).await?);
+    let font = match event.template {
+        ThumbnailTemplate::Grid => None,
+        ThumbnailTemplate::DeckCard => Some(FontVec::try_from_vec(
+            env.storage.get(&env.font_name).await?,
+        )?),
+    };
+
+    tracing::info!("Drawing canvas");
+
+    // drawing is cpu bound, so keep it off the async workers for bulk renders
+    let bytes =
+        tokio::task::spawn_blocking(move || draw_thumbnail(&event, image_data, font.as_ref()))
+            .await??;
+
+    tracing::info!("Putting to storage");
+
+    env.storage.put(&thumbnail_key, bytes).await?;
+
+    tracing::debug!("Rust function finished invocation");
+    Ok(())
+}
+
+// Pairs each id drawn on the thumbnail with the artwork it resolves to, in draw order
+fn resolve_artwork(event: &ThumbnailLambdaInput) -> Vec<(&str, Option<String>)> {
+    let mut artwork = event
+        .combat_pages
+        .iter()
         .map(|x| {
-            format!(
-                "{}.png",
-                x.and_then(|y| y.artwork).unwrap_or(NOT_FOUND_IMAGE_NAME)
+            (
+                x.as_str(),
+                get_combat_page_by_id(x)
+                    .and_then(|y| y.artwork)
+                    .map(|y| y.to_string()),
             )
         })
         .collect::<Vec<_>>();
+    if event.template == ThumbnailTemplate::DeckCard {
+        // key page portraits are stored by id, matching the key page embeds
+        artwork.push((
+            event.keypage.as_deref().unwrap_or(EMPTY_SLOT_ID),
+            event
+                .keypage
+                .as_ref()
+                .and_then(|x| get_key_page_by_id(x))
+                .map(|x| format!("{}/{}", KEY_PAGE_DIRECTORY, x.id)),
+        ));
+    }
+    artwork
+}
 
-    tracing::info!("Grabbing images: {:?}", image_dirs);
+// Ids that will be drawn with NOT_FOUND_IMAGE_NAME, excluding empty slots
+fn get_missing_artwork(event: &ThumbnailLambdaInput) -> Vec<String> {
+    resolve_artwork(event)
+        .into_iter()
+        .filter(|(id, artwork)| artwork.is_none() && *id != EMPTY_SLOT_ID)
+        .map(|(id, _)| id.to_string())
+        .collect()
+}
 
-    // todo: do not make redundant requests on duplicates
-    let get_object_data = stream::iter(image_dirs)
-        .map(|x| async {
-            env.s3_client
-                .as_ref()
-                .unwrap()
-                .get_object()
-                .bucket(&env.s3_bucket_name)
-                .key(x)
-                .send()
-                .await
-                .expect("request failed")
+fn draw_thumbnail(
+    event: &ThumbnailLambdaInput,
+    mut image_data: Vec<DynamicImage>,
+    font: Option<&FontVec>,
+) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    let canvas = match event.template {
+        ThumbnailTemplate::Grid => draw_grid(&image_data),
+        ThumbnailTemplate::DeckCard => {
+            let keypage_image = image_data.pop().ok_or("missing key page image")?;
+            let passive_names = event
+                .passive_names
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+
+            draw_deck_card(
+                &draw_grid(&image_data),
+                &keypage_image,
+                event.deck_name.as_deref().unwrap_or_default(),
+                &passive_names,
+                font.ok_or("no font loaded")?,
+            )
+        }
+    };
+
+    encode_png(&canvas)
+}
+
+fn encode_png(canvas: &RgbaImage) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    tracing::info!("Converting to PNG");
+
+    let mut bytes: Vec<u8> = Vec::new();
+    canvas.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    Ok(bytes)
+}
+
+async fn get_images<S: ThumbnailStorage>(
+    image_names: &[String],
+    env: &ThumbnailGeneratorEnvironment<S>,
+) -> Result<Vec<DynamicImage>, Box<dyn Error + Send + Sync>> {
+    let mut unique_names = image_names.iter().collect::<Vec<_>>();
+    unique_names.sort();
+    unique_names.dedup();
+
+    tracing::info!("Grabbing images: {:?}", unique_names);
+
+    let image_data = stream::iter(unique_names)
+        .map(|x| async move {
+            let data = env.storage.get(&format!("{}.png", x)).await?;
+            decode_png(data).map(|y| (x, y))
         })
         .buffered(9)
-        .map(|x| async { x.body.collect().await.expect("couldn't get image data") })
-        .buffered(9)
         .collect::<Vec<_>>()
-        .await;
-
-    let image_data = get_object_data
+        .await
         .into_iter()
-        .map(|x| {
-            ImageReader::with_format(std::io::Cursor::new(x.into_bytes()), ImageFormat::Png)
-                .decode()
-                .expect("couldn't decode image")
-        })
-        .collect::<Vec<_>>();
+        .collect::<Result<HashMap<_, _>, _>>()?;
 
-    tracing::info!("Drawing canvas");
+    Ok(image_names.iter().map(|x| image_data[x].clone()).collect())
+}
+
+fn decode_png(data: Vec<u8>) -> Result<DynamicImage, Box<dyn Error + Send + Sync>> {
+    Ok(ImageReader::with_format(Cursor::new(data), ImageFormat::Png).decode()?)
+}
 
+fn draw_grid(image_data: &[DynamicImage]) -> RgbaImage {
     let mut canvas = RgbaImage::new(BASE_COMBAT_PAGE_WIDTH * 3, BASE_COMBAT_PAGE_HEIGHT * 3);
     for i in 0..=2 {
         for j in 0..=2 {
             let index = i * 3 + j;
             let image = &image_data[index];
-            let resized = image.resize_exact(
-                BASE_COMBAT_PAGE_WIDTH,
-                BASE_COMBAT_PAGE_HEIGHT,
-                image::imageops::FilterType::Lanczos3,
-            );
+            // tiles from get_grid_tiles are already sized
+            let resized = if image.dimensions() == (BASE_COMBAT_PAGE_WIDTH, BASE_COMBAT_PAGE_HEIGHT)
+            {
+                image.clone()
+            } else {
+                image.resize_exact(
+                    BASE_COMBAT_PAGE_WIDTH,
+                    BASE_COMBAT_PAGE_HEIGHT,
+                    image::imageops::FilterType::Lanczos3,
+                )
+            };
 
             let _ = canvas.copy_from(
                 &resized,
@@ -108,64 +226,195 @@ async fn function_handler(
             );
         }
     }
+    canvas
+}
 
-    tracing::info!("Converting to PNG");
+// Lays out the deck name across the top, the key page and its passives down
+// the left side, and the combat page grid on the right
+fn draw_deck_card(
+    grid: &RgbaImage,
+    keypage_image: &DynamicImage,
+    deck_name: &str,
+    passive_names: &[&str],
+    font: &FontVec,
+) -> RgbaImage {
+    let width = CARD_PADDING * 3 + CARD_SIDEBAR_WIDTH + grid.width();
+    let height = CARD_HEADER_HEIGHT + grid.height() + CARD_PADDING;
+    let mut canvas = RgbaImage::from_pixel(width, height, CARD_BACKGROUND_COLOR);
 
-    let mut bytes: Vec<u8> = Vec::new();
-    canvas.write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+    draw_text_mut(
+        &mut canvas,
+        CARD_TEXT_COLOR,
+        CARD_PADDING as i32,
+        CARD_PADDING as i32,
+        CARD_TITLE_SCALE,
+        font,
+        &fit_text(deck_name, CARD_TITLE_SCALE, font, width - CARD_PADDING * 2),
+    );
 
-    tracing::info!("Putting to S3");
+    let portrait = keypage_image.resize(
+        CARD_SIDEBAR_WIDTH,
+        CARD_SIDEBAR_WIDTH,
+        image::imageops::FilterType::Lanczos3,
+    );
+    let _ = canvas.copy_from(
+        &portrait,
+        CARD_PADDING + (CARD_SIDEBAR_WIDTH - portrait.width()) / 2,
+        CARD_HEADER_HEIGHT,
+    );
 
-    let _ = env
-        .s3_client
-        .as_ref()
-        .unwrap()
-        .put_object()
-        .bucket(&env.s3_bucket_name)
-        .key(&thumbnail_key)
-        .body(ByteStream::from(bytes))
-        .content_type("image/png")
-        .send()
-        .await?;
+    let mut y = CARD_HEADER_HEIGHT + CARD_SIDEBAR_WIDTH + CARD_PADDING;
+    for passive_name in passive_names {
+        if y + CARD_PASSIVE_LINE_HEIGHT > height - CARD_PADDING {
+            break;
+        }
+        draw_text_mut(
+            &mut canvas,
+            CARD_TEXT_COLOR,
+            CARD_PADDING as i32,
+            y as i32,
+            CARD_PASSIVE_SCALE,
+            font,
+            &fit_text(passive_name, CARD_PASSIVE_SCALE, font, CARD_SIDEBAR_WIDTH),
+        );
+        y += CARD_PASSIVE_LINE_HEIGHT;
+    }
 
-    tracing::debug!("Rust function finished invocation");
-    Ok(())
+    let _ = canvas.copy_from(
+        grid,
+        CARD_PADDING * 2 + CARD_SIDEBAR_WIDTH,
+        CARD_HEADER_HEIGHT,
+    );
+    canvas
+}
+
+// Truncates text with an ellipsis until it fits within max_width
+fn fit_text(text: &str, scale: f32, font: &FontVec, max_width: u32) -> String {
+    if text_size(scale, font, text).0 <= max_width {
+        return text.to_string();
+    }
+    let mut chars = text.chars().collect::<Vec<_>>();
+    while !chars.is_empty() {
+        chars.pop();
+        let truncated = format!("{}…", chars.iter().collect::<String>().trim_end());
+        if text_size(scale, font, &truncated).0 <= max_width {
+            return truncated;
+        }
+    }
+    String::new()
 }
 
 #[tokio::main]
 async fn main() -> Result<(), lambda_runtime::Error> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let is_prerender = args.first().is_some_and(|x| x == PRERENDER_COMMAND);
+
     tracing_subscriber::fmt()
         .json()
-        .with_max_level(tracing::Level::INFO)
+        .with_max_level(if is_prerender {
+            tracing::Level::WARN
+        } else {
+            tracing::Level::INFO
+        })
         .with_current_span(false)
         .with_ansi(false)
         .without_time()
         .with_target(false)
         .init();
 
+    let thumbnail_directory = env::var("S3_DIRECTORY").unwrap();
+    let card_directory = env::var("CARD_DIRECTORY").unwrap();
+    let artwork_tile_directory = env::var("ARTWORK_TILE_DIRECTORY").unwrap();
+    let font_name = env::var("FONT_NAME").unwrap();
+
+    // self-hosted deployments can read and write a local directory instead of S3
+    if let Ok(root) = env::var("LOCAL_STORAGE_DIRECTORY") {
+        return start(
+            ThumbnailGeneratorEnvironment {
+                thumbnail_directory,
+                card_directory,
+                artwork_tile_directory,
+                font_name,
+                artwork_cache: ArtworkCache::new(ARTWORK_CACHE_CAPACITY),
+                storage: LocalStorage {
+                    root: PathBuf::from(root),
+                },
+            },
+            &args,
+        )
+        .await;
+    }
+
     let config = aws_config::load_from_env().await;
     let s3 = aws_sdk_s3::Client::new(&config);
 
-    let env = ThumbnailGeneratorEnvironment {
-        s3_bucket_name: env::var("S3_BUCKET_NAME").unwrap(),
-        s3_directory: env::var("S3_DIRECTORY").unwrap(),
-        s3_client: Some(s3),
-    };
+    start(
+        ThumbnailGeneratorEnvironment {
+            thumbnail_directory,
+            card_directory,
+            artwork_tile_directory,
+            font_name,
+            artwork_cache: ArtworkCache::new(ARTWORK_CACHE_CAPACITY),
+            storage: S3Storage {
+                bucket_name: env::var("S3_BUCKET_NAME").unwrap(),
+                client: s3,
+            },
+        },
+        &args,
+    )
+    .await
+}
+
+async fn start<S: ThumbnailStorage>(
+    env: ThumbnailGeneratorEnvironment<S>,
+    args: &[String],
+) -> Result<(), lambda_runtime::Error> {
+    if args.first().is_some_and(|x| x == PRERENDER_COMMAND) {
+        return prerender::run(&args[1..], &env).await;
+    }
+
     let env_ref = &env;
 
     tracing::debug!("Rust function setup complete");
 
-    run(service_fn(
-        move |event: LambdaEvent<ThumbnailLambdaInput>| function_handler(event.payload, env_ref),
-    ))
+    run(service_fn(move |event: LambdaEvent<ThumbnailRequest>| {
+        handle_request(event.payload, env_ref)
+    }))
     .await
 }
 
+async fn handle_request<S: ThumbnailStorage>(
+    request: ThumbnailRequest,
+    env: &ThumbnailGeneratorEnvironment<S>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    match request {
+        ThumbnailRequest::Deck(x) => function_handler(x, env).await,
+        ThumbnailRequest::Card(x) => card::card_handler(x, env).await,
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+
+    use image::DynamicImage;
+    use image::ImageFormat;
+    use image::Rgba;
+    use image::RgbaImage;
+    use ruina::ruina_reparser::get_combat_page_by_id;
+
+    use crate::draw_grid;
     use crate::function_handler;
+    use crate::ArtworkCache;
+    use crate::LocalStorage;
+    use crate::S3Storage;
     use crate::ThumbnailGeneratorEnvironment;
     use crate::ThumbnailLambdaInput;
+    use crate::ThumbnailStorage;
+    use crate::ThumbnailTemplate;
+    use crate::ARTWORK_CACHE_CAPACITY;
+    use crate::BASE_COMBAT_PAGE_HEIGHT;
+    use crate::BASE_COMBAT_PAGE_WIDTH;
 
     // todo: this test is broken. figure out how to run tests
     // using SSO crednetials provider
@@ -178,9 +427,15 @@ mod tests {
             .build();
         let s3 = aws_sdk_s3::Client::from_conf(config);
         let env = ThumbnailGeneratorEnvironment {
-            s3_bucket_name: "".to_string(),
-            s3_directory: "deck_thumbnails".to_string(),
-            s3_client: Some(s3),
+            thumbnail_directory: "deck_thumbnails".to_string(),
+            card_directory: "card_images".to_string(),
+            artwork_tile_directory: "artwork_tiles".to_string(),
+            font_name: "fonts/thumbnail.ttf".to_string(),
+            artwork_cache: ArtworkCache::new(ARTWORK_CACHE_CAPACITY),
+            storage: S3Storage {
+                bucket_name: "".to_string(),
+                client: s3,
+            },
         };
         let input = ThumbnailLambdaInput {
             combat_pages: vec![
@@ -193,8 +448,106 @@ mod tests {
             .try_into()
             .unwrap(),
             thumb_name: "turbo_nikolai_test".to_string(),
+            template: ThumbnailTemplate::DeckCard,
+            deck_name: Some("Turbo Nikolai".to_string()),
+            keypage: Some("250023".to_string()),
+            passive_names: vec!["Black Silence".to_string(), "Furioso".to_string()],
         };
 
         assert!(function_handler(input, &env).await.is_ok());
     }
+
+    fn encode_png(color: Rgba<u8>) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::new();
+        RgbaImage::from_pixel(4, 3, color)
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[tokio::test]
+    async fn sanity_local_storage() {
+        let root = std::env::temp_dir().join(format!("thumbnail_sanity_{}", std::process::id()));
+        let env = ThumbnailGeneratorEnvironment {
+            thumbnail_directory: "deck_thumbnails".to_string(),
+            card_directory: "card_images".to_string(),
+            artwork_tile_directory: "artwork_tiles".to_string(),
+            font_name: "fonts/thumbnail.ttf".to_string(),
+            artwork_cache: ArtworkCache::new(ARTWORK_CACHE_CAPACITY),
+            storage: LocalStorage { root: root.clone() },
+        };
+        let artwork = get_combat_page_by_id("608014").unwrap().artwork.unwrap();
+        let blue = Rgba([0, 0, 255, 255]);
+        let red = Rgba([255, 0, 0, 255]);
+        env.storage
+            .put(&format!("{}.png", artwork), encode_png(blue))
+            .await
+            .unwrap();
+        env.storage
+            .put("404_Not_Found.png", encode_png(red))
+            .await
+            .unwrap();
+
+        let build_input = || ThumbnailLambdaInput {
+            combat_pages: std::array::from_fn(|i| if i == 0 { "608014" } else { "0" }.to_string()),
+            thumb_name: "local_test".to_string(),
+            template: ThumbnailTemplate::Grid,
+            deck_name: None,
+            keypage: None,
+            passive_names: Vec::new(),
+        };
+        assert!(function_handler(build_input(), &env).await.is_ok());
+
+        // existing thumbnails are not regenerated
+        env.storage
+            .put(&format!("{}.png", artwork), encode_png(red))
+            .await
+            .unwrap();
+        assert!(function_handler(build_input(), &env).await.is_ok());
+
+        let thumbnail = image::load_from_memory(
+            &env.storage
+                .get("deck_thumbnails/local_test.png")
+                .await
+                .unwrap(),
+        )
+        .unwrap()
+        .to_rgba8();
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(BASE_COMBAT_PAGE_WIDTH * 3, thumbnail.width());
+        assert_eq!(&blue, thumbnail.get_pixel(1, 1));
+        assert_eq!(&red, thumbnail.get_pixel(BASE_COMBAT_PAGE_WIDTH + 1, 1));
+    }
+
+    #[test]
+    fn sanity_template_defaults_to_grid() {
+        let input: ThumbnailLambdaInput = serde_json::from_str(
+            r#"{"combat_pages":["1","2","3","4","5","6","7","8","9"],"thumb_name":"a"}"#,
+        )
+        .unwrap();
+        assert_eq!(ThumbnailTemplate::Grid, input.template);
+        assert!(input.passive_names.is_empty());
+
+        let input: ThumbnailLambdaInput = serde_json::from_str(
+            r#"{"combat_pages":["1","2","3","4","5","6","7","8","9"],"thumb_name":"a","template":"deck_card"}"#,
+        )
+        .unwrap();
+        assert_eq!(ThumbnailTemplate::DeckCard, input.template);
+    }
+
+    #[test]
+    fn sanity_draw_grid() {
+        let images = (0..9u8)
+            .map(|x| DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 3, Rgba([x, 0, 0, 255]))))
+            .collect::<Vec<_>>();
+
+        let canvas = draw_grid(&images);
+        assert_eq!(BASE_COMBAT_PAGE_WIDTH * 3, canvas.width());
+        assert_eq!(BASE_COMBAT_PAGE_HEIGHT * 3, canvas.height());
+        assert_eq!(
+            &Rgba([5, 0, 0, 255]),
+            canvas.get_pixel(BASE_COMBAT_PAGE_WIDTH * 2 + 1, BASE_COMBAT_PAGE_HEIGHT + 1)
+        );
+    }
 }
@@ -1,9 +1,34 @@
 use serde::Deserialize;
 
-pub struct ThumbnailGeneratorEnvironment {
-    pub s3_bucket_name: String,
-    pub s3_directory: String,
-    pub s3_client: Option<aws_sdk_s3::Client>,
+use crate::artwork::ArtworkCache;
+use crate::storage::ThumbnailStorage;
+
+pub struct ThumbnailGeneratorEnvironment<S: ThumbnailStorage> {
+    pub thumbnail_directory: String,
+    pub card_directory: String,
+    // pre-resized combat page artwork for the grid
+    pub artwork_tile_directory: String,
+    pub font_name: String,
+    pub artwork_cache: ArtworkCache,
+    pub storage: S,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThumbnailTemplate {
+    // 3x3 grid of combat page artwork
+    #[default]
+    Grid,
+    // the grid alongside the key page, passives and deck name
+    DeckCard,
+}
+
+// deck thumbnails predate card images, so requests are told apart by their fields
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ThumbnailRequest {
+    Deck(ThumbnailLambdaInput),
+    Card(CardLambdaInput),
 }
 
 // todo: figure out how to distribute this across packages without copy paste
@@ -11,4 +36,89 @@ pub struct ThumbnailGeneratorEnvironment {
 pub struct ThumbnailLambdaInput {
     pub combat_pages: [String; 9],
     pub thumb_name: String,
+    #[serde(default)]
+    pub template: ThumbnailTemplate,
+    #[serde(default)]
+    pub deck_name: Option<String>,
+    #[serde(default)]
+    pub keypage: Option<String>,
+    // already localized by binah_bot
+    #[serde(default)]
+    pub passive_names: Vec<String>,
+}
+
+// todo: figure out how to distribute this across packages without copy paste
+#[derive(Debug, Deserialize)]
+pub struct DeckData {
+    pub keypage_id: Option<String>,
+    pub passive_ids: Vec<String>,
+    pub combat_page_ids: [Option<String>; 9],
+}
+
+// the parts of a stored deck needed to render its thumbnail
+#[derive(Debug, Deserialize)]
+pub struct DeckThumbnailSource {
+    pub name: String,
+    pub deck_data: DeckData,
+}
+
+// todo: figure out how to distribute this across packages without copy paste
+#[derive(Debug, Deserialize)]
+pub struct CardLambdaInput {
+    pub thumb_name: String,
+    pub card: CardImage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CardImage {
+    CombatPage(CombatPageCard),
+    KeyPage(Box<KeyPageCard>),
+}
+
+// all text arrives already localized
+#[derive(Debug, Deserialize)]
+pub struct CombatPageCard {
+    pub artwork: Option<String>,
+    pub name: String,
+    pub cost: u8,
+    pub frame_color: u32,
+    pub description: String,
+    pub dice: Vec<CardDie>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CardDie {
+    // one of the ruina DieType variant names, e.g. `Slash` or `CBlock`
+    pub die_type: String,
+    pub min: u16,
+    pub max: u16,
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KeyPageCard {
+    // portrait is read from the key page sprite directory
+    pub id: String,
+    pub name: String,
+    pub frame_color: u32,
+    pub hp: u16,
+    pub stagger: u16,
+    pub min_speed: u8,
+    pub max_speed: u8,
+    pub hp_label: String,
+    pub stagger_label: String,
+    pub speed_label: String,
+    // slash, pierce and blunt, in that order
+    pub hp_resists: [CardResist; 3],
+    pub stagger_resists: [CardResist; 3],
+    pub passives_label: String,
+    pub passives: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CardResist {
+    // one of the ruina Resistance variant names, e.g. `Fatal` or `Endured`
+    pub resistance: String,
+    pub label: String,
 }
@@ -0,0 +1,374 @@
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::PathBuf;
+
+use futures::stream;
+use futures::StreamExt;
+use ruina::ruina_common::localizations::common::Locale;
+use ruina::ruina_reparser::get_passive_locales_by_id;
+use serde::Deserialize;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::function_handler;
+use crate::get_missing_artwork;
+use crate::models::DeckThumbnailSource;
+use crate::models::ThumbnailGeneratorEnvironment;
+use crate::models::ThumbnailLambdaInput;
+use crate::models::ThumbnailTemplate;
+use crate::storage::ThumbnailStorage;
+use crate::EMPTY_SLOT_ID;
+
+// must match the template binah_bot requests for deck embeds
+const DECK_CARD_TEMPLATE_NAME: &str = "deck_card";
+const DEFAULT_CONCURRENCY: usize = 8;
+// other locales are rendered by binah_bot the first time a deck is viewed in them
+const PRERENDER_LOCALE: Locale = Locale::English;
+
+#[derive(Debug, PartialEq)]
+struct PrerenderArgs {
+    concurrency: usize,
+    table_name: Option<String>,
+    deck_files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Default)]
+struct PrerenderReport {
+    rendered: usize,
+    skipped: usize,
+    // deck name and error
+    failed: Vec<(String, String)>,
+    // id and the number of thumbnails drawn without its artwork
+    missing_artwork: BTreeMap<String, usize>,
+}
+
+// deck files hold either a single deck or a list of decks
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DeckFile {
+    Single(Box<DeckThumbnailSource>),
+    Many(Vec<DeckThumbnailSource>),
+}
+
+// Usage: prerender [--concurrency N] [--table TABLE] [DECK_JSON...]
+//
+// Renders the given deck files, or every deck in the deck repository table
+// when no files are given. Thumbnails that already exist are skipped.
+pub async fn run<S: ThumbnailStorage>(
+    args: &[String],
+    env: &ThumbnailGeneratorEnvironment<S>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let args = parse_args(args)?;
+
+    let decks = if args.deck_files.is_empty() {
+        let table_name = args
+            .table_name
+            .or(std::env::var("DECK_REPOSITORY_NAME").ok())
+            .ok_or("no deck files given and no deck repository table set")?;
+        scan_decks(&table_name).await?
+    } else {
+        read_deck_files(&args.deck_files)?
+    };
+
+    println!("Pre-rendering {} decks", decks.len());
+    let report = prerender_decks(decks, args.concurrency, env).await;
+
+    println!(
+        "Rendered {}, skipped {} existing, {} failed",
+        report.rendered,
+        report.skipped,
+        report.failed.len()
+    );
+    for (deck_name, error) in &report.failed {
+        println!("Failed {}: {}", deck_name, error);
+    }
+    for (id, count) in &report.missing_artwork {
+        println!("Missing artwork for {} (used by {} thumbnails)", id, count);
+    }
+
+    if report.failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} thumbnails failed to render", report.failed.len()).into())
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<PrerenderArgs, String> {
+    let mut parsed = PrerenderArgs {
+        concurrency: DEFAULT_CONCURRENCY,
+        table_name: None,
+        deck_files: Vec::new(),
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--concurrency" => {
+                parsed.concurrency = iter
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .filter(|x| *x > 0)
+                    .ok_or("--concurrency expects a positive number")?;
+            }
+            "--table" => {
+                parsed.table_name =
+                    Some(iter.next().ok_or("--table expects a table name")?.clone());
+            }
+            x if x.starts_with("--") => return Err(format!("unknown option {}", x)),
+            x => parsed.deck_files.push(PathBuf::from(x)),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn read_deck_files(
+    paths: &[PathBuf],
+) -> Result<Vec<DeckThumbnailSource>, Box<dyn Error + Send + Sync>> {
+    let mut decks = Vec::new();
+    for path in paths {
+        match serde_json::from_str(&std::fs::read_to_string(path)?)? {
+            DeckFile::Single(x) => decks.push(*x),
+            DeckFile::Many(x) => decks.extend(x),
+        }
+    }
+    Ok(decks)
+}
+
+async fn scan_decks(
+    table_name: &str,
+) -> Result<Vec<DeckThumbnailSource>, Box<dyn Error + Send + Sync>> {
+    tracing::info!("Scanning decks in table_name={}", table_name);
+    let config = aws_config::load_from_env().await;
+    let client = aws_sdk_dynamodb::Client::new(&config);
+
+    let items = client
+        .scan()
+        .table_name(table_name)
+        .projection_expression("deck_name, deck_data")
+        .into_paginator()
+        .items()
+        .send()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    items
+        .iter()
+        .map(|x| {
+            let name = x
+                .get("deck_name")
+                .and_then(|y| y.as_s().ok())
+                .ok_or("no deck name")?;
+            let deck_data = x
+                .get("deck_data")
+                .and_then(|y| y.as_s().ok())
+                .ok_or("no deck data")?;
+            Ok(DeckThumbnailSource {
+                name: name.clone(),
+                deck_data: serde_json::from_str(deck_data)?,
+            })
+        })
+        .collect()
+}
+
+async fn prerender_decks<S: ThumbnailStorage>(
+    decks: Vec<DeckThumbnailSource>,
+    concurrency: usize,
+    env: &ThumbnailGeneratorEnvironment<S>,
+) -> PrerenderReport {
+    let mut report = PrerenderReport::default();
+
+    // decks with identical contents share a thumbnail
+    let mut seen = HashSet::new();
+    let inputs = decks
+        .iter()
+        .map(|x| (x.name.clone(), build_lambda_input(x)))
+        .filter(|(_, x)| seen.insert(x.thumb_name.clone()))
+        .collect::<Vec<_>>();
+
+    for (_, input) in &inputs {
+        for id in get_missing_artwork(input) {
+            *report.missing_artwork.entry(id).or_default() += 1;
+        }
+    }
+
+    let results = stream::iter(inputs)
+        .map(|(deck_name, input)| async move {
+            let thumbnail_key = format!("{}/{}.png", env.thumbnail_directory, input.thumb_name);
+            if env.storage.exists(&thumbnail_key).await {
+                return (deck_name, Ok(false));
+            }
+            (deck_name, function_handler(input, env).await.map(|_| true))
+        })
+        .buffer_unordered(concurrency)
+        .collect::<Vec<_>>()
+        .await;
+
+    for (deck_name, result) in results {
+        match result {
+            Ok(true) => report.rendered += 1,
+            Ok(false) => report.skipped += 1,
+            Err(e) => report.failed.push((deck_name, e.to_string())),
+        }
+    }
+
+    report
+}
+
+fn build_lambda_input(deck: &DeckThumbnailSource) -> ThumbnailLambdaInput {
+    ThumbnailLambdaInput {
+        combat_pages: resolve_optional(&deck.deck_data.combat_page_ids),
+        thumb_name: generate_thumb_name(deck),
+        template: ThumbnailTemplate::DeckCard,
+        deck_name: Some(deck.name.clone()),
+        keypage: deck.deck_data.keypage_id.clone(),
+        passive_names: deck
+            .deck_data
+            .passive_ids
+            .iter()
+            .map(|x| {
+                get_passive_locales_by_id(x)
+                    .get(&PRERENDER_LOCALE)
+                    .map(|y| y.name.to_string())
+                    .unwrap_or(x.to_string())
+            })
+            .collect(),
+    }
+}
+
+// must match generate_thumb_name in binah_bot, or embeds will point at missing thumbnails
+fn generate_thumb_name(deck: &DeckThumbnailSource) -> String {
+    let components = [
+        DECK_CARD_TEMPLATE_NAME.to_string(),
+        deck.name.clone(),
+        deck.deck_data
+            .keypage_id
+            .clone()
+            .unwrap_or(EMPTY_SLOT_ID.to_string()),
+        deck.deck_data.passive_ids.join(","),
+        PRERENDER_LOCALE.to_string(),
+    ]
+    .into_iter()
+    .chain(resolve_optional(&deck.deck_data.combat_page_ids))
+    .collect::<Vec<_>>();
+
+    format!("{:X}", xxh3_64(components.join("#").as_bytes()))
+}
+
+fn resolve_optional(combat_pages: &[Option<String>; 9]) -> [String; 9] {
+    std::array::from_fn(|i| combat_pages[i].clone().unwrap_or(EMPTY_SLOT_ID.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artwork::ArtworkCache;
+    use crate::models::DeckData;
+    use crate::storage::LocalStorage;
+
+    fn build_deck(name: &str, last_page: Option<&str>) -> DeckThumbnailSource {
+        DeckThumbnailSource {
+            name: name.to_string(),
+            deck_data: DeckData {
+                keypage_id: Some("250023".to_string()),
+                passive_ids: vec!["230018".to_string()],
+                combat_page_ids: [
+                    Some("608014"),
+                    Some("608014"),
+                    Some("608014"),
+                    Some("608015"),
+                    Some("608015"),
+                    Some("608015"),
+                    Some("608009"),
+                    Some("608009"),
+                    last_page,
+                ]
+                .map(|x| x.map(|y| y.to_string())),
+            },
+        }
+    }
+
+    #[test]
+    fn sanity_parse_args() {
+        let args =
+            ["--concurrency", "4", "a.json", "--table", "decks", "b.json"].map(|x| x.to_string());
+        assert_eq!(
+            Ok(PrerenderArgs {
+                concurrency: 4,
+                table_name: Some("decks".to_string()),
+                deck_files: vec![PathBuf::from("a.json"), PathBuf::from("b.json")],
+            }),
+            parse_args(&args)
+        );
+
+        assert!(parse_args(&["--concurrency".to_string(), "0".to_string()]).is_err());
+        assert!(parse_args(&["--force".to_string()]).is_err());
+    }
+
+    #[test]
+    fn sanity_generate_thumb_name() {
+        // pinned to the name binah_bot generates for the same deck in English
+        assert_eq!(
+            "CE2779042F9A4C9",
+            generate_thumb_name(&build_deck("Turbo Nikolai", None))
+        );
+    }
+
+    #[tokio::test]
+    async fn sanity_prerender_decks() {
+        let root = std::env::temp_dir().join(format!("thumbnail_prerender_{}", std::process::id()));
+        let env = ThumbnailGeneratorEnvironment {
+            thumbnail_directory: "deck_thumbnails".to_string(),
+            card_directory: "card_images".to_string(),
+            artwork_tile_directory: "artwork_tiles".to_string(),
+            font_name: "fonts/thumbnail.ttf".to_string(),
+            artwork_cache: ArtworkCache::new(4),
+            storage: LocalStorage { root: root.clone() },
+        };
+        let existing = build_deck("Turbo Nikolai", Some("999999"));
+        env.storage
+            .put(
+                &format!("deck_thumbnails/{}.png", generate_thumb_name(&existing)),
+                Vec::new(),
+            )
+            .await
+            .unwrap();
+
+        let decks = vec![
+            build_deck("Turbo Nikolai", Some("999999")),
+            existing,
+            // no artwork or font in storage, so this fails to render
+            build_deck("Turbo Nikolai 2", Some("999999")),
+        ];
+        let report = prerender_decks(decks, 2, &env).await;
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(0, report.rendered);
+        assert_eq!(1, report.skipped);
+        assert_eq!(1, report.failed.len());
+        assert_eq!("Turbo Nikolai 2", report.failed[0].0);
+        assert_eq!(
+            BTreeMap::from([("999999".to_string(), 2)]),
+            report.missing_artwork
+        );
+    }
+
+    #[test]
+    fn sanity_read_deck_file() {
+        let single: DeckFile = serde_json::from_str(
+            r#"{"name":"a","author_id":"1","deck_data":{"keypage_id":null,"passive_ids":[],"combat_page_ids":[null,null,null,null,null,null,null,null,"608014"]}}"#,
+        )
+        .unwrap();
+        assert!(
+            matches!(single, DeckFile::Single(x) if x.deck_data.combat_page_ids[8].as_deref() == Some("608014"))
+        );
+
+        let many: DeckFile = serde_json::from_str(
+            r#"[{"name":"a","deck_data":{"keypage_id":"250023","passive_ids":["230018"],"combat_page_ids":[null,null,null,null,null,null,null,null,null]}}]"#,
+        )
+        .unwrap();
+        assert!(matches!(many, DeckFile::Many(x) if x.len() == 1));
+    }
+}
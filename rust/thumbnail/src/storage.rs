@@ -0,0 +1,78 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use aws_sdk_s3::primitives::ByteStream;
+
+// Where artwork is read from and thumbnails are written to. Keys are
+// slash-separated paths relative to the root of the storage, e.g.
+// `deck_thumbnails/ABC.png`
+pub trait ThumbnailStorage {
+    async fn exists(&self, key: &str) -> bool;
+    async fn get(&self, key: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>>;
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+pub struct S3Storage {
+    pub bucket_name: String,
+    pub client: aws_sdk_s3::Client,
+}
+
+impl ThumbnailStorage for S3Storage {
+    async fn exists(&self, key: &str) -> bool {
+        self.client
+            .head_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .send()
+            .await
+            .is_ok()
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        let response = self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .send()
+            .await?;
+        Ok(response.body.collect().await?.to_vec())
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .body(ByteStream::from(data))
+            .content_type("image/png")
+            .send()
+            .await?;
+        Ok(())
+    }
+}
+
+// Mirrors the bucket layout in a local directory, e.g. a folder of extracted artwork
+pub struct LocalStorage {
+    pub root: PathBuf,
+}
+
+impl ThumbnailStorage for LocalStorage {
+    async fn exists(&self, key: &str) -> bool {
+        tokio::fs::try_exists(self.root.join(key))
+            .await
+            .unwrap_or(false)
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        Ok(tokio::fs::read(self.root.join(key)).await?)
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        Ok(tokio::fs::write(path, data).await?)
+    }
+}